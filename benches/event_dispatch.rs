@@ -0,0 +1,103 @@
+//! Compares the dispatching of input events by kind and code with the former fan-out, where every
+//! relation observed the property `event` of the input device and filtered the events itself.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use inexor_rgf_core_di::{profiles, Provider};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use inexor_rgf_plugin_input_device::api::InputDeviceEventDispatcher;
+use inexor_rgf_plugin_input_device::backend::DeviceHandle;
+use inexor_rgf_plugin_input_device::behaviour::event_payload::{
+    INPUT_EVENT_KIND, INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_TIMESTAMP, INPUT_EVENT_VALUE, KEY_EVENT_KEY_CODE,
+};
+
+/// The number of keys of a full keyboard and of a keyboard with many additional keys.
+const NUMBER_OF_KEYS: [i64; 3] = [100, 250, 500];
+
+fn key_event(key_code: i64) -> Value {
+    json!({
+        INPUT_EVENT_KIND: INPUT_EVENT_KIND_KEY_EVENT,
+        KEY_EVENT_KEY_CODE: key_code,
+        INPUT_EVENT_VALUE: 1,
+        INPUT_EVENT_TIMESTAMP: 0
+    })
+}
+
+/// Every key observes all events and compares the kind and the key code.
+fn fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fan_out");
+    for number_of_keys in NUMBER_OF_KEYS {
+        let state = Arc::new(AtomicI64::new(0));
+        let observers: Vec<Box<dyn Fn(&Value)>> = (0..number_of_keys)
+            .map(|key_code| {
+                let state = state.clone();
+                let observer: Box<dyn Fn(&Value)> = Box::new(move |v: &Value| {
+                    let event = v.clone();
+                    if !event.is_object() {
+                        return;
+                    }
+                    let input_event_kind = event.get(INPUT_EVENT_KIND);
+                    if input_event_kind.is_none() {
+                        return;
+                    }
+                    match input_event_kind.unwrap().as_str().unwrap() {
+                        INPUT_EVENT_KIND_KEY_EVENT => {
+                            let event_key_code = event.get(KEY_EVENT_KEY_CODE).unwrap().as_i64().unwrap_or(-1);
+                            if key_code == event_key_code {
+                                state.store(event.get(INPUT_EVENT_VALUE).unwrap().as_i64().unwrap(), Ordering::Relaxed);
+                            }
+                        }
+                        _ => {}
+                    }
+                });
+                observer
+            })
+            .collect();
+        let event = key_event(number_of_keys / 2);
+        group.bench_with_input(BenchmarkId::from_parameter(number_of_keys), &event, |b, event| {
+            b.iter(|| {
+                for observer in observers.iter() {
+                    observer(black_box(event));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The event is dispatched to the only key with the key code of the event.
+fn dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+    for number_of_keys in NUMBER_OF_KEYS {
+        let mut container = inexor_rgf_plugin_input_device::get::<profiles::Default>();
+        let dispatcher = Provider::<dyn InputDeviceEventDispatcher>::create(&mut container);
+        let input_device_id = Uuid::new_v4();
+        let state = Arc::new(AtomicI64::new(0));
+        for key_code in 0..number_of_keys {
+            let state = state.clone();
+            dispatcher.register(
+                input_device_id,
+                INPUT_EVENT_KIND_KEY_EVENT,
+                key_code,
+                key_code as u128,
+                Arc::new(move |event: &Value| {
+                    state.store(event.get(INPUT_EVENT_VALUE).unwrap().as_i64().unwrap(), Ordering::Relaxed);
+                }),
+            );
+        }
+        // The handle is only passed to the fallback, which isn't registered
+        let device: DeviceHandle = Arc::new(Mutex::new(None));
+        let event = key_event(number_of_keys / 2);
+        group.bench_with_input(BenchmarkId::from_parameter(number_of_keys), &event, |b, event| {
+            b.iter(|| dispatcher.dispatch(input_device_id, black_box(event), &device))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fan_out, dispatch);
+criterion_main!(benches);
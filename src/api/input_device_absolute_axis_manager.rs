@@ -1,6 +1,7 @@
+use crate::backend::{AbsoluteAxisInfo, InputBackendDevice};
 use crate::model::ReactiveEntityInstance;
 use async_trait::async_trait;
-use evdev::{AbsoluteAxisType, Device};
+use evdev::AbsoluteAxisType;
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use inexor_rgf_core_plugins::PluginContext;
@@ -13,19 +14,20 @@ pub const INPUT_DEVICE_ABSOLUTE_AXIS: &'static str = "input_device_absolute_axis
 pub trait InputDeviceAbsoluteAxisManager: Send + Sync {
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    fn create_input_device_absolute_axes(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>);
+    fn create_input_device_absolute_axes(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>);
 
-    fn create_input_device_absolute_axis(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType);
+    fn create_input_device_absolute_axis(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType);
 
     fn create_any_device_absolute_axis(&self, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType);
 
     fn create_entity_instance(
         &self,
         uuid: Uuid,
-        device_name: String,
+        device_label: String,
         unique_name: String,
         absolute_axis_name: String,
         absolute_axis: AbsoluteAxisType,
+        absolute_axis_info: AbsoluteAxisInfo,
     ) -> EntityInstance;
 
     fn try_create_absolute_axis_event(
@@ -33,7 +35,17 @@ pub trait InputDeviceAbsoluteAxisManager: Send + Sync {
         input_device: Arc<ReactiveEntityInstance>,
         input_device_absolute_axis: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_absolute_axis: bool,
     );
 
-    fn create_absolute_axis_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_absolute_axis: Arc<ReactiveEntityInstance>);
+    fn create_absolute_axis_event(
+        &self,
+        input_device: Arc<ReactiveEntityInstance>,
+        input_device_absolute_axis: Arc<ReactiveEntityInstance>,
+        create_send_absolute_axis: bool,
+    );
+
+    /// Removes the absolute axes of the input device with the given id and their relations to the input device. The absolute axes
+    /// of the pseudo device any-device are removed as soon as no input device provides them anymore.
+    fn remove_input_device_absolute_axes(&self, input_device_id: Uuid);
 }
@@ -0,0 +1,16 @@
+use crate::backend::InputBackend;
+use crate::config::InputDevicesConfig;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait InputDeviceBackendManager: Send + Sync {
+    /// Configures the default backend unless a backend has been set explicitly.
+    fn configure(&self, config: &InputDevicesConfig);
+
+    /// Returns the backend which is used to access the input devices.
+    fn get_backend(&self) -> Arc<dyn InputBackend>;
+
+    /// Replaces the backend, for example with a simulated backend.
+    fn set_backend(&self, backend: Arc<dyn InputBackend>);
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::backend::{DeviceHandle, InputBackendDevice};
+
+/// Handles the payload of an input event which has been dispatched to an entity.
+pub type InputEventHandler = Arc<dyn Fn(&Value) + Send + Sync>;
+
+/// Creates the entity of the given kind and code on demand. The managers read the name, the state
+/// and the ranges from the given input device.
+pub type InputEntityFactory = Arc<dyn Fn(&str, i64, &dyn InputBackendDevice) + Send + Sync>;
+
+/// Dispatches the input events of an input device to the entities of the keys, LEDs, axes and
+/// switches. Instead of every entity observing the property `event` of the input device, the
+/// handlers are looked up by the kind and the code of the event.
+#[async_trait]
+pub trait InputDeviceEventDispatcher: Send + Sync {
+    /// Registers a handler for the events of the given kind and code of the given input device.
+    /// An existing handler with the same handle id is replaced.
+    fn register(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, handle_id: u128, handler: InputEventHandler);
+
+    /// Removes the handler with the given handle id.
+    fn unregister(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, handle_id: u128);
+
+    /// Calls the handlers which are registered for the kind and the code of the given payload of
+    /// the property `event`. The handle of the input device is passed to the fallback.
+    fn dispatch(&self, input_device_id: Uuid, event: &Value, device: &DeviceHandle);
+
+    /// Calls the fallback for the given kind and code of the given input device unless a handler
+    /// is registered already. Returns true, if a handler is registered afterwards.
+    fn materialize(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, device: &DeviceHandle) -> bool;
+
+    /// Returns true, if a handler is registered for the given kind and code of the given input device.
+    fn has_handlers(&self, input_device_id: Uuid, input_event_kind: &str, code: i64) -> bool;
+
+    /// Registers a factory which is called if no handler is registered for the kind and the code
+    /// of an event of the given input device. The handlers which are registered by the fallback
+    /// are called with the same event afterwards.
+    fn register_fallback(&self, input_device_id: Uuid, fallback: InputEntityFactory);
+
+    /// Removes the fallback handler of the given input device.
+    fn unregister_fallback(&self, input_device_id: Uuid);
+
+    /// Returns the number of handlers of the given input device.
+    fn count_handlers(&self, input_device_id: Uuid) -> usize;
+}
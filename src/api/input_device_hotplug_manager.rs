@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait InputDeviceHotplugManager: Send + Sync {
+    /// Starts watching for device nodes which appear or disappear.
+    fn start(&self);
+
+    /// Stops watching for device nodes.
+    fn stop(&self);
+}
@@ -1,6 +1,7 @@
+use crate::backend::InputBackendDevice;
 use crate::model::ReactiveEntityInstance;
 use async_trait::async_trait;
-use evdev::{Device, Key};
+use evdev::{AttributeSet, Key};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use inexor_rgf_core_plugins::PluginContext;
@@ -13,13 +14,14 @@ pub const INPUT_DEVICE_KEY: &'static str = "input_device_key";
 pub trait InputDeviceKeyManager: Send + Sync {
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    fn create_input_device_keys(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>);
+    fn create_input_device_keys(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>);
 
-    fn create_input_device_key(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, key: Key);
+    /// Creates the key of the input device. The state of all keys is read once by the caller, if it is available.
+    fn create_input_device_key(&self, input_device: Arc<ReactiveEntityInstance>, key: Key, key_state: Option<&AttributeSet<Key>>);
 
     fn create_any_device_key(&self, input_device: Arc<ReactiveEntityInstance>, key: Key);
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, key_name: String, key: Key) -> EntityInstance;
+    fn create_entity_instance(&self, uuid: Uuid, device_label: String, unique_name: String, key_name: String, key: Key, key_down: bool) -> EntityInstance;
 
     fn try_create_key_event(
         &self,
@@ -30,4 +32,8 @@ pub trait InputDeviceKeyManager: Send + Sync {
     );
 
     fn create_key_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_key: Arc<ReactiveEntityInstance>, create_send_key: bool);
+
+    /// Removes the keys of the input device with the given id and their relations to the input device. The keys
+    /// of the pseudo device any-device are removed as soon as no input device provides them anymore.
+    fn remove_input_device_keys(&self, input_device_id: Uuid);
 }
@@ -1,6 +1,7 @@
+use crate::backend::InputBackendDevice;
 use crate::model::ReactiveEntityInstance;
 use async_trait::async_trait;
-use evdev::{Device, LedType};
+use evdev::{AttributeSet, LedType};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use inexor_rgf_core_plugins::PluginContext;
@@ -13,13 +14,14 @@ pub const INPUT_DEVICE_LED: &'static str = "input_device_led";
 pub trait InputDeviceLedManager: Send + Sync {
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    fn create_input_device_leds(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>);
+    fn create_input_device_leds(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>);
 
-    fn create_input_device_led(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, led: LedType);
+    /// Creates the LED of the input device, which is on if it is contained in the given LED state.
+    fn create_input_device_led(&self, input_device: Arc<ReactiveEntityInstance>, led: LedType, led_state: Option<&AttributeSet<LedType>>);
 
     fn create_any_device_led(&self, input_device: Arc<ReactiveEntityInstance>, led: LedType);
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, key_name: String, led: LedType) -> EntityInstance;
+    fn create_entity_instance(&self, uuid: Uuid, device_label: String, unique_name: String, key_name: String, led: LedType, state: bool) -> EntityInstance;
 
     fn try_create_led_event(
         &self,
@@ -30,4 +32,8 @@ pub trait InputDeviceLedManager: Send + Sync {
     );
 
     fn create_led_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_led: Arc<ReactiveEntityInstance>, create_send_led: bool);
+
+    /// Removes the LEDs of the input device with the given id and their relations to the input device. The LEDs
+    /// of the pseudo device any-device are removed as soon as no input device provides them anymore.
+    fn remove_input_device_leds(&self, input_device_id: Uuid);
 }
@@ -1,33 +1,56 @@
-use crate::config::InputDeviceConfig;
+use crate::backend::InputBackendDevice;
+use crate::config::{InputDeviceConfig, InputDeviceOptions, InputDevicesConfig};
 use async_trait::async_trait;
-use evdev::Device;
 use inexor_rgf_core_plugins::PluginContext;
+use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
 pub static NAMESPACE_INPUT_DEVICE: Uuid = Uuid::from_u128(0x6ba7b8109dad11d180b400c04fd530c7);
 
+/// The name of the pseudo device which aggregates the keys, LEDs, axes and switches of all input devices.
+pub const ANY_DEVICE: &'static str = "any-device";
+
+/// The label of the pseudo device any-device.
+pub const ANY_DEVICE_LABEL: &'static str = "/org/inexor/input/any_device";
+
 #[async_trait]
 pub trait InputDeviceManager: Send + Sync {
     fn init(&self);
 
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    /// Load list of input devices from TOML
+    /// Load list of input devices from TOML. If the configuration is loaded again, the input
+    /// devices which are not configured anymore or have been deactivated are removed.
     fn load_config(&self);
 
+    /// Applies the given configuration. Creates the input devices which are detected or configured
+    /// and removes the input devices which are not configured anymore or have been deactivated.
+    /// If reconnect is enabled, unplugged input devices which are still configured are kept.
+    fn apply_config(&self, config: InputDevicesConfig);
+
+    /// Returns the loaded configuration.
+    fn get_config(&self) -> Option<InputDevicesConfig>;
+
     fn autodetect_input_devices(&self);
 
     fn load_input_devices(&self, input_devices: Vec<InputDeviceConfig>);
 
-    /// Creates an reactive entity instance for the given input device.
-    fn create_input_device(
-        &self,
-        device: &Device,
-        autodetect_keys: bool,
-        autodetect_leds: bool,
-        autodetect_relative_axes: bool,
-        autodetect_absolute_axes: bool,
-        autodetect_switches: bool,
-    );
+    /// Creates an reactive entity instance for the given input device. The options determine which
+    /// keys, LEDs, axes and switches are detected and how the input device is managed.
+    fn create_input_device(&self, device: &dyn InputBackendDevice, options: &InputDeviceOptions);
+
+    /// Called if a device node or a persistent symlink to a device node has been appeared. Creates
+    /// the input device if it should be managed. Input devices which are configured by their
+    /// symlink are matched as soon as udev has created the symlink.
+    fn add_device_node(&self, device_node: &Path);
+
+    /// Called if a device node has been disappeared. Removes the input device of the device node.
+    fn remove_device_node(&self, device_node: &Path);
+
+    /// Removes the input device with the given id including its keys, LEDs, axes, switches and relations.
+    fn remove_input_device(&self, id: Uuid);
+
+    /// Removes all input devices. Called if the plugin is unloaded.
+    fn remove_input_devices(&self);
 }
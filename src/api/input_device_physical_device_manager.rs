@@ -0,0 +1,33 @@
+use crate::backend::InputBackendDevice;
+use crate::model::ReactiveEntityInstance;
+use async_trait::async_trait;
+use inexor_rgf_core_plugins::PluginContext;
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const PHYSICAL_DEVICE: &'static str = "physical_device";
+
+pub const HAS_INTERFACE: &'static str = "has_interface";
+
+/// Groups the input devices (interfaces) of the same hardware into a physical device. For
+/// example, a gaming keyboard provides the keys and the LEDs on different interfaces.
+#[async_trait]
+pub trait InputDevicePhysicalDeviceManager: Send + Sync {
+    fn set_context(&self, context: Arc<dyn PluginContext>);
+
+    /// Adds the input device to its physical device. The physical device is created if it
+    /// doesn't exist yet.
+    fn add_interface(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>);
+
+    /// Removes the input device with the given id from its physical device. The physical device
+    /// is removed as soon as it has no interfaces anymore.
+    fn remove_interface(&self, input_device_id: Uuid);
+
+    /// Returns the id of the physical device of the input device with the given id.
+    fn get_physical_device(&self, input_device_id: Uuid) -> Option<Uuid>;
+
+    /// Forwards the query of a key, LED, axis or switch to the interfaces of the physical device
+    /// which support it. Returns false if none of the interfaces supports it.
+    fn query(&self, id: Uuid, input_event_kind: &str, code: i64, query: &Value) -> bool;
+}
@@ -0,0 +1,23 @@
+use crate::behaviour::error::InputDeviceError;
+use async_trait::async_trait;
+use inexor_rgf_core_plugins::PluginContext;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const INPUT_DEVICE_PROBLEM: &'static str = "input_device_problem";
+
+/// Keeps track of the device nodes which can't be used as input devices. Each problem is
+/// published as an entity instance, so that users can be told how to solve it.
+#[async_trait]
+pub trait InputDeviceProblemManager: Send + Sync {
+    fn set_context(&self, context: Arc<dyn PluginContext>);
+
+    /// Creates or updates the problem of the given device node.
+    fn report_problem(&self, device_node: &Path, error: &InputDeviceError);
+
+    /// Removes the problem of the given device node, if any.
+    fn resolve_problem(&self, device_node: &Path);
+
+    /// Removes the problems of all device nodes.
+    fn resolve_problems(&self);
+}
@@ -1,6 +1,7 @@
+use crate::backend::InputBackendDevice;
 use crate::model::ReactiveEntityInstance;
 use async_trait::async_trait;
-use evdev::{Device, RelativeAxisType};
+use evdev::RelativeAxisType;
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use inexor_rgf_core_plugins::PluginContext;
@@ -13,16 +14,16 @@ pub const INPUT_DEVICE_RELATIVE_AXIS: &'static str = "input_device_relative_axis
 pub trait InputDeviceRelativeAxisManager: Send + Sync {
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    fn create_input_device_relative_axes(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>);
+    fn create_input_device_relative_axes(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>);
 
-    fn create_input_device_relative_axis(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType);
+    fn create_input_device_relative_axis(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType);
 
     fn create_any_device_relative_axis(&self, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType);
 
     fn create_entity_instance(
         &self,
         uuid: Uuid,
-        device_name: String,
+        device_label: String,
         unique_name: String,
         relative_axis_name: String,
         relative_axis: RelativeAxisType,
@@ -33,7 +34,17 @@ pub trait InputDeviceRelativeAxisManager: Send + Sync {
         input_device: Arc<ReactiveEntityInstance>,
         input_device_relative_axis: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_relative_axis: bool,
     );
 
-    fn create_relative_axis_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_relative_axis: Arc<ReactiveEntityInstance>);
+    fn create_relative_axis_event(
+        &self,
+        input_device: Arc<ReactiveEntityInstance>,
+        input_device_relative_axis: Arc<ReactiveEntityInstance>,
+        create_send_relative_axis: bool,
+    );
+
+    /// Removes the relative axes of the input device with the given id and their relations to the input device. The relative axes
+    /// of the pseudo device any-device are removed as soon as no input device provides them anymore.
+    fn remove_input_device_relative_axes(&self, input_device_id: Uuid);
 }
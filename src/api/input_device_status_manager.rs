@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use inexor_rgf_core_plugins::PluginContext;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const INPUT_DEVICE_PLUGIN: &'static str = "input_device_plugin";
+
+/// Collects statistics about the input devices and publishes them on the status entity of the
+/// plugin, so that the health of the plugin can be queried from the graph.
+#[async_trait]
+pub trait InputDeviceStatusManager: Send + Sync {
+    fn set_context(&self, context: Arc<dyn PluginContext>);
+
+    /// Creates the status entity and starts publishing the statistics periodically.
+    fn start(&self);
+
+    /// Stops publishing the statistics and removes the status entity.
+    fn stop(&self);
+
+    /// Sets the number of managed input devices.
+    fn set_managed_devices(&self, managed_devices: usize);
+
+    /// Counts an event of the given kind which has been received from an input device.
+    fn event_received(&self, kind: &str, timestamp: u64);
+
+    /// Counts events which have been sent to an input device or to a virtual device.
+    fn events_sent(&self, count: usize);
+
+    /// Counts a send request which has been dropped because it was invalid or couldn't be written.
+    fn send_request_dropped(&self);
+
+    /// Called if the reader of the input device with the given id has been started. Starting the
+    /// reader of the same input device again counts as restart.
+    fn reader_started(&self, input_device_id: Uuid);
+}
@@ -1,6 +1,7 @@
+use crate::backend::InputBackendDevice;
 use crate::model::ReactiveEntityInstance;
 use async_trait::async_trait;
-use evdev::{Device, SwitchType};
+use evdev::{AttributeSet, SwitchType};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use inexor_rgf_core_plugins::PluginContext;
@@ -13,20 +14,34 @@ pub const INPUT_DEVICE_SWITCH: &'static str = "input_device_switch";
 pub trait InputDeviceSwitchManager: Send + Sync {
     fn set_context(&self, context: Arc<dyn PluginContext>);
 
-    fn create_input_device_switches(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>);
+    fn create_input_device_switches(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>);
 
-    fn create_input_device_switch(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType);
+    /// Creates the switch of the input device. Its initial state is taken from the switch state of the input device.
+    fn create_input_device_switch(&self, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType, switch_state: Option<&AttributeSet<SwitchType>>);
 
     fn create_any_device_switch(&self, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType);
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, switch_name: String, switch: SwitchType) -> EntityInstance;
+    fn create_entity_instance(
+        &self,
+        uuid: Uuid,
+        device_label: String,
+        unique_name: String,
+        switch_name: String,
+        switch: SwitchType,
+        state: bool,
+    ) -> EntityInstance;
 
     fn try_create_switch_event(
         &self,
         input_device: Arc<ReactiveEntityInstance>,
         input_device_switch: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_switch: bool,
     );
 
-    fn create_switch_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_switch: Arc<ReactiveEntityInstance>);
+    fn create_switch_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_switch: Arc<ReactiveEntityInstance>, create_send_switch: bool);
+
+    /// Removes the switches of the input device with the given id and their relations to the input device. The switches
+    /// of the pseudo device any-device are removed as soon as no input device provides them anymore.
+    fn remove_input_device_switches(&self, input_device_id: Uuid);
 }
@@ -0,0 +1,39 @@
+use crate::backend::InputBackendDevice;
+use crate::model::ReactiveEntityInstance;
+use async_trait::async_trait;
+use evdev::InputEvent;
+use inexor_rgf_core_plugins::PluginContext;
+use std::io;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const VIRTUAL_DEVICE: &'static str = "virtual_device";
+
+pub const HAS_VIRTUAL_DEVICE: &'static str = "has_virtual_device";
+
+/// The suffix of the names of the virtual devices. Input devices with this suffix are created by
+/// the plugin itself and are therefore never managed as input devices.
+pub const VIRTUAL_DEVICE_NAME_SUFFIX: &'static str = " (Virtual)";
+
+#[async_trait]
+pub trait InputDeviceVirtualDeviceManager: Send + Sync {
+    fn set_context(&self, context: Arc<dyn PluginContext>);
+
+    /// Creates a virtual device which mirrors the capabilities of the given input device.
+    fn create_virtual_device(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>);
+
+    /// Returns true, if the given input device is a virtual device created by the plugin.
+    fn is_virtual_device(&self, device: &dyn InputBackendDevice) -> bool;
+
+    /// Returns true, if the input device with the given id has a virtual device.
+    fn has_virtual_device(&self, input_device_id: Uuid) -> bool;
+
+    /// Emits the given events on the virtual device of the input device with the given id.
+    fn send_events(&self, input_device_id: Uuid, events: &[InputEvent]) -> io::Result<()>;
+
+    /// Emits the given events on the virtual device with the given id.
+    fn send_events_by_virtual_device(&self, virtual_device_id: Uuid, events: &[InputEvent]) -> io::Result<()>;
+
+    /// Removes the virtual device of the input device with the given id.
+    fn remove_virtual_device(&self, input_device_id: Uuid);
+}
@@ -1,13 +1,27 @@
 pub use input_device_absolute_axis_manager::*;
+pub use input_device_backend_manager::*;
+pub use input_device_event_dispatcher::*;
+pub use input_device_hotplug_manager::*;
 pub use input_device_key_manager::*;
 pub use input_device_led_manager::*;
 pub use input_device_manager::*;
+pub use input_device_physical_device_manager::*;
+pub use input_device_problem_manager::*;
 pub use input_device_relative_axis_manager::*;
+pub use input_device_status_manager::*;
 pub use input_device_switch_manager::*;
+pub use input_device_virtual_device_manager::*;
 
 pub mod input_device_absolute_axis_manager;
+pub mod input_device_backend_manager;
+pub mod input_device_event_dispatcher;
+pub mod input_device_hotplug_manager;
 pub mod input_device_key_manager;
 pub mod input_device_led_manager;
 pub mod input_device_manager;
+pub mod input_device_physical_device_manager;
+pub mod input_device_problem_manager;
 pub mod input_device_relative_axis_manager;
+pub mod input_device_status_manager;
 pub mod input_device_switch_manager;
+pub mod input_device_virtual_device_manager;
@@ -0,0 +1,212 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use evdev::{
+    AbsoluteAxisType, AttributeSet, AttributeSetRef, Device, EventStream, EventType, FFEffectType, InputEvent, InputId, Key, LedType, PropType,
+    RelativeAxisType, SoundType, SwitchType,
+};
+use futures::Stream;
+
+use crate::backend::uinput::UinputVirtualDevice;
+use crate::backend::{AbsoluteAxisInfo, InputBackend, InputBackendDevice, InputBackendEventStream, InputBackendVirtualDevice, InputEventStream};
+use crate::hotplug::{by_id_path_of, by_path_path_of, list_device_nodes};
+use crate::identity::sysfs_path_of;
+
+/// Accesses the input devices using the evdev interface of the linux kernel.
+pub struct EvdevBackend {
+    /// The directory which contains the device nodes, usually /dev/input
+    input_device_path: PathBuf,
+}
+
+impl EvdevBackend {
+    pub fn new<P: AsRef<Path>>(input_device_path: P) -> EvdevBackend {
+        EvdevBackend {
+            input_device_path: input_device_path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl InputBackend for EvdevBackend {
+    fn enumerate(&self) -> Vec<Box<dyn InputBackendDevice>> {
+        list_device_nodes(&self.input_device_path)
+            .iter()
+            .filter_map(|device_node| self.open(device_node).ok())
+            .collect()
+    }
+
+    fn device_nodes(&self) -> Vec<PathBuf> {
+        list_device_nodes(&self.input_device_path)
+    }
+
+    fn open(&self, device_node: &Path) -> io::Result<Box<dyn InputBackendDevice>> {
+        let device = Device::open(device_node)?;
+        Ok(Box::new(EvdevDevice {
+            device_node: device_node.to_path_buf(),
+            device,
+        }))
+    }
+
+    fn create_virtual_device(&self, name: &str, device: &dyn InputBackendDevice) -> io::Result<Box<dyn InputBackendVirtualDevice>> {
+        Ok(Box::new(UinputVirtualDevice::create(name, device)?))
+    }
+}
+
+pub struct EvdevDevice {
+    device_node: PathBuf,
+
+    device: Device,
+}
+
+impl InputBackendDevice for EvdevDevice {
+    fn device_node(&self) -> &Path {
+        self.device_node.as_path()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.device.name()
+    }
+
+    fn physical_path(&self) -> Option<&str> {
+        self.device.physical_path()
+    }
+
+    fn unique_id(&self) -> Option<&str> {
+        self.device.unique_name()
+    }
+
+    fn by_id(&self) -> Option<PathBuf> {
+        by_id_path_of(self.device_node.as_path())
+    }
+
+    fn by_path(&self) -> Option<PathBuf> {
+        by_path_path_of(self.device_node.as_path())
+    }
+
+    fn sysfs_path(&self) -> Option<PathBuf> {
+        sysfs_path_of(self.device_node.as_path())
+    }
+
+    fn input_id(&self) -> InputId {
+        self.device.input_id()
+    }
+
+    fn driver_version(&self) -> (u8, u8, u8) {
+        self.device.driver_version()
+    }
+
+    fn input_properties(&self) -> &AttributeSetRef<PropType> {
+        self.device.properties()
+    }
+
+    fn supported_events(&self) -> &AttributeSetRef<EventType> {
+        self.device.supported_events()
+    }
+
+    fn supported_keys(&self) -> Option<&AttributeSetRef<Key>> {
+        self.device.supported_keys()
+    }
+
+    fn supported_leds(&self) -> Option<&AttributeSetRef<LedType>> {
+        self.device.supported_leds()
+    }
+
+    fn supported_relative_axes(&self) -> Option<&AttributeSetRef<RelativeAxisType>> {
+        self.device.supported_relative_axes()
+    }
+
+    fn supported_absolute_axes(&self) -> Option<&AttributeSetRef<AbsoluteAxisType>> {
+        self.device.supported_absolute_axes()
+    }
+
+    fn supported_switches(&self) -> Option<&AttributeSetRef<SwitchType>> {
+        self.device.supported_switches()
+    }
+
+    fn supported_force_feedback_effects(&self) -> Option<&AttributeSetRef<FFEffectType>> {
+        self.device.supported_ff()
+    }
+
+    fn supported_sounds(&self) -> Option<&AttributeSetRef<SoundType>> {
+        self.device.supported_sounds()
+    }
+
+    fn key_state(&self) -> io::Result<AttributeSet<Key>> {
+        self.device.get_key_state()
+    }
+
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>> {
+        self.device.get_led_state()
+    }
+
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>> {
+        self.device.get_switch_state()
+    }
+
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo> {
+        absolute_axis_info_of(&self.device, absolute_axis)
+    }
+
+    fn send_events(&mut self, events: &[InputEvent]) -> io::Result<()> {
+        self.device.send_events(events)
+    }
+
+    fn into_event_stream(self: Box<Self>) -> io::Result<InputEventStream> {
+        let event_stream = self.device.into_event_stream()?;
+        Ok(Box::new(EvdevEventStream { event_stream }))
+    }
+}
+
+pub struct EvdevEventStream {
+    event_stream: EventStream,
+}
+
+impl Stream for EvdevEventStream {
+    type Item = io::Result<InputEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.event_stream).poll_next(cx)
+    }
+}
+
+impl InputBackendEventStream for EvdevEventStream {
+    fn grab(&mut self) -> io::Result<()> {
+        self.event_stream.device_mut().grab()
+    }
+
+    fn ungrab(&mut self) -> io::Result<()> {
+        self.event_stream.device_mut().ungrab()
+    }
+
+    fn key_state(&self) -> io::Result<AttributeSet<Key>> {
+        self.event_stream.device().get_key_state()
+    }
+
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>> {
+        self.event_stream.device().get_led_state()
+    }
+
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>> {
+        self.event_stream.device().get_switch_state()
+    }
+
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo> {
+        absolute_axis_info_of(self.event_stream.device(), absolute_axis)
+    }
+}
+
+fn absolute_axis_info_of(device: &Device, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo> {
+    let abs_state = device.get_abs_state()?;
+    match abs_state.get(absolute_axis.0 as usize) {
+        Some(absinfo) => Ok(AbsoluteAxisInfo {
+            value: absinfo.value,
+            minimum: absinfo.minimum,
+            maximum: absinfo.maximum,
+            fuzz: absinfo.fuzz,
+            flat: absinfo.flat,
+            resolution: absinfo.resolution,
+        }),
+        None => Err(io::Error::from(io::ErrorKind::InvalidInput)),
+    }
+}
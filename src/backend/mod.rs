@@ -0,0 +1,168 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use evdev::{
+    AbsoluteAxisType, AttributeSet, AttributeSetRef, EventType, FFEffectType, InputEvent, InputId, Key, LedType, PropType, RelativeAxisType, SoundType,
+    SwitchType,
+};
+use futures::Stream;
+
+pub use evdev_backend::*;
+pub use simulated_backend::*;
+
+pub mod evdev_backend;
+pub mod simulated_backend;
+mod uinput;
+
+/// The stream of input events of an input device.
+pub type InputEventStream = Box<dyn InputBackendEventStream>;
+
+/// The handle which is used to write events to an input device. The handle is shared between
+/// the sender and the reader, which closes the handle if the input device has been disconnected.
+pub type DeviceHandle = Arc<Mutex<Option<Box<dyn InputBackendDevice>>>>;
+
+/// The input events of an opened input device. The stream owns the device, so the device can
+/// still be grabbed or released and its state can be queried while the events are read.
+pub trait InputBackendEventStream: Stream<Item = io::Result<InputEvent>> + Send + Unpin {
+    /// Grabs the input device exclusively (EVIOCGRAB). Other clients won't receive the events
+    /// of the device anymore.
+    fn grab(&mut self) -> io::Result<()>;
+
+    /// Releases the exclusive grab of the input device.
+    fn ungrab(&mut self) -> io::Result<()>;
+
+    /// Returns the keys which are currently pressed.
+    fn key_state(&self) -> io::Result<AttributeSet<Key>>;
+
+    /// Returns the LEDs which are currently lit.
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>>;
+
+    /// Returns the switches which are currently active.
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>>;
+
+    /// Returns the current value and the range of the given absolute axis.
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo>;
+}
+
+/// The state and the range of an absolute axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AbsoluteAxisInfo {
+    pub value: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub fuzz: i32,
+    pub flat: i32,
+    pub resolution: i32,
+}
+
+impl AbsoluteAxisInfo {
+    /// Maps the given value into the range of the axis. Axes with a negative minimum (for example
+    /// joysticks) are mapped to -1..1, all other axes are mapped to 0..1.
+    pub fn normalize(&self, value: i32) -> f64 {
+        if self.maximum <= self.minimum {
+            return 0.0;
+        }
+        let value = value.clamp(self.minimum, self.maximum);
+        let normalized = (value as f64 - self.minimum as f64) / (self.maximum as f64 - self.minimum as f64);
+        if self.minimum < 0 {
+            normalized * 2.0 - 1.0
+        } else {
+            normalized
+        }
+    }
+}
+
+/// Provides access to the input devices of the system.
+pub trait InputBackend: Send + Sync {
+    /// Returns all input devices which are currently available.
+    fn enumerate(&self) -> Vec<Box<dyn InputBackendDevice>>;
+
+    /// Returns the device nodes of all input devices, including the device nodes which can't be
+    /// opened, for example because of missing permissions.
+    fn device_nodes(&self) -> Vec<PathBuf>;
+
+    /// Opens the input device with the given device node.
+    fn open(&self, device_node: &Path) -> io::Result<Box<dyn InputBackendDevice>>;
+
+    /// Creates a virtual input device (uinput) with the given name which mirrors the keys, LEDs,
+    /// axes, switches, sounds and properties of the given input device.
+    fn create_virtual_device(&self, name: &str, device: &dyn InputBackendDevice) -> io::Result<Box<dyn InputBackendVirtualDevice>>;
+}
+
+/// An opened input device.
+pub trait InputBackendDevice: Send {
+    /// The device node, for example /dev/input/event3.
+    fn device_node(&self) -> &Path;
+
+    fn name(&self) -> Option<&str>;
+
+    fn physical_path(&self) -> Option<&str>;
+
+    /// The unique id of the input device (uniq), for example a serial number or a bluetooth
+    /// address. Many input devices don't provide a unique id.
+    fn unique_id(&self) -> Option<&str>;
+
+    /// The persistent symlink in by-id which points to the device node, if any.
+    fn by_id(&self) -> Option<PathBuf>;
+
+    /// The persistent symlink in by-path which points to the device node, if any.
+    fn by_path(&self) -> Option<PathBuf>;
+
+    /// The path of the input device in the sysfs, if any.
+    fn sysfs_path(&self) -> Option<PathBuf>;
+
+    fn input_id(&self) -> InputId;
+
+    fn driver_version(&self) -> (u8, u8, u8);
+
+    /// The properties of the input device (INPUT_PROP_*), for example whether the input device
+    /// is a touchscreen (direct) or a touchpad (pointer, buttonpad).
+    fn input_properties(&self) -> &AttributeSetRef<PropType>;
+
+    /// The event types which are supported by the input device, for example KEY and LED.
+    fn supported_events(&self) -> &AttributeSetRef<EventType>;
+
+    fn supported_keys(&self) -> Option<&AttributeSetRef<Key>>;
+
+    fn supported_leds(&self) -> Option<&AttributeSetRef<LedType>>;
+
+    fn supported_relative_axes(&self) -> Option<&AttributeSetRef<RelativeAxisType>>;
+
+    fn supported_absolute_axes(&self) -> Option<&AttributeSetRef<AbsoluteAxisType>>;
+
+    fn supported_switches(&self) -> Option<&AttributeSetRef<SwitchType>>;
+
+    /// The force feedback effects which are supported by the input device, for example rumble.
+    fn supported_force_feedback_effects(&self) -> Option<&AttributeSetRef<FFEffectType>>;
+
+    /// The sounds which are supported by the input device, for example the bell of a keyboard.
+    fn supported_sounds(&self) -> Option<&AttributeSetRef<SoundType>>;
+
+    /// Returns the keys which are currently pressed.
+    fn key_state(&self) -> io::Result<AttributeSet<Key>>;
+
+    /// Returns the LEDs which are currently lit.
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>>;
+
+    /// Returns the switches which are currently active.
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>>;
+
+    /// Returns the current value and the range of the given absolute axis.
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo>;
+
+    /// Writes the given events to the input device.
+    fn send_events(&mut self, events: &[InputEvent]) -> io::Result<()>;
+
+    /// Consumes the input device and returns the stream of its input events.
+    fn into_event_stream(self: Box<Self>) -> io::Result<InputEventStream>;
+}
+
+/// A virtual input device. The events which are emitted by a virtual device are processed by
+/// the kernel like the events of a physical input device, so they reach the desktop.
+pub trait InputBackendVirtualDevice: Send {
+    fn name(&self) -> &str;
+
+    /// Emits the given events followed by a SYN_REPORT.
+    fn emit(&mut self, events: &[InputEvent]) -> io::Result<()>;
+}
@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+
+use evdev::{
+    AbsoluteAxisType, AttributeSet, AttributeSetRef, BusType, EventType, FFEffectType, InputEvent, InputId, Key, LedType, PropType, RelativeAxisType,
+    SoundType, SwitchType,
+};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::Stream;
+
+use crate::backend::{AbsoluteAxisInfo, InputBackend, InputBackendDevice, InputBackendEventStream, InputBackendVirtualDevice, InputEventStream};
+
+/// An in-memory input backend. The devices and their events are scripted, so the plugin can be
+/// used without real hardware.
+#[derive(Default)]
+pub struct SimulatedBackend {
+    devices: RwLock<Vec<SimulatedDevice>>,
+
+    virtual_devices: RwLock<Vec<SimulatedVirtualDevice>>,
+}
+
+impl SimulatedBackend {
+    pub fn new() -> SimulatedBackend {
+        SimulatedBackend::default()
+    }
+
+    /// Plugs in the given device.
+    pub fn add_device(&self, device: SimulatedDevice) {
+        self.devices.write().unwrap().push(device);
+    }
+
+    /// Unplugs the device with the given device node. The event streams of the device are closed.
+    pub fn remove_device(&self, device_node: &Path) -> Option<SimulatedDevice> {
+        let mut devices = self.devices.write().unwrap();
+        let index = devices.iter().position(|device| device.device_node == device_node)?;
+        let device = devices.remove(index);
+        device.listeners.lock().unwrap().clear();
+        Some(device)
+    }
+
+    pub fn get_device(&self, device_node: &Path) -> Option<SimulatedDevice> {
+        self.devices.read().unwrap().iter().find(|device| device.device_node == device_node).cloned()
+    }
+
+    /// Returns the virtual device with the given name.
+    pub fn get_virtual_device(&self, name: &str) -> Option<SimulatedVirtualDevice> {
+        self.virtual_devices
+            .read()
+            .unwrap()
+            .iter()
+            .find(|virtual_device| virtual_device.name == name)
+            .cloned()
+    }
+}
+
+impl InputBackend for SimulatedBackend {
+    fn enumerate(&self) -> Vec<Box<dyn InputBackendDevice>> {
+        self.devices
+            .read()
+            .unwrap()
+            .iter()
+            .map(|device| Box::new(device.clone()) as Box<dyn InputBackendDevice>)
+            .collect()
+    }
+
+    fn device_nodes(&self) -> Vec<PathBuf> {
+        self.devices.read().unwrap().iter().map(|device| device.device_node.clone()).collect()
+    }
+
+    fn open(&self, device_node: &Path) -> io::Result<Box<dyn InputBackendDevice>> {
+        match self.get_device(device_node) {
+            Some(device) => Ok(Box::new(device)),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn create_virtual_device(&self, name: &str, _device: &dyn InputBackendDevice) -> io::Result<Box<dyn InputBackendVirtualDevice>> {
+        let virtual_device = SimulatedVirtualDevice {
+            name: name.into(),
+            emitted_events: Arc::new(Mutex::new(Vec::new())),
+        };
+        self.virtual_devices.write().unwrap().push(virtual_device.clone());
+        Ok(Box::new(virtual_device))
+    }
+}
+
+/// A scripted input device. All clones share the same event streams and the same list of sent events.
+#[derive(Clone)]
+pub struct SimulatedDevice {
+    device_node: PathBuf,
+
+    name: String,
+
+    physical_path: String,
+
+    unique_id: String,
+
+    by_id: Option<PathBuf>,
+
+    by_path: Option<PathBuf>,
+
+    input_id: InputId,
+
+    input_properties: AttributeSet<PropType>,
+
+    events: AttributeSet<EventType>,
+
+    keys: AttributeSet<Key>,
+
+    leds: AttributeSet<LedType>,
+
+    relative_axes: AttributeSet<RelativeAxisType>,
+
+    absolute_axes: AttributeSet<AbsoluteAxisType>,
+
+    switches: AttributeSet<SwitchType>,
+
+    force_feedback_effects: AttributeSet<FFEffectType>,
+
+    sounds: AttributeSet<SoundType>,
+
+    state: Arc<Mutex<SimulatedDeviceState>>,
+
+    grabbed: Arc<AtomicBool>,
+
+    listeners: Arc<Mutex<Vec<UnboundedSender<io::Result<InputEvent>>>>>,
+
+    sent_events: Arc<Mutex<Vec<InputEvent>>>,
+}
+
+impl SimulatedDevice {
+    pub fn new<P: Into<PathBuf>>(device_node: P, name: &str, physical_path: &str) -> SimulatedDevice {
+        let mut events = AttributeSet::new();
+        events.insert(EventType::SYNCHRONIZATION);
+        SimulatedDevice {
+            device_node: device_node.into(),
+            name: name.into(),
+            physical_path: physical_path.into(),
+            unique_id: String::new(),
+            by_id: None,
+            by_path: None,
+            input_id: InputId::new(BusType::BUS_VIRTUAL, 0, 0, 0),
+            input_properties: AttributeSet::new(),
+            events,
+            keys: AttributeSet::new(),
+            leds: AttributeSet::new(),
+            relative_axes: AttributeSet::new(),
+            absolute_axes: AttributeSet::new(),
+            switches: AttributeSet::new(),
+            force_feedback_effects: AttributeSet::new(),
+            sounds: AttributeSet::new(),
+            state: Arc::new(Mutex::new(SimulatedDeviceState::default())),
+            grabbed: Arc::new(AtomicBool::new(false)),
+            listeners: Arc::new(Mutex::new(Vec::new())),
+            sent_events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn with_unique_id(mut self, unique_id: &str) -> SimulatedDevice {
+        self.unique_id = unique_id.into();
+        self
+    }
+
+    /// The symlink in by-id. Simulated devices are not looked up in the file system.
+    pub fn with_by_id<P: Into<PathBuf>>(mut self, by_id: P) -> SimulatedDevice {
+        self.by_id = Some(by_id.into());
+        self
+    }
+
+    /// The symlink in by-path. Simulated devices are not looked up in the file system.
+    pub fn with_by_path<P: Into<PathBuf>>(mut self, by_path: P) -> SimulatedDevice {
+        self.by_path = Some(by_path.into());
+        self
+    }
+
+    pub fn with_input_id(mut self, input_id: InputId) -> SimulatedDevice {
+        self.input_id = input_id;
+        self
+    }
+
+    pub fn with_input_properties(mut self, input_properties: &[PropType]) -> SimulatedDevice {
+        input_properties.iter().for_each(|input_property| self.input_properties.insert(*input_property));
+        self
+    }
+
+    pub fn with_keys(mut self, keys: &[Key]) -> SimulatedDevice {
+        keys.iter().for_each(|key| self.keys.insert(*key));
+        self.events.insert(EventType::KEY);
+        self
+    }
+
+    pub fn with_leds(mut self, leds: &[LedType]) -> SimulatedDevice {
+        leds.iter().for_each(|led| self.leds.insert(*led));
+        self.events.insert(EventType::LED);
+        self
+    }
+
+    pub fn with_relative_axes(mut self, relative_axes: &[RelativeAxisType]) -> SimulatedDevice {
+        relative_axes.iter().for_each(|relative_axis| self.relative_axes.insert(*relative_axis));
+        self.events.insert(EventType::RELATIVE);
+        self
+    }
+
+    pub fn with_absolute_axes(mut self, absolute_axes: &[AbsoluteAxisType]) -> SimulatedDevice {
+        absolute_axes.iter().for_each(|absolute_axis| self.absolute_axes.insert(*absolute_axis));
+        self.events.insert(EventType::ABSOLUTE);
+        self
+    }
+
+    pub fn with_absolute_axis_info(mut self, absolute_axis: AbsoluteAxisType, absolute_axis_info: AbsoluteAxisInfo) -> SimulatedDevice {
+        self.absolute_axes.insert(absolute_axis);
+        self.events.insert(EventType::ABSOLUTE);
+        self.state.lock().unwrap().absolute_axes.insert(absolute_axis.0, absolute_axis_info);
+        self
+    }
+
+    pub fn with_switches(mut self, switches: &[SwitchType]) -> SimulatedDevice {
+        switches.iter().for_each(|switch| self.switches.insert(*switch));
+        self.events.insert(EventType::SWITCH);
+        self
+    }
+
+    pub fn with_force_feedback_effects(mut self, force_feedback_effects: &[FFEffectType]) -> SimulatedDevice {
+        force_feedback_effects
+            .iter()
+            .for_each(|force_feedback_effect| self.force_feedback_effects.insert(*force_feedback_effect));
+        self.events.insert(EventType::FORCEFEEDBACK);
+        self
+    }
+
+    pub fn with_sounds(mut self, sounds: &[SoundType]) -> SimulatedDevice {
+        sounds.iter().for_each(|sound| self.sounds.insert(*sound));
+        self.events.insert(EventType::SOUND);
+        self
+    }
+
+    /// Changes the state of the device without emitting an event, for example to simulate
+    /// events which have been dropped.
+    pub fn set_state(&self, event_type: EventType, code: u16, value: i32) {
+        let mut state = self.state.lock().unwrap();
+        match event_type {
+            EventType::KEY => match value {
+                0 => state.keys.remove(Key::new(code)),
+                _ => state.keys.insert(Key::new(code)),
+            },
+            EventType::LED => match value {
+                0 => state.leds.remove(LedType(code)),
+                _ => state.leds.insert(LedType(code)),
+            },
+            EventType::SWITCH => match value {
+                0 => state.switches.remove(SwitchType(code)),
+                _ => state.switches.insert(SwitchType(code)),
+            },
+            EventType::ABSOLUTE => state.absolute_axes.entry(code).or_default().value = value,
+            _ => {}
+        }
+    }
+
+    /// Emits the given event to all event streams of the device.
+    pub fn emit(&self, event_type: EventType, code: u16, value: i32) {
+        self.set_state(event_type, code, value);
+        let event = InputEvent::new_now(event_type, code, value);
+        self.listeners.lock().unwrap().retain(|listener| listener.unbounded_send(Ok(event)).is_ok());
+    }
+
+    /// Returns true, if the device is grabbed exclusively.
+    pub fn is_grabbed(&self) -> bool {
+        self.grabbed.load(Ordering::SeqCst)
+    }
+
+    /// Returns the events which have been written to the device.
+    pub fn sent_events(&self) -> Vec<InputEvent> {
+        self.sent_events.lock().unwrap().clone()
+    }
+}
+
+impl InputBackendDevice for SimulatedDevice {
+    fn device_node(&self) -> &Path {
+        self.device_node.as_path()
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+
+    fn physical_path(&self) -> Option<&str> {
+        Some(self.physical_path.as_str())
+    }
+
+    fn unique_id(&self) -> Option<&str> {
+        Some(self.unique_id.as_str()).filter(|unique_id| !unique_id.is_empty())
+    }
+
+    fn by_id(&self) -> Option<PathBuf> {
+        self.by_id.clone()
+    }
+
+    fn by_path(&self) -> Option<PathBuf> {
+        self.by_path.clone()
+    }
+
+    fn sysfs_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn input_id(&self) -> InputId {
+        self.input_id.clone()
+    }
+
+    fn driver_version(&self) -> (u8, u8, u8) {
+        (1, 0, 1)
+    }
+
+    fn input_properties(&self) -> &AttributeSetRef<PropType> {
+        &self.input_properties
+    }
+
+    fn supported_events(&self) -> &AttributeSetRef<EventType> {
+        &self.events
+    }
+
+    fn supported_keys(&self) -> Option<&AttributeSetRef<Key>> {
+        Some(&self.keys)
+    }
+
+    fn supported_leds(&self) -> Option<&AttributeSetRef<LedType>> {
+        Some(&self.leds)
+    }
+
+    fn supported_relative_axes(&self) -> Option<&AttributeSetRef<RelativeAxisType>> {
+        Some(&self.relative_axes)
+    }
+
+    fn supported_absolute_axes(&self) -> Option<&AttributeSetRef<AbsoluteAxisType>> {
+        Some(&self.absolute_axes)
+    }
+
+    fn supported_switches(&self) -> Option<&AttributeSetRef<SwitchType>> {
+        Some(&self.switches)
+    }
+
+    fn supported_force_feedback_effects(&self) -> Option<&AttributeSetRef<FFEffectType>> {
+        Some(&self.force_feedback_effects)
+    }
+
+    fn supported_sounds(&self) -> Option<&AttributeSetRef<SoundType>> {
+        Some(&self.sounds)
+    }
+
+    fn key_state(&self) -> io::Result<AttributeSet<Key>> {
+        Ok(self.state.lock().unwrap().keys.clone())
+    }
+
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>> {
+        Ok(self.state.lock().unwrap().leds.clone())
+    }
+
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>> {
+        Ok(self.state.lock().unwrap().switches.clone())
+    }
+
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo> {
+        Ok(self.state.lock().unwrap().absolute_axes.get(&absolute_axis.0).cloned().unwrap_or_default())
+    }
+
+    fn send_events(&mut self, events: &[InputEvent]) -> io::Result<()> {
+        self.sent_events.lock().unwrap().extend_from_slice(events);
+        Ok(())
+    }
+
+    fn into_event_stream(self: Box<Self>) -> io::Result<InputEventStream> {
+        let (sender, receiver) = unbounded();
+        self.listeners.lock().unwrap().push(sender);
+        Ok(Box::new(SimulatedEventStream {
+            receiver,
+            state: self.state.clone(),
+            grabbed: self.grabbed.clone(),
+        }))
+    }
+}
+
+pub struct SimulatedEventStream {
+    receiver: UnboundedReceiver<io::Result<InputEvent>>,
+
+    state: Arc<Mutex<SimulatedDeviceState>>,
+
+    grabbed: Arc<AtomicBool>,
+}
+
+impl Stream for SimulatedEventStream {
+    type Item = io::Result<InputEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl InputBackendEventStream for SimulatedEventStream {
+    fn grab(&mut self) -> io::Result<()> {
+        self.grabbed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn ungrab(&mut self) -> io::Result<()> {
+        self.grabbed.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn key_state(&self) -> io::Result<AttributeSet<Key>> {
+        Ok(self.state.lock().unwrap().keys.clone())
+    }
+
+    fn led_state(&self) -> io::Result<AttributeSet<LedType>> {
+        Ok(self.state.lock().unwrap().leds.clone())
+    }
+
+    fn switch_state(&self) -> io::Result<AttributeSet<SwitchType>> {
+        Ok(self.state.lock().unwrap().switches.clone())
+    }
+
+    fn absolute_axis_info(&self, absolute_axis: AbsoluteAxisType) -> io::Result<AbsoluteAxisInfo> {
+        Ok(self.state.lock().unwrap().absolute_axes.get(&absolute_axis.0).cloned().unwrap_or_default())
+    }
+}
+
+/// A virtual device which records the emitted events. All clones share the same list of emitted events.
+#[derive(Clone)]
+pub struct SimulatedVirtualDevice {
+    name: String,
+
+    emitted_events: Arc<Mutex<Vec<InputEvent>>>,
+}
+
+impl SimulatedVirtualDevice {
+    /// Returns the events which have been emitted by the virtual device.
+    pub fn emitted_events(&self) -> Vec<InputEvent> {
+        self.emitted_events.lock().unwrap().clone()
+    }
+}
+
+impl InputBackendVirtualDevice for SimulatedVirtualDevice {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn emit(&mut self, events: &[InputEvent]) -> io::Result<()> {
+        let mut emitted_events = self.emitted_events.lock().unwrap();
+        emitted_events.extend_from_slice(events);
+        emitted_events.push(InputEvent::new(EventType::SYNCHRONIZATION, 0, 0));
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct SimulatedDeviceState {
+    keys: AttributeSet<Key>,
+
+    leds: AttributeSet<LedType>,
+
+    switches: AttributeSet<SwitchType>,
+
+    absolute_axes: HashMap<u16, AbsoluteAxisInfo>,
+}
@@ -0,0 +1,173 @@
+//! Creates virtual input devices using the uinput interface of the linux kernel. Unlike the
+//! VirtualDeviceBuilder of evdev 0.11, which only supports keys, axes and switches, the virtual
+//! device mirrors all capabilities of an input device including its LEDs, sounds and properties.
+//! The ioctls UI_DEV_SETUP and UI_ABS_SETUP require linux 4.5 or newer.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+
+use evdev::{EventType, InputEvent, Synchronization};
+use nix::{ioctl_none, ioctl_write_int, ioctl_write_ptr};
+
+use crate::backend::{InputBackendDevice, InputBackendVirtualDevice};
+
+const UINPUT_PATH: &str = "/dev/uinput";
+
+const UINPUT_MAX_NAME_SIZE: usize = 80;
+
+const UINPUT_IOCTL_BASE: u8 = b'U';
+
+// The request codes are encoded for the target architecture by nix
+ioctl_none!(ui_dev_create, UINPUT_IOCTL_BASE, 1);
+ioctl_none!(ui_dev_destroy, UINPUT_IOCTL_BASE, 2);
+ioctl_write_ptr!(ui_dev_setup, UINPUT_IOCTL_BASE, 3, UinputSetup);
+ioctl_write_ptr!(ui_abs_setup, UINPUT_IOCTL_BASE, 4, UinputAbsSetup);
+ioctl_write_int!(ui_set_evbit, UINPUT_IOCTL_BASE, 100);
+ioctl_write_int!(ui_set_keybit, UINPUT_IOCTL_BASE, 101);
+ioctl_write_int!(ui_set_relbit, UINPUT_IOCTL_BASE, 102);
+ioctl_write_int!(ui_set_absbit, UINPUT_IOCTL_BASE, 103);
+ioctl_write_int!(ui_set_ledbit, UINPUT_IOCTL_BASE, 105);
+ioctl_write_int!(ui_set_sndbit, UINPUT_IOCTL_BASE, 106);
+ioctl_write_int!(ui_set_swbit, UINPUT_IOCTL_BASE, 109);
+ioctl_write_int!(ui_set_propbit, UINPUT_IOCTL_BASE, 110);
+
+/// The signature of the ioctls which set a single bit, for example UI_SET_KEYBIT.
+type SetBit = unsafe fn(libc::c_int, nix::sys::ioctl::ioctl_param_type) -> nix::Result<libc::c_int>;
+
+/// struct uinput_setup
+#[repr(C)]
+struct UinputSetup {
+    id: libc::input_id,
+    name: [u8; UINPUT_MAX_NAME_SIZE],
+    ff_effects_max: u32,
+}
+
+/// struct uinput_abs_setup
+#[repr(C)]
+struct UinputAbsSetup {
+    code: u16,
+    absinfo: libc::input_absinfo,
+}
+
+/// A virtual input device which has been created using /dev/uinput. The virtual device is
+/// destroyed as soon as it is dropped.
+pub struct UinputVirtualDevice {
+    name: String,
+
+    file: File,
+}
+
+impl UinputVirtualDevice {
+    /// Creates a virtual input device with the given name which supports the same event types,
+    /// keys, LEDs, axes, switches, sounds and properties as the given input device. Force feedback
+    /// is not mirrored because the effects would have to be uploaded by the plugin.
+    pub fn create(name: &str, device: &dyn InputBackendDevice) -> io::Result<UinputVirtualDevice> {
+        let file = OpenOptions::new().read(true).write(true).open(UINPUT_PATH)?;
+        let virtual_device = UinputVirtualDevice { name: name.into(), file };
+        if let Some(keys) = device.supported_keys() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::KEY.0])?;
+            virtual_device.set_bits(ui_set_keybit, &keys.iter().map(|key| key.code()).collect::<Vec<u16>>())?;
+        }
+        if let Some(leds) = device.supported_leds() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::LED.0])?;
+            virtual_device.set_bits(ui_set_ledbit, &leds.iter().map(|led| led.0).collect::<Vec<u16>>())?;
+        }
+        if let Some(relative_axes) = device.supported_relative_axes() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::RELATIVE.0])?;
+            virtual_device.set_bits(ui_set_relbit, &relative_axes.iter().map(|relative_axis| relative_axis.0).collect::<Vec<u16>>())?;
+        }
+        if let Some(absolute_axes) = device.supported_absolute_axes() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::ABSOLUTE.0])?;
+            for absolute_axis in absolute_axes.iter() {
+                virtual_device.set_bits(ui_set_absbit, &[absolute_axis.0])?;
+                let info = device.absolute_axis_info(absolute_axis).unwrap_or_default();
+                let abs_setup = UinputAbsSetup {
+                    code: absolute_axis.0,
+                    absinfo: libc::input_absinfo {
+                        value: info.value,
+                        minimum: info.minimum,
+                        maximum: info.maximum,
+                        fuzz: info.fuzz,
+                        flat: info.flat,
+                        resolution: info.resolution,
+                    },
+                };
+                // SAFETY: The file descriptor is open and abs_setup outlives the call
+                to_io_result(unsafe { ui_abs_setup(virtual_device.file.as_raw_fd(), &abs_setup) })?;
+            }
+        }
+        if let Some(switches) = device.supported_switches() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::SWITCH.0])?;
+            virtual_device.set_bits(ui_set_swbit, &switches.iter().map(|switch| switch.0).collect::<Vec<u16>>())?;
+        }
+        if let Some(sounds) = device.supported_sounds() {
+            virtual_device.set_bits(ui_set_evbit, &[EventType::SOUND.0])?;
+            virtual_device.set_bits(ui_set_sndbit, &sounds.iter().map(|sound| sound.0).collect::<Vec<u16>>())?;
+        }
+        virtual_device.set_bits(ui_set_propbit, &device.input_properties().iter().map(|input_property| input_property.0).collect::<Vec<u16>>())?;
+        let input_id = device.input_id();
+        let mut setup = UinputSetup {
+            id: libc::input_id {
+                bustype: input_id.bus_type().0,
+                vendor: input_id.vendor(),
+                product: input_id.product(),
+                version: input_id.version(),
+            },
+            name: [0; UINPUT_MAX_NAME_SIZE],
+            ff_effects_max: 0,
+        };
+        // The name is truncated and always terminated by a null byte
+        let name_length = name.len().min(UINPUT_MAX_NAME_SIZE - 1);
+        setup.name[..name_length].copy_from_slice(&name.as_bytes()[..name_length]);
+        // SAFETY: The file descriptor is open and setup outlives the call
+        to_io_result(unsafe { ui_dev_setup(virtual_device.file.as_raw_fd(), &setup) })?;
+        // SAFETY: The file descriptor is open and UI_DEV_CREATE has no argument
+        to_io_result(unsafe { ui_dev_create(virtual_device.file.as_raw_fd()) })?;
+        Ok(virtual_device)
+    }
+
+    fn set_bits(&self, set_bit: SetBit, codes: &[u16]) -> io::Result<()> {
+        for code in codes {
+            // SAFETY: The file descriptor is open and the code is passed by value
+            to_io_result(unsafe { set_bit(self.file.as_raw_fd(), *code as nix::sys::ioctl::ioctl_param_type) })?;
+        }
+        Ok(())
+    }
+}
+
+fn to_io_result(result: nix::Result<libc::c_int>) -> io::Result<()> {
+    result.map(|_| ()).map_err(|errno| io::Error::from_raw_os_error(errno as i32))
+}
+
+impl InputBackendVirtualDevice for UinputVirtualDevice {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn emit(&mut self, events: &[InputEvent]) -> io::Result<()> {
+        let mut buffer = Vec::with_capacity((events.len() + 1) * std::mem::size_of::<libc::input_event>());
+        let syn_report = InputEvent::new(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0);
+        for event in events.iter().chain(std::iter::once(&syn_report)) {
+            // The kernel sets the timestamp
+            let raw_event = libc::input_event {
+                time: libc::timeval { tv_sec: 0, tv_usec: 0 },
+                type_: event.event_type().0,
+                code: event.code(),
+                value: event.value(),
+            };
+            // SAFETY: input_event is a repr(C) plain old data struct which lives until the bytes are copied
+            let bytes = unsafe { std::slice::from_raw_parts(&raw_event as *const libc::input_event as *const u8, std::mem::size_of::<libc::input_event>()) };
+            buffer.extend_from_slice(bytes);
+        }
+        self.file.write_all(&buffer)
+    }
+}
+
+impl Drop for UinputVirtualDevice {
+    fn drop(&mut self) {
+        // SAFETY: The file descriptor is still open and UI_DEV_DESTROY has no argument
+        let _ = unsafe { ui_dev_destroy(self.file.as_raw_fd()) };
+    }
+}
@@ -1,56 +1,124 @@
 use std::sync::Arc;
 
 use crate::di::*;
+use async_std::task;
 use async_trait::async_trait;
+use futures::future::join_all;
 use log::debug;
 use uuid::Uuid;
 
+use crate::api::{
+    InputDeviceBackendManager, InputDeviceEventDispatcher, InputDeviceHotplugManager, InputDeviceManager, InputDevicePhysicalDeviceManager,
+    InputDeviceProblemManager, InputDeviceStatusManager, InputDeviceVirtualDeviceManager,
+};
 use crate::behaviour::entity::input_device::InputDevice;
+use crate::behaviour::entity::input_device_plugin::InputDevicePlugin;
+use crate::behaviour::entity::physical_device::PhysicalDevice;
+use crate::behaviour::entity::virtual_device::VirtualDevice;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::EntityBehaviourProvider;
 
 const INPUT_DEVICE: &'static str = "input_device";
 
+const VIRTUAL_DEVICE: &'static str = "virtual_device";
+
+const INPUT_DEVICE_PLUGIN: &'static str = "input_device_plugin";
+
+const PHYSICAL_DEVICE: &'static str = "physical_device";
+
 #[wrapper]
 pub struct InputDeviceStorage(std::sync::RwLock<std::collections::HashMap<Uuid, std::sync::Arc<InputDevice>>>);
 
+#[wrapper]
+pub struct VirtualDeviceBehaviourStorage(std::sync::RwLock<std::collections::HashMap<Uuid, std::sync::Arc<VirtualDevice>>>);
+
+#[wrapper]
+pub struct InputDevicePluginBehaviourStorage(std::sync::RwLock<std::collections::HashMap<Uuid, std::sync::Arc<InputDevicePlugin>>>);
+
+#[wrapper]
+pub struct PhysicalDeviceBehaviourStorage(std::sync::RwLock<std::collections::HashMap<Uuid, std::sync::Arc<PhysicalDevice>>>);
+
 #[provides]
 fn create_input_device_storage() -> InputDeviceStorage {
     InputDeviceStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
 }
 
+#[provides]
+fn create_virtual_device_behaviour_storage() -> VirtualDeviceBehaviourStorage {
+    VirtualDeviceBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
+#[provides]
+fn create_input_device_plugin_behaviour_storage() -> InputDevicePluginBehaviourStorage {
+    InputDevicePluginBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
+#[provides]
+fn create_physical_device_behaviour_storage() -> PhysicalDeviceBehaviourStorage {
+    PhysicalDeviceBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
 #[async_trait]
 pub trait InputDeviceEntityBehaviourProvider: EntityBehaviourProvider + Send + Sync {
     fn create_input_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
 
+    fn create_virtual_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
+    fn create_input_device_plugin(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
+    fn create_physical_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
     fn remove_input_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
 
+    fn remove_virtual_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
+    fn remove_input_device_plugin(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
+    fn remove_physical_device(&self, entity_instance: Arc<ReactiveEntityInstance>);
+
     fn remove_by_id(&self, id: Uuid);
+
+    /// Stops the readers of all input devices and waits until they have released the input devices.
+    fn stop_input_devices(&self);
 }
 
-// #[derive(Clone)]
+#[component]
 pub struct InputDeviceEntityBehaviourProviderImpl {
+    input_device_manager: Wrc<dyn InputDeviceManager>,
+    input_device_hotplug_manager: Wrc<dyn InputDeviceHotplugManager>,
+    input_device_backend_manager: Wrc<dyn InputDeviceBackendManager>,
+    input_device_virtual_device_manager: Wrc<dyn InputDeviceVirtualDeviceManager>,
+    input_device_event_dispatcher: Wrc<dyn InputDeviceEventDispatcher>,
+    input_device_problem_manager: Wrc<dyn InputDeviceProblemManager>,
+    input_device_status_manager: Wrc<dyn InputDeviceStatusManager>,
+    input_device_physical_device_manager: Wrc<dyn InputDevicePhysicalDeviceManager>,
+
     input_device: InputDeviceStorage,
+
+    virtual_device: VirtualDeviceBehaviourStorage,
+
+    input_device_plugin: InputDevicePluginBehaviourStorage,
+
+    physical_device: PhysicalDeviceBehaviourStorage,
 }
 
 interfaces!(InputDeviceEntityBehaviourProviderImpl: dyn EntityBehaviourProvider);
 
-#[component]
-impl InputDeviceEntityBehaviourProviderImpl {
-    #[provides]
-    fn new() -> Self {
-        Self {
-            input_device: create_input_device_storage(),
-        }
-    }
-}
+impl InputDeviceEntityBehaviourProviderImpl {}
 
 #[async_trait]
 #[provides]
 impl InputDeviceEntityBehaviourProvider for InputDeviceEntityBehaviourProviderImpl {
     fn create_input_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
         let id = entity_instance.id;
-        let device_key = InputDevice::new(entity_instance.clone());
+        let device_key = InputDevice::new(
+            entity_instance.clone(),
+            self.input_device_backend_manager.get_backend(),
+            self.input_device_virtual_device_manager.clone(),
+            self.input_device_event_dispatcher.clone(),
+            self.input_device_problem_manager.clone(),
+            self.input_device_status_manager.clone(),
+        );
         if device_key.is_ok() {
             let input_device = Arc::new(device_key.unwrap());
             self.input_device.0.write().unwrap().insert(id, input_device);
@@ -59,17 +127,94 @@ impl InputDeviceEntityBehaviourProvider for InputDeviceEntityBehaviourProviderIm
         }
     }
 
+    fn create_virtual_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        let id = entity_instance.id;
+        let virtual_device = VirtualDevice::new(
+            entity_instance.clone(),
+            self.input_device_virtual_device_manager.clone(),
+            self.input_device_status_manager.clone(),
+        );
+        if virtual_device.is_ok() {
+            let virtual_device = Arc::new(virtual_device.unwrap());
+            self.virtual_device.0.write().unwrap().insert(id, virtual_device);
+            entity_instance.add_behaviour(VIRTUAL_DEVICE);
+            debug!("Added behaviour {} to entity instance {}", VIRTUAL_DEVICE, id);
+        }
+    }
+
+    fn create_input_device_plugin(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        let id = entity_instance.id;
+        let input_device_plugin = InputDevicePlugin::new(entity_instance.clone(), self.input_device_manager.clone(), self.input_device_hotplug_manager.clone());
+        if input_device_plugin.is_ok() {
+            let input_device_plugin = Arc::new(input_device_plugin.unwrap());
+            self.input_device_plugin.0.write().unwrap().insert(id, input_device_plugin);
+            entity_instance.add_behaviour(INPUT_DEVICE_PLUGIN);
+            debug!("Added behaviour {} to entity instance {}", INPUT_DEVICE_PLUGIN, id);
+        }
+    }
+
+    fn create_physical_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        let id = entity_instance.id;
+        let physical_device = PhysicalDevice::new(entity_instance.clone(), self.input_device_physical_device_manager.clone());
+        if physical_device.is_ok() {
+            let physical_device = Arc::new(physical_device.unwrap());
+            self.physical_device.0.write().unwrap().insert(id, physical_device);
+            entity_instance.add_behaviour(PHYSICAL_DEVICE);
+            debug!("Added behaviour {} to entity instance {}", PHYSICAL_DEVICE, id);
+        }
+    }
+
     fn remove_input_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
         self.input_device.0.write().unwrap().remove(&entity_instance.id);
         entity_instance.remove_behaviour(INPUT_DEVICE);
         debug!("Removed behaviour {} from entity instance {}", INPUT_DEVICE, entity_instance.id);
     }
 
+    fn remove_virtual_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        self.virtual_device.0.write().unwrap().remove(&entity_instance.id);
+        entity_instance.remove_behaviour(VIRTUAL_DEVICE);
+        debug!("Removed behaviour {} from entity instance {}", VIRTUAL_DEVICE, entity_instance.id);
+    }
+
+    fn remove_input_device_plugin(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        self.input_device_plugin.0.write().unwrap().remove(&entity_instance.id);
+        entity_instance.remove_behaviour(INPUT_DEVICE_PLUGIN);
+        debug!("Removed behaviour {} from entity instance {}", INPUT_DEVICE_PLUGIN, entity_instance.id);
+    }
+
+    fn remove_physical_device(&self, entity_instance: Arc<ReactiveEntityInstance>) {
+        self.physical_device.0.write().unwrap().remove(&entity_instance.id);
+        entity_instance.remove_behaviour(PHYSICAL_DEVICE);
+        debug!("Removed behaviour {} from entity instance {}", PHYSICAL_DEVICE, entity_instance.id);
+    }
+
     fn remove_by_id(&self, id: Uuid) {
         if self.input_device.0.write().unwrap().contains_key(&id) {
             self.input_device.0.write().unwrap().remove(&id);
             debug!("Removed behaviour {} from entity instance {}", INPUT_DEVICE, id);
         }
+        if self.virtual_device.0.write().unwrap().contains_key(&id) {
+            self.virtual_device.0.write().unwrap().remove(&id);
+            debug!("Removed behaviour {} from entity instance {}", VIRTUAL_DEVICE, id);
+        }
+        if self.input_device_plugin.0.write().unwrap().contains_key(&id) {
+            self.input_device_plugin.0.write().unwrap().remove(&id);
+            debug!("Removed behaviour {} from entity instance {}", INPUT_DEVICE_PLUGIN, id);
+        }
+        if self.physical_device.0.write().unwrap().contains_key(&id) {
+            self.physical_device.0.write().unwrap().remove(&id);
+            debug!("Removed behaviour {} from entity instance {}", PHYSICAL_DEVICE, id);
+        }
+    }
+
+    fn stop_input_devices(&self) {
+        // Don't hold the lock while waiting for the readers
+        let input_devices: Vec<Arc<InputDevice>> = self.input_device.0.read().unwrap().values().cloned().collect();
+        for input_device in input_devices.iter() {
+            input_device.stop();
+        }
+        // The readers are stopped at the same time, so the plugin waits at most once for the timeout
+        task::block_on(join_all(input_devices.iter().map(|input_device| input_device.join())));
     }
 }
 
@@ -77,6 +222,9 @@ impl EntityBehaviourProvider for InputDeviceEntityBehaviourProviderImpl {
     fn add_behaviours(&self, entity_instance: Arc<ReactiveEntityInstance>) {
         match entity_instance.clone().type_name.as_str() {
             INPUT_DEVICE => self.create_input_device(entity_instance),
+            VIRTUAL_DEVICE => self.create_virtual_device(entity_instance),
+            INPUT_DEVICE_PLUGIN => self.create_input_device_plugin(entity_instance),
+            PHYSICAL_DEVICE => self.create_physical_device(entity_instance),
             _ => {}
         }
     }
@@ -84,6 +232,9 @@ impl EntityBehaviourProvider for InputDeviceEntityBehaviourProviderImpl {
     fn remove_behaviours(&self, entity_instance: Arc<ReactiveEntityInstance>) {
         match entity_instance.clone().type_name.as_str() {
             INPUT_DEVICE => self.remove_input_device(entity_instance),
+            VIRTUAL_DEVICE => self.remove_virtual_device(entity_instance),
+            INPUT_DEVICE_PLUGIN => self.remove_input_device_plugin(entity_instance),
+            PHYSICAL_DEVICE => self.remove_physical_device(entity_instance),
             _ => {}
         }
     }
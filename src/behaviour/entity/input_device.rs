@@ -1,237 +1,853 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::AsRef;
-use std::num::TryFromIntError;
-use std::sync::Arc;
-use std::time::Duration;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
-use crate::reactive::BehaviourCreationError;
+use async_std::future::timeout;
 use async_std::task;
-use log::{error, trace};
+use async_std::task::JoinHandle;
+use log::{debug, error, trace};
 use serde_json::{json, Value};
+use uuid::Uuid;
 
+use crate::api::{InputDeviceEventDispatcher, InputDeviceProblemManager, InputDeviceStatusManager, InputDeviceVirtualDeviceManager};
+use crate::backend::{AbsoluteAxisInfo, DeviceHandle, InputBackend, InputBackendDevice, InputBackendEventStream};
 use crate::behaviour::entity::InputDeviceProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{kind_and_code_of, to_input_events, to_timestamp, with_syn_report, without_syn_report};
 use crate::behaviour::event_payload::{
-    ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE, INPUT_EVENT_KIND, INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_KIND_LED_EVENT,
-    INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT, INPUT_EVENT_KIND_SWITCH_EVENT, INPUT_EVENT_VALUE, KEY_EVENT_KEY_CODE, LED_EVENT_LED_TYPE,
-    RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE, SWITCH_EVENT_SWITCH_TYPE,
+    ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE, ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE, INPUT_EVENT_KIND, INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+    INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT, INPUT_EVENT_KIND_SWITCH_EVENT, INPUT_EVENT_TIMESTAMP,
+    INPUT_EVENT_VALUE, KEY_EVENT_KEY_CODE, LED_EVENT_LED_TYPE, RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE, SWITCH_EVENT_SWITCH_TYPE,
 };
+use crate::identity::identity_of;
 use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
 use crate::model::ReactiveEntityInstance;
+use crate::model::ReactivePropertyInstance;
 use crate::reactive::entity::Disconnectable;
-use evdev::{EventType, InputEvent, InputEventKind};
+use evdev::{AbsoluteAxisType, EventType, InputEvent, InputEventKind, Key, LedType, SwitchType, Synchronization};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use futures::FutureExt;
-use futures::{select, StreamExt};
-use futures_timer::Delay;
+use futures::{select_biased, StreamExt};
 
 pub const INPUT_DEVICE: &'static str = "input_device";
 
+/// No such device (the device node has been removed)
+const ENODEV: i32 = 19;
+
+/// The maximum time to wait for the reader to release the input device.
+const READER_STOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The time to wait before the first attempt to reopen a disconnected input device.
+const RECONNECT_BACKOFF_MIN: Duration = Duration::from_millis(100);
+
+/// The maximum time to wait between two attempts to reopen a disconnected input device.
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(5);
+
 pub struct InputDevice {
     pub entity: Arc<ReactiveEntityInstance>,
 
     pub handle_id: u128,
 
-    stopper: crossbeam::channel::Sender<()>,
+    pub grab_handle_id: u128,
+
+    pub event_handle_id: u128,
+
+    pub query_handle_id: u128,
+
+    stopper: UnboundedSender<()>,
+
+    sender: Arc<EventSender>,
+
+    /// The task which reads the events of the input device.
+    reader: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl InputDevice {
-    pub fn new<'a>(e: Arc<ReactiveEntityInstance>) -> Result<InputDevice, BehaviourCreationError> {
-        let physical_path = e.properties.get(InputDeviceProperties::PHYSICAL_PATH.as_ref());
-        if physical_path.is_none() {
-            error!("Missing physical_path");
-            return Err(BehaviourCreationError.into());
-        }
-        let physical_path = physical_path.unwrap().as_string().unwrap();
+    pub fn new<'a>(
+        e: Arc<ReactiveEntityInstance>,
+        backend: Arc<dyn InputBackend>,
+        virtual_device_manager: Arc<dyn InputDeviceVirtualDeviceManager>,
+        event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
+        problem_manager: Arc<dyn InputDeviceProblemManager>,
+        status_manager: Arc<dyn InputDeviceStatusManager>,
+    ) -> Result<InputDevice, InputDeviceError> {
+        let identity = e
+            .as_string(InputDeviceProperties::IDENTITY)
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::IDENTITY.to_string())))?;
 
         let name = e.as_string(InputDeviceProperties::NAME).unwrap_or("Unknown Device".into());
-        trace!("Initializing behaviour for input device {} with physical path {}", name, physical_path);
+        trace!("Initializing behaviour for input device {} with identity {}", name, identity);
 
-        let device = evdev::enumerate().find(|d| physical_path.as_str() == d.physical_path().unwrap_or(""));
-        if device.is_none() {
-            return Err(BehaviourCreationError);
-        }
-        let device = device.unwrap();
+        // Identical input devices may have the same physical path, but never the same identity
+        let device = find_device(backend.as_ref(), identity.as_str(), &locations_of(&e))
+            .ok_or_else(|| report_error(&e, InputDeviceError::DeviceNotFound(identity.clone())))?;
 
-        let handle_id = e.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()).unwrap().id.as_u128();
+        // The device is opened a second time for writing, because the reader owns the first handle
+        let writer: DeviceHandle = Arc::new(Mutex::new(match backend.open(device.device_node()) {
+            Ok(writer) => Some(writer),
+            Err(io_error) => {
+                // Reading may still be possible, sending fails with the published error
+                report_error(&e, InputDeviceError::from_io(device.device_node(), io_error));
+                None
+            }
+        }));
 
-        let physical_path_2 = physical_path.clone();
-        e.properties
+        let property_send_event = e
+            .properties
             .get(InputDeviceProperties::SEND_EVENT.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |send_event: &Value| {
-                    // event_type (u64)
-                    let event_type = send_event.get("event_type");
-                    if event_type.is_none() {
-                        return;
-                    }
-                    let event_type = event_type.unwrap();
-                    if !event_type.is_number() {
-                        // Invalid
-                        return;
-                    }
-                    let event_type = to_event_type(event_type.as_u64().unwrap());
-                    if event_type.is_err() {
-                        // Invalid
-                        return;
-                    }
-                    let event_type = event_type.unwrap();
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::SEND_EVENT.to_string())))?;
+        let property_event = e
+            .properties
+            .get(InputDeviceProperties::EVENT.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::EVENT.to_string())))?;
+        let property_grab = e
+            .properties
+            .get(InputDeviceProperties::GRAB.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::GRAB.to_string())))?;
+        let property_query = e
+            .properties
+            .get(InputDeviceProperties::QUERY.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::QUERY.to_string())))?;
+        if !e.properties.contains_key(InputDeviceProperties::FRAME.as_ref()) {
+            return Err(report_error(&e, InputDeviceError::MissingProperty(InputDeviceProperties::FRAME.to_string())));
+        }
 
-                    // code (i64 -> i16)
-                    let code = send_event.get("code");
-                    if code.is_none() {
-                        return;
-                    }
-                    let code = code.unwrap();
-                    if !code.is_number() {
-                        // Invalid
-                        return;
-                    }
-                    let code = u16::try_from(code.as_i64().unwrap()).unwrap();
+        let handle_id = property_send_event.id.as_u128();
 
-                    // value (bool)
-                    let value = send_event.get("value");
-                    if value.is_none() {
-                        return;
+        let sender = Arc::new(EventSender {
+            input_device_id: e.id,
+            writer: writer.clone(),
+            virtual_device_manager: virtual_device_manager.clone(),
+            pressed_keys: Mutex::new(HashSet::new()),
+        });
+        let send_sender = sender.clone();
+        let send_entity_instance = e.clone();
+        let send_status_manager = status_manager.clone();
+        property_send_event.stream.read().unwrap().observe_with_handle(
+            move |send_event: &Value| {
+                let result = to_input_events(send_event).and_then(|events| {
+                    let count = events.len();
+                    send_sender.send(events).map(|_| count)
+                });
+                let send_error = match result {
+                    Ok(count) => {
+                        send_status_manager.events_sent(count);
+                        String::new()
                     }
-                    let value = value.unwrap();
-                    if !value.is_boolean() {
-                        // Invalid
-                        return;
+                    Err(e) => {
+                        send_status_manager.send_request_dropped();
+                        report_error(&send_entity_instance, e).to_string()
                     }
-                    let value = if value.as_bool().unwrap() { i32::MAX } else { 0 };
+                };
+                set_send_error(&send_entity_instance, InputDeviceProperties::SEND_ERROR, send_error);
+            },
+            handle_id,
+        );
 
-                    let device = evdev::enumerate().find(|d| physical_path_2.as_str() == d.physical_path().unwrap_or(""));
-                    if device.is_none() {
-                        return;
-                    }
+        // The events are dispatched to the keys, LEDs, axes and switches by their kind and code
+        let event_handle_id = property_event.id.as_u128();
+        let input_device_id = e.id;
+        let dispatch_event_dispatcher = event_dispatcher.clone();
+        let dispatch_writer = writer.clone();
+        property_event.stream.read().unwrap().observe_with_handle(
+            move |event: &Value| dispatch_event_dispatcher.dispatch(input_device_id, event, &dispatch_writer),
+            event_handle_id,
+        );
 
-                    let _result = device.unwrap().send_events(&[InputEvent::new(event_type, code, value)]);
-                },
-                handle_id,
-            );
+        // Entities which are created on demand are created as soon as they are queried
+        let query_handle_id = property_query.id.as_u128();
+        let query_event_dispatcher = event_dispatcher.clone();
+        let query_writer = writer.clone();
+        let query_entity_instance = e.clone();
+        property_query.stream.read().unwrap().observe_with_handle(
+            move |query: &Value| match kind_and_code_of(query) {
+                Some((input_event_kind, code)) => {
+                    if !query_event_dispatcher.materialize(input_device_id, input_event_kind, code, &query_writer) {
+                        trace!("Query {} of input device {} has no result", query, input_device_id);
+                    }
+                }
+                None => {
+                    report_error(&query_entity_instance, InputDeviceError::InvalidPayload(format!("Invalid query {}", query)));
+                }
+            },
+            query_handle_id,
+        );
 
-        let (stopper_tx, stopper_rx) = crossbeam::channel::bounded(1);
+        let (grab_tx, grab_rx) = unbounded();
+        let grab_handle_id = property_grab.id.as_u128();
+        property_grab.stream.read().unwrap().observe_with_handle(
+            move |grab: &Value| {
+                if let Some(grab) = grab.as_bool() {
+                    let _ = grab_tx.unbounded_send(grab);
+                }
+            },
+            grab_handle_id,
+        );
 
-        let entity_instance = e.clone();
+        let (stopper_tx, stopper_rx) = unbounded();
 
+        let reader = Reader {
+            entity_instance: e.clone(),
+            backend,
+            writer,
+            problem_manager,
+            status_manager: status_manager.clone(),
+            event_dispatcher,
+            stopper: stopper_rx,
+            grab_requests: grab_rx,
+            grab: e.as_bool(InputDeviceProperties::GRAB).unwrap_or(false),
+            identity,
+            resync: false,
+            device_nodes: Vec::new(),
+        };
         let thread_name = format!("{}-{}", e.type_name.clone(), e.id.to_string());
-        let _handler = task::Builder::new().name(thread_name).spawn(async move {
-            let event_stream = device.into_event_stream();
-            if event_stream.is_err() {
-                error!("{:?}", event_stream.err().unwrap());
-                return;
+        let reader = task::Builder::new().name(thread_name).spawn(reader.run(device));
+        if reader.is_ok() {
+            status_manager.reader_started(e.id);
+        }
+
+        Ok(InputDevice {
+            entity: e.clone(),
+            sender,
+            stopper: stopper_tx,
+            reader: Mutex::new(reader.ok()),
+            handle_id,
+            grab_handle_id,
+            event_handle_id,
+            query_handle_id,
+        })
+    }
+
+    pub fn type_name(&self) -> String {
+        self.entity.type_name.clone()
+    }
+
+    /// Releases the keys which are held down by the virtual device and tells the reader to stop.
+    /// Doesn't wait for the reader, which releases the input device as soon as it has stopped.
+    pub fn stop(&self) {
+        self.sender.release_keys();
+        let _ = self.stopper.unbounded_send(());
+    }
+
+    /// Waits until the reader has stopped and has released the input device.
+    pub async fn join(&self) {
+        let reader = self.reader.lock().unwrap().take();
+        if let Some(reader) = reader {
+            trace!("Waiting for the reader of {} with id {}", INPUT_DEVICE, self.entity.id);
+            if timeout(READER_STOP_TIMEOUT, reader).await.is_err() {
+                error!("The reader of {} with id {} didn't stop in time", INPUT_DEVICE, self.entity.id);
+            }
+        }
+    }
+}
+
+impl Disconnectable for InputDevice {
+    fn disconnect(&self) {
+        trace!("Disconnecting {} with id {}", INPUT_DEVICE, self.entity.id);
+        let property = self.entity.properties.get(InputDeviceProperties::SEND_EVENT.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+        }
+        let property = self.entity.properties.get(InputDeviceProperties::GRAB.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.grab_handle_id);
+        }
+        let property = self.entity.properties.get(InputDeviceProperties::EVENT.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.event_handle_id);
+        }
+        let property = self.entity.properties.get(InputDeviceProperties::QUERY.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.query_handle_id);
+        }
+        trace!("Stopping reader of {} with id {}", INPUT_DEVICE, self.entity.id);
+        self.stop();
+    }
+}
+
+/// Automatically disconnect streams on destruction
+impl Drop for InputDevice {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/// The reason why the reader has stopped reading the events of the input device.
+enum ReaderExit {
+    /// The behaviour has been disconnected.
+    Stopped,
+
+    /// The input device has been unplugged or is not readable anymore.
+    Disconnected,
+}
+
+/// Reads the events of the input device. If the input device has been disconnected, for example
+/// because a wireless receiver dropped out, the reader keeps trying to reopen the input device
+/// with backoff. The entity instance is kept, so that the flows keep working after the input
+/// device has been reconnected.
+struct Reader {
+    entity_instance: Arc<ReactiveEntityInstance>,
+
+    backend: Arc<dyn InputBackend>,
+
+    writer: DeviceHandle,
+
+    problem_manager: Arc<dyn InputDeviceProblemManager>,
+
+    status_manager: Arc<dyn InputDeviceStatusManager>,
+
+    event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
+
+    stopper: UnboundedReceiver<()>,
+
+    grab_requests: UnboundedReceiver<bool>,
+
+    /// True, if the input device should be grabbed. Survives reconnects.
+    grab: bool,
+
+    /// The identity is stable across reconnects, unlike the device node.
+    identity: String,
+
+    /// True, if the state of the input device has to be published as soon as the events are read.
+    resync: bool,
+
+    /// The device nodes which existed at the last attempt to reopen the input device.
+    device_nodes: Vec<PathBuf>,
+}
+
+impl Reader {
+    async fn run(mut self, device: Box<dyn InputBackendDevice>) {
+        let mut device = Some(device);
+        let mut backoff = RECONNECT_BACKOFF_MIN;
+        loop {
+            let current_device = match device.take() {
+                Some(current_device) => current_device,
+                None => {
+                    // Wait before the next attempt, unless the reader is stopped meanwhile
+                    if timeout(backoff, self.stopper.next()).await.is_ok() {
+                        break;
+                    }
+                    backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX);
+                    match self.reopen() {
+                        Some(reopened_device) => {
+                            debug!("Reconnected input device {} at {}", self.entity_instance.id, reopened_device.device_node().display());
+                            self.reconnect(reopened_device.as_ref());
+                            reopened_device
+                        }
+                        None => continue,
+                    }
+                }
+            };
+            match self.read_events(current_device).await {
+                ReaderExit::Stopped => break,
+                ReaderExit::Disconnected => backoff = RECONNECT_BACKOFF_MIN,
             }
+        }
+        // Events can't be sent to a disconnected input device
+        self.writer.lock().unwrap().take();
+    }
 
-            let property_event = entity_instance.properties.get(InputDeviceProperties::EVENT.as_ref());
-            if property_event.is_none() {
-                error!("Missing property event");
-                return;
+    /// Reads the events of the given input device until the input device has been disconnected
+    /// or the reader has been stopped.
+    async fn read_events(&mut self, device: Box<dyn InputBackendDevice>) -> ReaderExit {
+        let entity_instance = self.entity_instance.clone();
+        let device_node = device.device_node().to_path_buf();
+        let absolute_axis_infos = absolute_axis_infos_of(device.as_ref());
+        let state_codes = StateCodes::of(device.as_ref());
+        let mut event_stream = match device.into_event_stream() {
+            Ok(event_stream) => event_stream,
+            Err(io_error) => {
+                report_error(&entity_instance, InputDeviceError::from_io(device_node.as_path(), io_error));
+                return ReaderExit::Disconnected;
             }
-            let property_event = property_event.unwrap();
+        };
+
+        // The properties have been checked before the reader has been spawned
+        let (property_event, property_frame) = match (
+            entity_instance.properties.get(InputDeviceProperties::EVENT.as_ref()),
+            entity_instance.properties.get(InputDeviceProperties::FRAME.as_ref()),
+        ) {
+            (Some(property_event), Some(property_frame)) => (property_event, property_frame),
+            _ => return ReaderExit::Stopped,
+        };
+        set_connected(&entity_instance, true);
 
-            let mut event_stream = event_stream.unwrap();
-            loop {
-                let mut delay = Delay::new(Duration::from_millis(1_000)).fuse();
+        // The events of the current frame (only in frame mode)
+        let mut frame: Vec<Value> = Vec::new();
+
+        // True, if events have been dropped by the kernel. The following events are discarded
+        // up to and including the next SYN_REPORT, afterwards the state is queried.
+        let mut dropped = false;
+        let mut resync_requested = std::mem::take(&mut self.resync);
+
+        let mut grabbed = false;
+        if self.grab {
+            grabbed = set_grab(event_stream.as_mut(), true, &entity_instance, device_node.as_path(), self.problem_manager.as_ref());
+        }
+        let exit = loop {
+            if resync_requested {
+                resync_requested = false;
+                // Entities which are created on demand are not created by the state
+                let input_device_id = entity_instance.id;
+                let event_dispatcher = &self.event_dispatcher;
+                resync(event_stream.as_ref(), &state_codes, &property_event, |input_event_kind, code| {
+                    event_dispatcher.has_handlers(input_device_id, input_event_kind, code)
+                });
+            }
+            let mut grab_request = None;
+            {
+                let mut stop = self.stopper.next();
                 let mut event = event_stream.next().fuse();
+                let mut grab = self.grab_requests.next();
 
-                select! {
-                    _ = delay => {
-                        match stopper_rx.try_recv() {
-                            // Stop thread
-                            Ok(_) => break,
-                            // Continue thread
-                            Err(_) => continue,
-                        }
-                    },
+                // A stop request takes precedence over pending events, so that the entity
+                // isn't modified anymore after the behaviour has been disconnected
+                select_biased! {
+                    _ = stop => break ReaderExit::Stopped,
                     maybe_event = event => {
                         match maybe_event {
                             Some(Ok(event)) => {
                                 match event.kind() {
-                                    InputEventKind::Key(key) => {
-                                        property_event.set(json!({
-                                            INPUT_EVENT_KIND: INPUT_EVENT_KIND_KEY_EVENT,
-                                            KEY_EVENT_KEY_CODE: key.code(),
-                                            INPUT_EVENT_VALUE: event.value()
-                                        }))
+                                    InputEventKind::Synchronization(Synchronization::SYN_REPORT) => {
+                                        if dropped {
+                                            // The events which have been lost are replaced by the current state
+                                            trace!("Resynchronizing input device {} after SYN_DROPPED", entity_instance.id);
+                                            dropped = false;
+                                            resync_requested = true;
+                                        } else {
+                                            // The frame is complete
+                                            publish_frame(&mut frame, &property_event, &property_frame);
+                                        }
                                     }
-                                    InputEventKind::Led(led_type) => {
-                                        property_event.set(json!({
-                                            INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
-                                            LED_EVENT_LED_TYPE: led_type.0,
-                                            INPUT_EVENT_VALUE: event.value()
-                                        }))
+                                    InputEventKind::Synchronization(Synchronization::SYN_DROPPED) => {
+                                        // The kernel buffer overran and events have been lost. The
+                                        // incomplete frame is discarded.
+                                        frame.clear();
+                                        dropped = true;
                                     }
-                                    InputEventKind::RelAxis(relative_axis_type) => {
-                                        property_event.set(json!({
-                                            INPUT_EVENT_KIND: INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
-                                            RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE: relative_axis_type.0,
-                                            INPUT_EVENT_VALUE: event.value()
-                                        }))
+                                    // The events of an incomplete frame are discarded
+                                    _ if dropped => {}
+                                    _ => {
+                                        if let Some(payload) = to_event_payload(&event, &absolute_axis_infos) {
+                                            if let Some((kind, _)) = kind_and_code_of(&payload) {
+                                                self.status_manager.event_received(kind, to_timestamp(event.timestamp()));
+                                            }
+                                            if entity_instance.as_bool(InputDeviceProperties::FRAME_MODE).unwrap_or(false) {
+                                                frame.push(payload);
+                                            } else {
+                                                publish_frame(&mut frame, &property_event, &property_frame);
+                                                property_event.set(payload);
+                                            }
+                                        }
                                     }
-                                    InputEventKind::AbsAxis(absolute_axis_type) => {
-                                        property_event.set(json!({
-                                            INPUT_EVENT_KIND: INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
-                                            ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE: absolute_axis_type.0,
-                                            INPUT_EVENT_VALUE: event.value()
-                                        }))
-                                    }
-                                    InputEventKind::Switch(switch_type) => {
-                                        property_event.set(json!({
-                                            INPUT_EVENT_KIND: INPUT_EVENT_KIND_SWITCH_EVENT,
-                                            SWITCH_EVENT_SWITCH_TYPE: switch_type.0,
-                                            INPUT_EVENT_VALUE: event.value()
-                                        }))
-                                    }
-                                    _ => {}
                                 }
                             }
-                            Some(Err(_)) => {
-                                // debug!("Error: {:?}\r", e);
+                            Some(Err(e)) => match e.kind() {
+                                // Transient errors, the next events are awaited
+                                io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => {}
+                                // Any other error, for example ENODEV if the device node has been removed, is
+                                // handled like a disconnect, so that the input device is reopened with backoff
+                                _ => {
+                                    debug!("Failed to read the events of input device {}: {}", entity_instance.id, e);
+                                    break ReaderExit::Disconnected;
+                                }
                             },
-                            None => break,
+                            None => break ReaderExit::Disconnected,
                         }
-                    }
+                    },
+                    grab = grab => match grab {
+                        Some(grab) => grab_request = Some(grab),
+                        // The behaviour has been disconnected
+                        None => break ReaderExit::Stopped,
+                    },
                 };
             }
-        });
+            if let Some(grab) = grab_request {
+                self.grab = grab;
+                if grab != grabbed {
+                    grabbed = set_grab(event_stream.as_mut(), grab, &entity_instance, device_node.as_path(), self.problem_manager.as_ref());
+                }
+            }
+        };
+        match exit {
+            ReaderExit::Stopped => {
+                if grabbed {
+                    // The grab is released anyway if the device is closed
+                    let _ = event_stream.ungrab();
+                }
+            }
+            ReaderExit::Disconnected => {
+                debug!("Input device {} has been disconnected", entity_instance.id);
+                // Events can't be sent to a disconnected input device
+                self.writer.lock().unwrap().take();
+                set_connected(&entity_instance, false);
+            }
+        }
+        exit
+    }
 
-        Ok(InputDevice {
-            entity: e.clone(),
-            stopper: stopper_tx.clone(),
-            handle_id,
-        })
+    /// Looks up the input device by its identity, because the device node usually changes if an
+    /// input device has been plugged in again.
+    fn reopen(&mut self) -> Option<Box<dyn InputBackendDevice>> {
+        let identity = self.identity.as_str();
+        if let Some(device) = open_device_at(self.backend.as_ref(), identity, &locations_of(&self.entity_instance)) {
+            return Some(device);
+        }
+        // Enumerating opens every input device, which is only worth it if a device node has appeared
+        let device_nodes = self.backend.device_nodes();
+        if device_nodes == self.device_nodes {
+            return None;
+        }
+        self.device_nodes = device_nodes;
+        enumerate_device(self.backend.as_ref(), identity)
     }
 
-    pub fn type_name(&self) -> String {
-        self.entity.type_name.clone()
+    /// Reopens the writer. The current state of the reconnected input device is published as soon
+    /// as its events are read.
+    fn reconnect(&mut self, device: &dyn InputBackendDevice) {
+        let device_node = device.device_node();
+        *self.writer.lock().unwrap() = match self.backend.open(device_node) {
+            Ok(writer) => Some(writer),
+            Err(io_error) => {
+                report_error(&self.entity_instance, InputDeviceError::from_io(device_node, io_error));
+                None
+            }
+        };
+        self.status_manager.reader_started(self.entity_instance.id);
+        // The keys which have been released while the input device was disconnected
+        self.resync = true;
     }
 }
 
-impl Disconnectable for InputDevice {
-    fn disconnect(&self) {
-        trace!("Disconnecting {} with id {}", INPUT_DEVICE, self.entity.id);
-        let property = self.entity.properties.get(InputDeviceProperties::SEND_EVENT.as_ref());
-        if property.is_some() {
-            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+/// Sends events to the input device or to its virtual device. Keeps track of the keys and buttons
+/// which have been pressed by the virtual device, so that they can be released if the input device
+/// goes away.
+struct EventSender {
+    input_device_id: Uuid,
+
+    writer: DeviceHandle,
+
+    virtual_device_manager: Arc<dyn InputDeviceVirtualDeviceManager>,
+
+    /// The codes of the keys which have been pressed by the virtual device and not released yet.
+    pressed_keys: Mutex<HashSet<u16>>,
+}
+
+impl EventSender {
+    fn send(&self, events: Vec<InputEvent>) -> Result<(), InputDeviceError> {
+        // The kernel only processes LEDs, sounds and force feedback written to the device
+        // node of the physical device. All other events are emitted by the virtual device.
+        let has_virtual_device = self.virtual_device_manager.has_virtual_device(self.input_device_id);
+        let (virtual_events, physical_events): (Vec<InputEvent>, Vec<InputEvent>) = events
+            .into_iter()
+            .partition(|event| has_virtual_device && !is_output_event_type(event.event_type()));
+
+        let virtual_events = without_syn_report(virtual_events);
+        if !virtual_events.is_empty() {
+            self.virtual_device_manager
+                .send_events(self.input_device_id, &virtual_events)
+                .map_err(|e| InputDeviceError::Io(format!("Failed to send events to the virtual device: {}", e)))?;
+            self.track_keys(&virtual_events);
+        }
+        if !physical_events.is_empty() {
+            let physical_events = with_syn_report(physical_events);
+            let mut writer = self.writer.lock().unwrap();
+            // The device node may change if the input device has been reconnected
+            match writer
+                .as_mut()
+                .map(|device| (device.device_node().to_path_buf(), device.send_events(&physical_events)))
+            {
+                Some((_, Ok(()))) => {}
+                Some((device_node, Err(e))) => {
+                    if e.raw_os_error() == Some(ENODEV) {
+                        // The device node has been removed
+                        writer.take();
+                    }
+                    return Err(InputDeviceError::from_io(device_node.as_path(), e));
+                }
+                None => return Err(InputDeviceError::DeviceNotFound(self.input_device_id.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    fn track_keys(&self, events: &[InputEvent]) {
+        let mut pressed_keys = self.pressed_keys.lock().unwrap();
+        for event in events.iter().filter(|event| event.event_type() == EventType::KEY) {
+            match event.value() {
+                0 => pressed_keys.remove(&event.code()),
+                _ => pressed_keys.insert(event.code()),
+            };
+        }
+    }
+
+    /// Sends a key up event to the virtual device for each key which is still held down.
+    fn release_keys(&self) {
+        let pressed_keys: Vec<u16> = self.pressed_keys.lock().unwrap().drain().collect();
+        if pressed_keys.is_empty() {
+            return;
+        }
+        debug!("Releasing {} keys of input device {}", pressed_keys.len(), self.input_device_id);
+        let events: Vec<InputEvent> = pressed_keys.into_iter().map(|code| InputEvent::new(EventType::KEY, code, 0)).collect();
+        if let Err(e) = self.virtual_device_manager.send_events(self.input_device_id, &events) {
+            // If the virtual device is gone, the kernel has released the keys already
+            debug!("Failed to release the keys of input device {}: {}", self.input_device_id, e);
         }
-        trace!("Stopping thread of {} with id {}", INPUT_DEVICE, self.entity.id);
-        let _ = self.stopper.send(());
     }
 }
 
-/// Automatically disconnect streams on destruction
-impl Drop for InputDevice {
-    fn drop(&mut self) {
-        self.disconnect();
+/// Grabs or releases the input device. Returns true, if the input device is grabbed afterwards.
+/// The grab fails with EBUSY if another process has grabbed the input device, which is reported
+/// as problem of the device node.
+fn set_grab(
+    event_stream: &mut dyn InputBackendEventStream,
+    grab: bool,
+    entity_instance: &ReactiveEntityInstance,
+    device_node: &Path,
+    problem_manager: &dyn InputDeviceProblemManager,
+) -> bool {
+    let result = if grab { event_stream.grab() } else { event_stream.ungrab() };
+    match result {
+        Ok(_) => {
+            trace!("{} input device {}", if grab { "Grabbed" } else { "Released" }, entity_instance.id);
+            problem_manager.resolve_problem(device_node);
+            grab
+        }
+        Err(e) => {
+            let error = report_error(entity_instance, InputDeviceError::from_io(device_node, e));
+            if let InputDeviceError::DeviceBusy(_) = error {
+                problem_manager.report_problem(device_node, &error);
+            }
+            !grab
+        }
     }
 }
 
-fn to_event_type(event_type: u64) -> Result<EventType, TryFromIntError> {
-    let r_event_type = u16::try_from(event_type);
-    if r_event_type.is_err() {
-        return Err(r_event_type.err().unwrap());
+/// Converts an input event into the payload of the property `event`. Returns None for events
+/// which are not forwarded to the entities, for example synchronization events.
+fn to_event_payload(event: &InputEvent, absolute_axis_infos: &HashMap<u16, AbsoluteAxisInfo>) -> Option<Value> {
+    let timestamp = to_timestamp(event.timestamp());
+    match event.kind() {
+        InputEventKind::Key(key) => Some(json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_KEY_EVENT,
+            KEY_EVENT_KEY_CODE: key.code(),
+            INPUT_EVENT_VALUE: event.value(),
+            INPUT_EVENT_TIMESTAMP: timestamp
+        })),
+        InputEventKind::Led(led_type) => Some(json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
+            LED_EVENT_LED_TYPE: led_type.0,
+            INPUT_EVENT_VALUE: event.value(),
+            INPUT_EVENT_TIMESTAMP: timestamp
+        })),
+        InputEventKind::RelAxis(relative_axis_type) => Some(json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
+            RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE: relative_axis_type.0,
+            INPUT_EVENT_VALUE: event.value(),
+            INPUT_EVENT_TIMESTAMP: timestamp
+        })),
+        InputEventKind::AbsAxis(absolute_axis_type) => {
+            let normalized = absolute_axis_infos
+                .get(&absolute_axis_type.0)
+                .map(|absolute_axis_info| absolute_axis_info.normalize(event.value()))
+                .unwrap_or(0.0);
+            Some(json!({
+                INPUT_EVENT_KIND: INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+                ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE: absolute_axis_type.0,
+                ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE: normalized,
+                INPUT_EVENT_VALUE: event.value(),
+                INPUT_EVENT_TIMESTAMP: timestamp
+            }))
+        }
+        InputEventKind::Switch(switch_type) => Some(json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_SWITCH_EVENT,
+            SWITCH_EVENT_SWITCH_TYPE: switch_type.0,
+            INPUT_EVENT_VALUE: event.value(),
+            INPUT_EVENT_TIMESTAMP: timestamp
+        })),
+        _ => None,
     }
-    Ok(EventType(r_event_type.unwrap()))
+}
+
+/// Returns the ranges of the absolute axes of the input device, which are needed for the normalized values.
+fn absolute_axis_infos_of(device: &dyn InputBackendDevice) -> HashMap<u16, AbsoluteAxisInfo> {
+    device
+        .supported_absolute_axes()
+        .map(|supported_absolute_axes| {
+            supported_absolute_axes
+                .iter()
+                .filter_map(|absolute_axis_type| device.absolute_axis_info(absolute_axis_type).ok().map(|info| (absolute_axis_type.0, info)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The keys, LEDs, switches and absolute axes of an input device whose state can be queried.
+struct StateCodes {
+    keys: Vec<Key>,
+
+    leds: Vec<LedType>,
+
+    switches: Vec<SwitchType>,
+
+    absolute_axes: Vec<AbsoluteAxisType>,
+}
+
+impl StateCodes {
+    fn of(device: &dyn InputBackendDevice) -> StateCodes {
+        StateCodes {
+            keys: device.supported_keys().map(|keys| keys.iter().collect()).unwrap_or_default(),
+            leds: device.supported_leds().map(|leds| leds.iter().collect()).unwrap_or_default(),
+            switches: device.supported_switches().map(|switches| switches.iter().collect()).unwrap_or_default(),
+            absolute_axes: device
+                .supported_absolute_axes()
+                .map(|absolute_axes| absolute_axes.iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Returns the input device with the given identity. The locations where the input device has been
+/// found before are tried first, all input devices are enumerated only if it has been moved.
+fn find_device(backend: &dyn InputBackend, identity: &str, locations: &[String]) -> Option<Box<dyn InputBackendDevice>> {
+    open_device_at(backend, identity, locations).or_else(|| enumerate_device(backend, identity))
+}
+
+/// Opens the input device with the given identity at one of the given locations. The symlinks in
+/// by-id and by-path point to the current device node of the input device.
+fn open_device_at(backend: &dyn InputBackend, identity: &str, locations: &[String]) -> Option<Box<dyn InputBackendDevice>> {
+    let mut device_nodes: Vec<PathBuf> = Vec::new();
+    for location in locations.iter().filter(|location| !location.is_empty()) {
+        let device_node = std::fs::canonicalize(location).unwrap_or_else(|_| PathBuf::from(location));
+        if !device_nodes.contains(&device_node) {
+            device_nodes.push(device_node);
+        }
+    }
+    device_nodes
+        .iter()
+        .filter_map(|device_node| backend.open(device_node).ok())
+        .find(|device| identity_of(device.as_ref()).identity == identity)
+}
+
+/// Opens all input devices and returns the input device with the given identity.
+fn enumerate_device(backend: &dyn InputBackend, identity: &str) -> Option<Box<dyn InputBackendDevice>> {
+    backend.enumerate().into_iter().find(|device| identity_of(device.as_ref()).identity == identity)
+}
+
+/// Returns the locations where the input device has been found the last time: the device node
+/// and the symlinks in by-id and by-path.
+fn locations_of(entity_instance: &ReactiveEntityInstance) -> Vec<String> {
+    [InputDeviceProperties::DEVICE_NODE, InputDeviceProperties::BY_ID, InputDeviceProperties::BY_PATH]
+        .into_iter()
+        .filter_map(|property| entity_instance.as_string(property))
+        .collect()
+}
+
+/// Publishes whether the input device is connected.
+fn set_connected(entity_instance: &ReactiveEntityInstance, connected: bool) {
+    if entity_instance.as_bool(InputDeviceProperties::CONNECTED) != Some(connected) {
+        entity_instance.set(InputDeviceProperties::CONNECTED.to_string(), json!(connected));
+    }
+}
+
+/// Publishes the collected events of a completed frame. First the entities of the keys, LEDs,
+/// axes and switches are updated one after another, then the whole frame is published at once.
+/// Observers of the property `frame` therefore see the complete state of the frame.
+fn publish_frame(frame: &mut Vec<Value>, property_event: &ReactivePropertyInstance, property_frame: &ReactivePropertyInstance) {
+    if frame.is_empty() {
+        return;
+    }
+    for payload in frame.iter() {
+        property_event.set(payload.clone());
+    }
+    property_frame.set(Value::Array(frame.drain(..).collect()));
+}
+
+/// Reads the current state of the device and publishes it as events, so that the entities which
+/// have missed events are up to date again. The state is queried by the handle of the reader.
+/// Only the state of the keys, LEDs, axes and switches which exist is published.
+fn resync<F: Fn(&str, i64) -> bool>(
+    event_stream: &dyn InputBackendEventStream,
+    state_codes: &StateCodes,
+    property_event: &ReactivePropertyInstance,
+    exists: F,
+) {
+    let timestamp = to_timestamp(SystemTime::now());
+    match event_stream.key_state() {
+        Ok(key_state) => {
+            for key in state_codes.keys.iter().filter(|key| exists(INPUT_EVENT_KIND_KEY_EVENT, key.code() as i64)) {
+                property_event.set(json!({
+                    INPUT_EVENT_KIND: INPUT_EVENT_KIND_KEY_EVENT,
+                    KEY_EVENT_KEY_CODE: key.code(),
+                    INPUT_EVENT_VALUE: if key_state.contains(*key) { 1 } else { 0 },
+                    INPUT_EVENT_TIMESTAMP: timestamp
+                }));
+            }
+        }
+        Err(e) => error!("Failed to query the key state: {}", e),
+    }
+    match event_stream.led_state() {
+        Ok(led_state) => {
+            for led_type in state_codes.leds.iter().filter(|led_type| exists(INPUT_EVENT_KIND_LED_EVENT, led_type.0 as i64)) {
+                property_event.set(json!({
+                    INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
+                    LED_EVENT_LED_TYPE: led_type.0,
+                    INPUT_EVENT_VALUE: if led_state.contains(*led_type) { 1 } else { 0 },
+                    INPUT_EVENT_TIMESTAMP: timestamp
+                }));
+            }
+        }
+        Err(e) => error!("Failed to query the LED state: {}", e),
+    }
+    match event_stream.switch_state() {
+        Ok(switch_state) => {
+            for switch_type in state_codes
+                .switches
+                .iter()
+                .filter(|switch_type| exists(INPUT_EVENT_KIND_SWITCH_EVENT, switch_type.0 as i64))
+            {
+                property_event.set(json!({
+                    INPUT_EVENT_KIND: INPUT_EVENT_KIND_SWITCH_EVENT,
+                    SWITCH_EVENT_SWITCH_TYPE: switch_type.0,
+                    INPUT_EVENT_VALUE: if switch_state.contains(*switch_type) { 1 } else { 0 },
+                    INPUT_EVENT_TIMESTAMP: timestamp
+                }));
+            }
+        }
+        Err(e) => error!("Failed to query the switch state: {}", e),
+    }
+    for absolute_axis_type in state_codes
+        .absolute_axes
+        .iter()
+        .filter(|absolute_axis_type| exists(INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, absolute_axis_type.0 as i64))
+    {
+        if let Ok(absolute_axis_info) = event_stream.absolute_axis_info(*absolute_axis_type) {
+            property_event.set(json!({
+                INPUT_EVENT_KIND: INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+                ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE: absolute_axis_type.0,
+                ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE: absolute_axis_info.normalize(absolute_axis_info.value),
+                INPUT_EVENT_VALUE: absolute_axis_info.value,
+                INPUT_EVENT_TIMESTAMP: timestamp
+            }));
+        }
+    }
+}
+
+/// Publishes the reason why the last events couldn't be sent. An empty string means that the
+/// last events have been sent successfully. The error itself is reported by the caller.
+pub fn set_send_error<S: Into<String>>(entity_instance: &ReactiveEntityInstance, property_name: S, send_error: String) {
+    let property_name = property_name.into();
+    if entity_instance.as_string(property_name.clone()).as_ref() != Some(&send_error) {
+        entity_instance.set(property_name, json!(send_error));
+    }
+}
+
+/// Returns true, if events of the given event type are written to the device node of the
+/// physical input device.
+fn is_output_event_type(event_type: EventType) -> bool {
+    event_type == EventType::LED || event_type == EventType::SOUND || event_type == EventType::FORCEFEEDBACK
 }
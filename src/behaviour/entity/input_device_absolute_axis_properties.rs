@@ -14,8 +14,26 @@ pub enum InputDeviceAbsoluteAxisProperties {
     ABSOLUTE_AXIS,
     #[strum(serialize = "absolute_axis_type")]
     ABSOLUTE_AXIS_TYPE,
+    #[strum(serialize = "minimum")]
+    MINIMUM,
+    #[strum(serialize = "maximum")]
+    MAXIMUM,
+    #[strum(serialize = "fuzz")]
+    FUZZ,
+    #[strum(serialize = "flat")]
+    FLAT,
+    #[strum(serialize = "resolution")]
+    RESOLUTION,
     #[strum(serialize = "state")]
     STATE,
+    #[strum(serialize = "set_state")]
+    SET_STATE,
+    #[strum(serialize = "normalized")]
+    NORMALIZED,
+    #[strum(serialize = "last_changed")]
+    LAST_CHANGED,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceAbsoluteAxisProperties {
@@ -25,7 +43,16 @@ impl InputDeviceAbsoluteAxisProperties {
             InputDeviceAbsoluteAxisProperties::LABEL => json!(String::new()),
             InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS => json!(String::new()),
             InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE => json!(-1),
+            InputDeviceAbsoluteAxisProperties::MINIMUM => json!(0),
+            InputDeviceAbsoluteAxisProperties::MAXIMUM => json!(0),
+            InputDeviceAbsoluteAxisProperties::FUZZ => json!(0),
+            InputDeviceAbsoluteAxisProperties::FLAT => json!(0),
+            InputDeviceAbsoluteAxisProperties::RESOLUTION => json!(0),
             InputDeviceAbsoluteAxisProperties::STATE => json!(0),
+            InputDeviceAbsoluteAxisProperties::SET_STATE => json!(0),
+            InputDeviceAbsoluteAxisProperties::NORMALIZED => json!(0.0),
+            InputDeviceAbsoluteAxisProperties::LAST_CHANGED => json!(0),
+            InputDeviceAbsoluteAxisProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -34,7 +61,16 @@ impl InputDeviceAbsoluteAxisProperties {
             NamedProperty::from(InputDeviceAbsoluteAxisProperties::LABEL),
             NamedProperty::from(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS),
             NamedProperty::from(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::MINIMUM),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::MAXIMUM),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::FUZZ),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::FLAT),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::RESOLUTION),
             NamedProperty::from(InputDeviceAbsoluteAxisProperties::STATE),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::SET_STATE),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::NORMALIZED),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::LAST_CHANGED),
+            NamedProperty::from(InputDeviceAbsoluteAxisProperties::ERROR),
         ]
     }
 }
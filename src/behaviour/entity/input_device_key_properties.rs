@@ -16,8 +16,12 @@ pub enum InputDeviceKeyProperties {
     KEY_CODE,
     #[strum(serialize = "key_down")]
     KEY_DOWN,
+    #[strum(serialize = "last_changed")]
+    LAST_CHANGED,
     #[strum(serialize = "set_key_down")]
     SET_KEY_DOWN,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceKeyProperties {
@@ -28,7 +32,9 @@ impl InputDeviceKeyProperties {
             InputDeviceKeyProperties::KEY => json!(String::new()),
             InputDeviceKeyProperties::KEY_CODE => json!(-1),
             InputDeviceKeyProperties::KEY_DOWN => json!(false),
+            InputDeviceKeyProperties::LAST_CHANGED => json!(0),
             InputDeviceKeyProperties::SET_KEY_DOWN => json!(false),
+            InputDeviceKeyProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -38,7 +44,9 @@ impl InputDeviceKeyProperties {
             NamedProperty::from(InputDeviceKeyProperties::KEY),
             NamedProperty::from(InputDeviceKeyProperties::KEY_CODE),
             NamedProperty::from(InputDeviceKeyProperties::KEY_DOWN),
+            NamedProperty::from(InputDeviceKeyProperties::LAST_CHANGED),
             NamedProperty::from(InputDeviceKeyProperties::SET_KEY_DOWN),
+            NamedProperty::from(InputDeviceKeyProperties::ERROR),
         ]
     }
 }
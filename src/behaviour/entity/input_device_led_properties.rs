@@ -16,8 +16,12 @@ pub enum InputDeviceLedProperties {
     LED_TYPE,
     #[strum(serialize = "state")]
     STATE,
+    #[strum(serialize = "last_changed")]
+    LAST_CHANGED,
     #[strum(serialize = "set_state")]
     SET_STATE,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceLedProperties {
@@ -28,7 +32,9 @@ impl InputDeviceLedProperties {
             InputDeviceLedProperties::LED => json!(String::new()),
             InputDeviceLedProperties::LED_TYPE => json!(-1),
             InputDeviceLedProperties::STATE => json!(false),
+            InputDeviceLedProperties::LAST_CHANGED => json!(0),
             InputDeviceLedProperties::SET_STATE => json!(false),
+            InputDeviceLedProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -38,7 +44,9 @@ impl InputDeviceLedProperties {
             NamedProperty::from(InputDeviceLedProperties::LED),
             NamedProperty::from(InputDeviceLedProperties::LED_TYPE),
             NamedProperty::from(InputDeviceLedProperties::STATE),
+            NamedProperty::from(InputDeviceLedProperties::LAST_CHANGED),
             NamedProperty::from(InputDeviceLedProperties::SET_STATE),
+            NamedProperty::from(InputDeviceLedProperties::ERROR),
         ]
     }
 }
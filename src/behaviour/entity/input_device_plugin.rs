@@ -0,0 +1,69 @@
+use std::convert::AsRef;
+use std::sync::Arc;
+
+use log::{debug, trace};
+use serde_json::Value;
+
+use crate::api::{InputDeviceHotplugManager, InputDeviceManager, INPUT_DEVICE_PLUGIN};
+use crate::behaviour::entity::InputDevicePluginProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::model::ReactiveEntityInstance;
+use crate::reactive::entity::Disconnectable;
+
+/// Reloads the configuration of the input devices if the property `reload_config` is set to true.
+pub struct InputDevicePlugin {
+    pub entity: Arc<ReactiveEntityInstance>,
+
+    pub handle_id: u128,
+}
+
+impl InputDevicePlugin {
+    pub fn new<'a>(
+        e: Arc<ReactiveEntityInstance>,
+        input_device_manager: Arc<dyn InputDeviceManager>,
+        input_device_hotplug_manager: Arc<dyn InputDeviceHotplugManager>,
+    ) -> Result<InputDevicePlugin, InputDeviceError> {
+        let property_reload_config = e
+            .properties
+            .get(InputDevicePluginProperties::RELOAD_CONFIG.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(InputDevicePluginProperties::RELOAD_CONFIG.to_string())))?;
+        let handle_id = property_reload_config.id.as_u128();
+
+        property_reload_config.stream.read().unwrap().observe_with_handle(
+            move |reload_config: &Value| {
+                if !reload_config.as_bool().unwrap_or(false) {
+                    return;
+                }
+                debug!("Reloading the configuration of the input devices");
+                // The input device path or hotplug may have changed
+                input_device_hotplug_manager.stop();
+                input_device_manager.load_config();
+                input_device_hotplug_manager.start();
+            },
+            handle_id,
+        );
+
+        Ok(InputDevicePlugin { entity: e.clone(), handle_id })
+    }
+
+    pub fn type_name(&self) -> String {
+        self.entity.type_name.clone()
+    }
+}
+
+impl Disconnectable for InputDevicePlugin {
+    fn disconnect(&self) {
+        trace!("Disconnecting {} with id {}", INPUT_DEVICE_PLUGIN, self.entity.id);
+        let property = self.entity.properties.get(InputDevicePluginProperties::RELOAD_CONFIG.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+        }
+    }
+}
+
+/// Automatically disconnect streams on destruction
+impl Drop for InputDevicePlugin {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
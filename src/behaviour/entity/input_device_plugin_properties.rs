@@ -0,0 +1,71 @@
+use indradb::{Identifier, NamedProperty};
+use inexor_rgf_core_reactive::NamedProperties;
+use serde_json::{json, Value};
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+#[allow(non_camel_case_types)]
+#[derive(AsRefStr, IntoStaticStr, Display)]
+pub enum InputDevicePluginProperties {
+    #[strum(serialize = "name")]
+    NAME,
+    #[strum(serialize = "label")]
+    LABEL,
+    #[strum(serialize = "managed_devices")]
+    MANAGED_DEVICES,
+    #[strum(serialize = "events_received")]
+    EVENTS_RECEIVED,
+    #[strum(serialize = "events_sent")]
+    EVENTS_SENT,
+    #[strum(serialize = "dropped_send_requests")]
+    DROPPED_SEND_REQUESTS,
+    #[strum(serialize = "reader_restarts")]
+    READER_RESTARTS,
+    #[strum(serialize = "last_event")]
+    LAST_EVENT,
+    #[strum(serialize = "reload_config")]
+    RELOAD_CONFIG,
+}
+
+impl InputDevicePluginProperties {
+    pub fn default_value(&self) -> Value {
+        match self {
+            InputDevicePluginProperties::NAME => json!(String::new()),
+            InputDevicePluginProperties::LABEL => json!(String::new()),
+            InputDevicePluginProperties::MANAGED_DEVICES => json!(0),
+            InputDevicePluginProperties::EVENTS_RECEIVED => json!({}),
+            InputDevicePluginProperties::EVENTS_SENT => json!(0),
+            InputDevicePluginProperties::DROPPED_SEND_REQUESTS => json!(0),
+            InputDevicePluginProperties::READER_RESTARTS => json!(0),
+            InputDevicePluginProperties::LAST_EVENT => json!(0),
+            InputDevicePluginProperties::RELOAD_CONFIG => json!(false),
+        }
+    }
+    pub fn properties() -> NamedProperties {
+        vec![
+            NamedProperty::from(InputDevicePluginProperties::NAME),
+            NamedProperty::from(InputDevicePluginProperties::LABEL),
+            NamedProperty::from(InputDevicePluginProperties::MANAGED_DEVICES),
+            NamedProperty::from(InputDevicePluginProperties::EVENTS_RECEIVED),
+            NamedProperty::from(InputDevicePluginProperties::EVENTS_SENT),
+            NamedProperty::from(InputDevicePluginProperties::DROPPED_SEND_REQUESTS),
+            NamedProperty::from(InputDevicePluginProperties::READER_RESTARTS),
+            NamedProperty::from(InputDevicePluginProperties::LAST_EVENT),
+            NamedProperty::from(InputDevicePluginProperties::RELOAD_CONFIG),
+        ]
+    }
+}
+
+impl From<InputDevicePluginProperties> for NamedProperty {
+    fn from(p: InputDevicePluginProperties) -> Self {
+        NamedProperty {
+            name: Identifier::new(p.to_string()).unwrap(),
+            value: p.default_value(),
+        }
+    }
+}
+
+impl From<InputDevicePluginProperties> for String {
+    fn from(p: InputDevicePluginProperties) -> Self {
+        p.to_string()
+    }
+}
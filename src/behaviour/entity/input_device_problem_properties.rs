@@ -0,0 +1,59 @@
+use indradb::{Identifier, NamedProperty};
+use inexor_rgf_core_reactive::NamedProperties;
+use serde_json::{json, Value};
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+#[allow(non_camel_case_types)]
+#[derive(AsRefStr, IntoStaticStr, Display)]
+pub enum InputDeviceProblemProperties {
+    #[strum(serialize = "name")]
+    NAME,
+    #[strum(serialize = "label")]
+    LABEL,
+    #[strum(serialize = "device_node")]
+    DEVICE_NODE,
+    #[strum(serialize = "problem")]
+    PROBLEM,
+    #[strum(serialize = "message")]
+    MESSAGE,
+    #[strum(serialize = "hint")]
+    HINT,
+}
+
+impl InputDeviceProblemProperties {
+    pub fn default_value(&self) -> Value {
+        match self {
+            InputDeviceProblemProperties::NAME => json!(String::new()),
+            InputDeviceProblemProperties::LABEL => json!(String::new()),
+            InputDeviceProblemProperties::DEVICE_NODE => json!(String::new()),
+            InputDeviceProblemProperties::PROBLEM => json!(String::new()),
+            InputDeviceProblemProperties::MESSAGE => json!(String::new()),
+            InputDeviceProblemProperties::HINT => json!(String::new()),
+        }
+    }
+    pub fn properties() -> NamedProperties {
+        vec![
+            NamedProperty::from(InputDeviceProblemProperties::NAME),
+            NamedProperty::from(InputDeviceProblemProperties::LABEL),
+            NamedProperty::from(InputDeviceProblemProperties::DEVICE_NODE),
+            NamedProperty::from(InputDeviceProblemProperties::PROBLEM),
+            NamedProperty::from(InputDeviceProblemProperties::MESSAGE),
+            NamedProperty::from(InputDeviceProblemProperties::HINT),
+        ]
+    }
+}
+
+impl From<InputDeviceProblemProperties> for NamedProperty {
+    fn from(p: InputDeviceProblemProperties) -> Self {
+        NamedProperty {
+            name: Identifier::new(p.to_string()).unwrap(),
+            value: p.default_value(),
+        }
+    }
+}
+
+impl From<InputDeviceProblemProperties> for String {
+    fn from(p: InputDeviceProblemProperties) -> Self {
+        p.to_string()
+    }
+}
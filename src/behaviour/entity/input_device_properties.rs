@@ -12,6 +12,42 @@ pub enum InputDeviceProperties {
     LABEL,
     #[strum(serialize = "physical_path")]
     PHYSICAL_PATH,
+    #[strum(serialize = "identity")]
+    IDENTITY,
+    #[strum(serialize = "identity_strategy")]
+    IDENTITY_STRATEGY,
+    #[strum(serialize = "bus_type")]
+    BUS_TYPE,
+    #[strum(serialize = "unique_id")]
+    UNIQUE_ID,
+    #[strum(serialize = "device_node")]
+    DEVICE_NODE,
+    #[strum(serialize = "by_id")]
+    BY_ID,
+    #[strum(serialize = "by_path")]
+    BY_PATH,
+    #[strum(serialize = "sysfs_path")]
+    SYSFS_PATH,
+    #[strum(serialize = "input_properties")]
+    INPUT_PROPERTIES,
+    #[strum(serialize = "device_class")]
+    DEVICE_CLASS,
+    #[strum(serialize = "supported_events")]
+    SUPPORTED_EVENTS,
+    #[strum(serialize = "supported_keys")]
+    SUPPORTED_KEYS,
+    #[strum(serialize = "supported_leds")]
+    SUPPORTED_LEDS,
+    #[strum(serialize = "supported_relative_axes")]
+    SUPPORTED_RELATIVE_AXES,
+    #[strum(serialize = "supported_absolute_axes")]
+    SUPPORTED_ABSOLUTE_AXES,
+    #[strum(serialize = "supported_switches")]
+    SUPPORTED_SWITCHES,
+    #[strum(serialize = "supported_force_feedback_effects")]
+    SUPPORTED_FORCE_FEEDBACK_EFFECTS,
+    #[strum(serialize = "supported_sounds")]
+    SUPPORTED_SOUNDS,
     #[strum(serialize = "driver_version")]
     DRIVER_VERSION,
     #[strum(serialize = "vendor")]
@@ -22,8 +58,22 @@ pub enum InputDeviceProperties {
     VERSION,
     #[strum(serialize = "event")]
     EVENT,
+    #[strum(serialize = "frame")]
+    FRAME,
+    #[strum(serialize = "frame_mode")]
+    FRAME_MODE,
     #[strum(serialize = "send_event")]
     SEND_EVENT,
+    #[strum(serialize = "send_error")]
+    SEND_ERROR,
+    #[strum(serialize = "grab")]
+    GRAB,
+    #[strum(serialize = "query")]
+    QUERY,
+    #[strum(serialize = "connected")]
+    CONNECTED,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceProperties {
@@ -32,12 +82,37 @@ impl InputDeviceProperties {
             InputDeviceProperties::NAME => json!(String::new()),
             InputDeviceProperties::LABEL => json!(String::new()),
             InputDeviceProperties::PHYSICAL_PATH => json!(""),
+            InputDeviceProperties::IDENTITY => json!(String::new()),
+            InputDeviceProperties::IDENTITY_STRATEGY => json!(String::new()),
+            InputDeviceProperties::BUS_TYPE => json!(String::new()),
+            InputDeviceProperties::UNIQUE_ID => json!(String::new()),
+            InputDeviceProperties::DEVICE_NODE => json!(String::new()),
+            InputDeviceProperties::BY_ID => json!(String::new()),
+            InputDeviceProperties::BY_PATH => json!(String::new()),
+            InputDeviceProperties::SYSFS_PATH => json!(String::new()),
+            InputDeviceProperties::INPUT_PROPERTIES => json!([]),
+            InputDeviceProperties::DEVICE_CLASS => json!([]),
+            InputDeviceProperties::SUPPORTED_EVENTS => json!([]),
+            InputDeviceProperties::SUPPORTED_KEYS => json!([]),
+            InputDeviceProperties::SUPPORTED_LEDS => json!([]),
+            InputDeviceProperties::SUPPORTED_RELATIVE_AXES => json!([]),
+            InputDeviceProperties::SUPPORTED_ABSOLUTE_AXES => json!([]),
+            InputDeviceProperties::SUPPORTED_SWITCHES => json!([]),
+            InputDeviceProperties::SUPPORTED_FORCE_FEEDBACK_EFFECTS => json!([]),
+            InputDeviceProperties::SUPPORTED_SOUNDS => json!([]),
             InputDeviceProperties::DRIVER_VERSION => json!("1.0.0"),
             InputDeviceProperties::VENDOR => json!(0),
             InputDeviceProperties::PRODUCT => json!(0),
             InputDeviceProperties::VERSION => json!(0),
             InputDeviceProperties::EVENT => json!({}),
+            InputDeviceProperties::FRAME => json!([]),
+            InputDeviceProperties::FRAME_MODE => json!(false),
             InputDeviceProperties::SEND_EVENT => json!({}),
+            InputDeviceProperties::SEND_ERROR => json!(String::new()),
+            InputDeviceProperties::GRAB => json!(false),
+            InputDeviceProperties::QUERY => json!({}),
+            InputDeviceProperties::CONNECTED => json!(false),
+            InputDeviceProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -45,12 +120,37 @@ impl InputDeviceProperties {
             NamedProperty::from(InputDeviceProperties::NAME),
             NamedProperty::from(InputDeviceProperties::LABEL),
             NamedProperty::from(InputDeviceProperties::PHYSICAL_PATH),
+            NamedProperty::from(InputDeviceProperties::IDENTITY),
+            NamedProperty::from(InputDeviceProperties::IDENTITY_STRATEGY),
+            NamedProperty::from(InputDeviceProperties::BUS_TYPE),
+            NamedProperty::from(InputDeviceProperties::UNIQUE_ID),
+            NamedProperty::from(InputDeviceProperties::DEVICE_NODE),
+            NamedProperty::from(InputDeviceProperties::BY_ID),
+            NamedProperty::from(InputDeviceProperties::BY_PATH),
+            NamedProperty::from(InputDeviceProperties::SYSFS_PATH),
+            NamedProperty::from(InputDeviceProperties::INPUT_PROPERTIES),
+            NamedProperty::from(InputDeviceProperties::DEVICE_CLASS),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_EVENTS),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_KEYS),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_LEDS),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_RELATIVE_AXES),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_ABSOLUTE_AXES),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_SWITCHES),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_FORCE_FEEDBACK_EFFECTS),
+            NamedProperty::from(InputDeviceProperties::SUPPORTED_SOUNDS),
             NamedProperty::from(InputDeviceProperties::DRIVER_VERSION),
             NamedProperty::from(InputDeviceProperties::VENDOR),
             NamedProperty::from(InputDeviceProperties::PRODUCT),
             NamedProperty::from(InputDeviceProperties::VERSION),
             NamedProperty::from(InputDeviceProperties::EVENT),
+            NamedProperty::from(InputDeviceProperties::FRAME),
+            NamedProperty::from(InputDeviceProperties::FRAME_MODE),
             NamedProperty::from(InputDeviceProperties::SEND_EVENT),
+            NamedProperty::from(InputDeviceProperties::SEND_ERROR),
+            NamedProperty::from(InputDeviceProperties::GRAB),
+            NamedProperty::from(InputDeviceProperties::QUERY),
+            NamedProperty::from(InputDeviceProperties::CONNECTED),
+            NamedProperty::from(InputDeviceProperties::ERROR),
         ]
     }
 }
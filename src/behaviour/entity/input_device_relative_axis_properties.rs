@@ -16,6 +16,12 @@ pub enum InputDeviceRelativeAxisProperties {
     RELATIVE_AXIS_TYPE,
     #[strum(serialize = "state")]
     STATE,
+    #[strum(serialize = "last_changed")]
+    LAST_CHANGED,
+    #[strum(serialize = "set_state")]
+    SET_STATE,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceRelativeAxisProperties {
@@ -26,6 +32,9 @@ impl InputDeviceRelativeAxisProperties {
             InputDeviceRelativeAxisProperties::RELATIVE_AXIS => json!(String::new()),
             InputDeviceRelativeAxisProperties::RELATIVE_AXIS_TYPE => json!(-1),
             InputDeviceRelativeAxisProperties::STATE => json!(0),
+            InputDeviceRelativeAxisProperties::LAST_CHANGED => json!(0),
+            InputDeviceRelativeAxisProperties::SET_STATE => json!(0),
+            InputDeviceRelativeAxisProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -35,6 +44,9 @@ impl InputDeviceRelativeAxisProperties {
             NamedProperty::from(InputDeviceRelativeAxisProperties::RELATIVE_AXIS),
             NamedProperty::from(InputDeviceRelativeAxisProperties::RELATIVE_AXIS_TYPE),
             NamedProperty::from(InputDeviceRelativeAxisProperties::STATE),
+            NamedProperty::from(InputDeviceRelativeAxisProperties::LAST_CHANGED),
+            NamedProperty::from(InputDeviceRelativeAxisProperties::SET_STATE),
+            NamedProperty::from(InputDeviceRelativeAxisProperties::ERROR),
         ]
     }
 }
@@ -16,6 +16,12 @@ pub enum InputDeviceSwitchProperties {
     SWITCH_TYPE,
     #[strum(serialize = "state")]
     STATE,
+    #[strum(serialize = "last_changed")]
+    LAST_CHANGED,
+    #[strum(serialize = "set_state")]
+    SET_STATE,
+    #[strum(serialize = "error")]
+    ERROR,
 }
 
 impl InputDeviceSwitchProperties {
@@ -26,6 +32,9 @@ impl InputDeviceSwitchProperties {
             InputDeviceSwitchProperties::SWITCH => json!(String::new()),
             InputDeviceSwitchProperties::SWITCH_TYPE => json!(-1),
             InputDeviceSwitchProperties::STATE => json!(0),
+            InputDeviceSwitchProperties::LAST_CHANGED => json!(0),
+            InputDeviceSwitchProperties::SET_STATE => json!(false),
+            InputDeviceSwitchProperties::ERROR => json!(String::new()),
         }
     }
     pub fn properties() -> NamedProperties {
@@ -35,6 +44,9 @@ impl InputDeviceSwitchProperties {
             NamedProperty::from(InputDeviceSwitchProperties::SWITCH),
             NamedProperty::from(InputDeviceSwitchProperties::SWITCH_TYPE),
             NamedProperty::from(InputDeviceSwitchProperties::STATE),
+            NamedProperty::from(InputDeviceSwitchProperties::LAST_CHANGED),
+            NamedProperty::from(InputDeviceSwitchProperties::SET_STATE),
+            NamedProperty::from(InputDeviceSwitchProperties::ERROR),
         ]
     }
 }
@@ -1,9 +1,13 @@
 pub use input_device_absolute_axis_properties::*;
 pub use input_device_key_properties::*;
 pub use input_device_led_properties::*;
+pub use input_device_plugin_properties::*;
+pub use input_device_problem_properties::*;
 pub use input_device_properties::*;
 pub use input_device_relative_axis_properties::*;
 pub use input_device_switch_properties::*;
+pub use physical_device_properties::*;
+pub use virtual_device_properties::*;
 
 pub mod entity_behaviour_provider;
 
@@ -11,6 +15,13 @@ pub mod input_device;
 pub mod input_device_absolute_axis_properties;
 pub mod input_device_key_properties;
 pub mod input_device_led_properties;
+pub mod input_device_plugin;
+pub mod input_device_plugin_properties;
+pub mod input_device_problem_properties;
 pub mod input_device_properties;
 pub mod input_device_relative_axis_properties;
 pub mod input_device_switch_properties;
+pub mod physical_device;
+pub mod physical_device_properties;
+pub mod virtual_device;
+pub mod virtual_device_properties;
@@ -0,0 +1,72 @@
+use std::convert::AsRef;
+use std::sync::Arc;
+
+use log::trace;
+use serde_json::Value;
+
+use crate::api::{InputDevicePhysicalDeviceManager, PHYSICAL_DEVICE};
+use crate::behaviour::entity::PhysicalDeviceProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::kind_and_code_of;
+use crate::model::ReactiveEntityInstance;
+use crate::reactive::entity::Disconnectable;
+
+/// Forwards the queries of keys, LEDs, axes and switches to the interfaces of the physical device
+/// which support them.
+pub struct PhysicalDevice {
+    pub entity: Arc<ReactiveEntityInstance>,
+
+    pub handle_id: u128,
+}
+
+impl PhysicalDevice {
+    pub fn new<'a>(
+        e: Arc<ReactiveEntityInstance>,
+        input_device_physical_device_manager: Arc<dyn InputDevicePhysicalDeviceManager>,
+    ) -> Result<PhysicalDevice, InputDeviceError> {
+        let physical_device_id = e.id;
+        let property_query = e
+            .properties
+            .get(PhysicalDeviceProperties::QUERY.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(PhysicalDeviceProperties::QUERY.to_string())))?;
+        let handle_id = property_query.id.as_u128();
+
+        let query_entity_instance = e.clone();
+        property_query.stream.read().unwrap().observe_with_handle(
+            move |query: &Value| match kind_and_code_of(query) {
+                Some((input_event_kind, code)) => {
+                    if !input_device_physical_device_manager.query(physical_device_id, input_event_kind, code, query) {
+                        trace!("Query {} of physical device {} has no result", query, physical_device_id);
+                    }
+                }
+                None => {
+                    report_error(&query_entity_instance, InputDeviceError::InvalidPayload(format!("Invalid query {}", query)));
+                }
+            },
+            handle_id,
+        );
+
+        Ok(PhysicalDevice { entity: e.clone(), handle_id })
+    }
+
+    pub fn type_name(&self) -> String {
+        self.entity.type_name.clone()
+    }
+}
+
+impl Disconnectable for PhysicalDevice {
+    fn disconnect(&self) {
+        trace!("Disconnecting {} with id {}", PHYSICAL_DEVICE, self.entity.id);
+        let property = self.entity.properties.get(PhysicalDeviceProperties::QUERY.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+        }
+    }
+}
+
+/// Automatically disconnect streams on destruction
+impl Drop for PhysicalDevice {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
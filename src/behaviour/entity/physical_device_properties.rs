@@ -0,0 +1,91 @@
+use indradb::{Identifier, NamedProperty};
+use inexor_rgf_core_reactive::NamedProperties;
+use serde_json::{json, Value};
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+#[allow(non_camel_case_types)]
+#[derive(AsRefStr, IntoStaticStr, Display)]
+pub enum PhysicalDeviceProperties {
+    #[strum(serialize = "name")]
+    NAME,
+    #[strum(serialize = "label")]
+    LABEL,
+    #[strum(serialize = "identity")]
+    IDENTITY,
+    #[strum(serialize = "grouped_by")]
+    GROUPED_BY,
+    #[strum(serialize = "vendor")]
+    VENDOR,
+    #[strum(serialize = "product")]
+    PRODUCT,
+    #[strum(serialize = "interfaces")]
+    INTERFACES,
+    #[strum(serialize = "supported_keys")]
+    SUPPORTED_KEYS,
+    #[strum(serialize = "supported_leds")]
+    SUPPORTED_LEDS,
+    #[strum(serialize = "supported_relative_axes")]
+    SUPPORTED_RELATIVE_AXES,
+    #[strum(serialize = "supported_absolute_axes")]
+    SUPPORTED_ABSOLUTE_AXES,
+    #[strum(serialize = "supported_switches")]
+    SUPPORTED_SWITCHES,
+    #[strum(serialize = "query")]
+    QUERY,
+    #[strum(serialize = "error")]
+    ERROR,
+}
+
+impl PhysicalDeviceProperties {
+    pub fn default_value(&self) -> Value {
+        match self {
+            PhysicalDeviceProperties::NAME => json!(String::new()),
+            PhysicalDeviceProperties::LABEL => json!(String::new()),
+            PhysicalDeviceProperties::IDENTITY => json!(String::new()),
+            PhysicalDeviceProperties::GROUPED_BY => json!(String::new()),
+            PhysicalDeviceProperties::VENDOR => json!(0),
+            PhysicalDeviceProperties::PRODUCT => json!(0),
+            PhysicalDeviceProperties::INTERFACES => json!(0),
+            PhysicalDeviceProperties::SUPPORTED_KEYS => json!([]),
+            PhysicalDeviceProperties::SUPPORTED_LEDS => json!([]),
+            PhysicalDeviceProperties::SUPPORTED_RELATIVE_AXES => json!([]),
+            PhysicalDeviceProperties::SUPPORTED_ABSOLUTE_AXES => json!([]),
+            PhysicalDeviceProperties::SUPPORTED_SWITCHES => json!([]),
+            PhysicalDeviceProperties::QUERY => json!({}),
+            PhysicalDeviceProperties::ERROR => json!(String::new()),
+        }
+    }
+    pub fn properties() -> NamedProperties {
+        vec![
+            NamedProperty::from(PhysicalDeviceProperties::NAME),
+            NamedProperty::from(PhysicalDeviceProperties::LABEL),
+            NamedProperty::from(PhysicalDeviceProperties::IDENTITY),
+            NamedProperty::from(PhysicalDeviceProperties::GROUPED_BY),
+            NamedProperty::from(PhysicalDeviceProperties::VENDOR),
+            NamedProperty::from(PhysicalDeviceProperties::PRODUCT),
+            NamedProperty::from(PhysicalDeviceProperties::INTERFACES),
+            NamedProperty::from(PhysicalDeviceProperties::SUPPORTED_KEYS),
+            NamedProperty::from(PhysicalDeviceProperties::SUPPORTED_LEDS),
+            NamedProperty::from(PhysicalDeviceProperties::SUPPORTED_RELATIVE_AXES),
+            NamedProperty::from(PhysicalDeviceProperties::SUPPORTED_ABSOLUTE_AXES),
+            NamedProperty::from(PhysicalDeviceProperties::SUPPORTED_SWITCHES),
+            NamedProperty::from(PhysicalDeviceProperties::QUERY),
+            NamedProperty::from(PhysicalDeviceProperties::ERROR),
+        ]
+    }
+}
+
+impl From<PhysicalDeviceProperties> for NamedProperty {
+    fn from(p: PhysicalDeviceProperties) -> Self {
+        NamedProperty {
+            name: Identifier::new(p.to_string()).unwrap(),
+            value: p.default_value(),
+        }
+    }
+}
+
+impl From<PhysicalDeviceProperties> for String {
+    fn from(p: PhysicalDeviceProperties) -> Self {
+        p.to_string()
+    }
+}
@@ -0,0 +1,86 @@
+use std::convert::AsRef;
+use std::sync::Arc;
+
+use log::trace;
+use serde_json::Value;
+
+use crate::api::{InputDeviceStatusManager, InputDeviceVirtualDeviceManager};
+use crate::behaviour::entity::input_device::set_send_error;
+use crate::behaviour::entity::VirtualDeviceProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{to_input_events, without_syn_report};
+use crate::model::ReactiveEntityInstance;
+use crate::reactive::entity::Disconnectable;
+
+pub const VIRTUAL_DEVICE: &'static str = "virtual_device";
+
+/// Emits the events which are set on the property `send_event` on the virtual device.
+pub struct VirtualDevice {
+    pub entity: Arc<ReactiveEntityInstance>,
+
+    pub handle_id: u128,
+}
+
+impl VirtualDevice {
+    pub fn new<'a>(
+        e: Arc<ReactiveEntityInstance>,
+        virtual_device_manager: Arc<dyn InputDeviceVirtualDeviceManager>,
+        status_manager: Arc<dyn InputDeviceStatusManager>,
+    ) -> Result<VirtualDevice, InputDeviceError> {
+        let property_send_event = e
+            .properties
+            .get(VirtualDeviceProperties::SEND_EVENT.as_ref())
+            .ok_or_else(|| report_error(&e, InputDeviceError::MissingProperty(VirtualDeviceProperties::SEND_EVENT.to_string())))?;
+        let handle_id = property_send_event.id.as_u128();
+
+        let virtual_device_id = e.id;
+        let entity_instance = e.clone();
+        property_send_event.stream.read().unwrap().observe_with_handle(
+            move |send_event: &Value| {
+                let result = to_input_events(send_event).and_then(|events| {
+                    // The virtual device completes the frame itself
+                    let events = without_syn_report(events);
+                    virtual_device_manager
+                        .send_events_by_virtual_device(virtual_device_id, &events)
+                        .map(|_| events.len())
+                        .map_err(|e| InputDeviceError::Io(format!("Failed to send events to the virtual device: {}", e)))
+                });
+                let send_error = match result {
+                    Ok(count) => {
+                        status_manager.events_sent(count);
+                        String::new()
+                    }
+                    Err(e) => {
+                        status_manager.send_request_dropped();
+                        report_error(&entity_instance, e).to_string()
+                    }
+                };
+                set_send_error(&entity_instance, VirtualDeviceProperties::SEND_ERROR, send_error);
+            },
+            handle_id,
+        );
+
+        Ok(VirtualDevice { entity: e.clone(), handle_id })
+    }
+
+    pub fn type_name(&self) -> String {
+        self.entity.type_name.clone()
+    }
+}
+
+impl Disconnectable for VirtualDevice {
+    fn disconnect(&self) {
+        trace!("Disconnecting {} with id {}", VIRTUAL_DEVICE, self.entity.id);
+        let property = self.entity.properties.get(VirtualDeviceProperties::SEND_EVENT.as_ref());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+        }
+    }
+}
+
+/// Automatically disconnect streams on destruction
+impl Drop for VirtualDevice {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
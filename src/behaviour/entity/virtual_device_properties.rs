@@ -0,0 +1,55 @@
+use indradb::{Identifier, NamedProperty};
+use inexor_rgf_core_reactive::NamedProperties;
+use serde_json::{json, Value};
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+#[allow(non_camel_case_types)]
+#[derive(AsRefStr, IntoStaticStr, Display)]
+pub enum VirtualDeviceProperties {
+    #[strum(serialize = "name")]
+    NAME,
+    #[strum(serialize = "label")]
+    LABEL,
+    #[strum(serialize = "send_event")]
+    SEND_EVENT,
+    #[strum(serialize = "send_error")]
+    SEND_ERROR,
+    #[strum(serialize = "error")]
+    ERROR,
+}
+
+impl VirtualDeviceProperties {
+    pub fn default_value(&self) -> Value {
+        match self {
+            VirtualDeviceProperties::NAME => json!(String::new()),
+            VirtualDeviceProperties::LABEL => json!(String::new()),
+            VirtualDeviceProperties::SEND_EVENT => json!({}),
+            VirtualDeviceProperties::SEND_ERROR => json!(String::new()),
+            VirtualDeviceProperties::ERROR => json!(String::new()),
+        }
+    }
+    pub fn properties() -> NamedProperties {
+        vec![
+            NamedProperty::from(VirtualDeviceProperties::NAME),
+            NamedProperty::from(VirtualDeviceProperties::LABEL),
+            NamedProperty::from(VirtualDeviceProperties::SEND_EVENT),
+            NamedProperty::from(VirtualDeviceProperties::SEND_ERROR),
+            NamedProperty::from(VirtualDeviceProperties::ERROR),
+        ]
+    }
+}
+
+impl From<VirtualDeviceProperties> for NamedProperty {
+    fn from(p: VirtualDeviceProperties) -> Self {
+        NamedProperty {
+            name: Identifier::new(p.to_string()).unwrap(),
+            value: p.default_value(),
+        }
+    }
+}
+
+impl From<VirtualDeviceProperties> for String {
+    fn from(p: VirtualDeviceProperties) -> Self {
+        p.to_string()
+    }
+}
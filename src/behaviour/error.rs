@@ -0,0 +1,96 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use log::error;
+use serde_json::json;
+
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+
+/// The name of the property which contains the last error of an entity instance.
+pub const ERROR: &'static str = "error";
+
+/// Device or resource busy
+const EBUSY: i32 = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputDeviceError {
+    /// The entity instance doesn't have the property with the given name.
+    MissingProperty(String),
+
+    /// The input device doesn't exist (anymore).
+    DeviceNotFound(String),
+
+    /// The device node exists but isn't accessible, usually because the user isn't a member of
+    /// the group `input`.
+    PermissionDenied(String),
+
+    /// The input device has been grabbed exclusively by another process.
+    DeviceBusy(String),
+
+    /// The value of a property or an event payload is malformed.
+    InvalidPayload(String),
+
+    /// Reading from or writing to the input device failed.
+    Io(String),
+}
+
+impl InputDeviceError {
+    /// Classifies an I/O error which occurred while accessing the given device node.
+    pub fn from_io(device_node: &Path, e: io::Error) -> InputDeviceError {
+        if e.raw_os_error() == Some(EBUSY) {
+            return InputDeviceError::DeviceBusy(device_node.display().to_string());
+        }
+        match e.kind() {
+            io::ErrorKind::NotFound => InputDeviceError::DeviceNotFound(device_node.display().to_string()),
+            io::ErrorKind::PermissionDenied => InputDeviceError::PermissionDenied(device_node.display().to_string()),
+            _ => InputDeviceError::Io(format!("{}: {}", device_node.display(), e)),
+        }
+    }
+
+    /// Returns a short identifier of the kind of the error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InputDeviceError::MissingProperty(_) => "missing_property",
+            InputDeviceError::DeviceNotFound(_) => "device_not_found",
+            InputDeviceError::PermissionDenied(_) => "permission_denied",
+            InputDeviceError::DeviceBusy(_) => "device_busy",
+            InputDeviceError::InvalidPayload(_) => "invalid_payload",
+            InputDeviceError::Io(_) => "io",
+        }
+    }
+
+    /// Returns a hint how the user can solve the problem.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            InputDeviceError::DeviceNotFound(_) => "Check the path of the input device in config/input_devices.toml",
+            InputDeviceError::PermissionDenied(_) => "Add the user to the group input (usermod -aG input <user>) and log in again",
+            InputDeviceError::DeviceBusy(_) => "Another process has grabbed the input device exclusively",
+            _ => "",
+        }
+    }
+}
+
+impl fmt::Display for InputDeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputDeviceError::MissingProperty(property_name) => write!(f, "Missing property {}", property_name),
+            InputDeviceError::DeviceNotFound(device) => write!(f, "The input device {} is not available", device),
+            InputDeviceError::PermissionDenied(device) => write!(f, "Permission denied to access the input device {}", device),
+            InputDeviceError::DeviceBusy(device) => write!(f, "The input device {} is grabbed by another process", device),
+            InputDeviceError::InvalidPayload(message) => write!(f, "Invalid payload: {}", message),
+            InputDeviceError::Io(message) => write!(f, "I/O error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for InputDeviceError {}
+
+/// Logs the error and publishes it on the property `error` of the affected entity instance.
+/// Returns the error, so that it can be propagated.
+pub fn report_error(entity_instance: &ReactiveEntityInstance, error: InputDeviceError) -> InputDeviceError {
+    error!("{} {}: {}", entity_instance.type_name, entity_instance.id, error);
+    entity_instance.set(ERROR.to_string(), json!(error.to_string()));
+    error
+}
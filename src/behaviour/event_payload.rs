@@ -1,5 +1,13 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use evdev::{EventType, InputEvent, Synchronization};
+use serde_json::{json, Value};
+
+use crate::behaviour::error::InputDeviceError;
+
 pub const INPUT_EVENT_KIND: &'static str = "input_event_kind";
 pub const INPUT_EVENT_VALUE: &'static str = "input_event_value";
+pub const INPUT_EVENT_TIMESTAMP: &'static str = "input_event_timestamp";
 
 pub const INPUT_EVENT_KIND_KEY_EVENT: &'static str = "key_event";
 pub const INPUT_EVENT_KIND_LED_EVENT: &'static str = "led_event";
@@ -11,4 +19,99 @@ pub const KEY_EVENT_KEY_CODE: &'static str = "key_event_key_code";
 pub const LED_EVENT_LED_TYPE: &'static str = "led_event_led_type";
 pub const RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE: &'static str = "relative_axis_event_relative_axis_type";
 pub const ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE: &'static str = "absolute_axis_event_absolute_axis_type";
+pub const ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE: &'static str = "absolute_axis_event_normalized_value";
 pub const SWITCH_EVENT_SWITCH_TYPE: &'static str = "switch_event_switch_type";
+
+pub const SEND_EVENT_EVENT_TYPE: &'static str = "event_type";
+pub const SEND_EVENT_CODE: &'static str = "code";
+pub const SEND_EVENT_VALUE: &'static str = "value";
+
+/// Converts the kernel timestamp of an input event into microseconds since the unix epoch.
+pub fn to_timestamp(timestamp: SystemTime) -> u64 {
+    timestamp.duration_since(UNIX_EPOCH).map(|duration| duration.as_micros() as u64).unwrap_or(0)
+}
+
+/// Returns the timestamp of the payload of the property `event`.
+pub fn timestamp_of(event: &Value) -> Value {
+    event.get(INPUT_EVENT_TIMESTAMP).cloned().unwrap_or(json!(0))
+}
+
+/// Returns the kind and the code (key code, LED type, axis type or switch type) of the payload
+/// of the property `event`.
+pub fn kind_and_code_of(event: &Value) -> Option<(&str, i64)> {
+    let input_event_kind = event.get(INPUT_EVENT_KIND)?.as_str()?;
+    let code = match input_event_kind {
+        INPUT_EVENT_KIND_KEY_EVENT => KEY_EVENT_KEY_CODE,
+        INPUT_EVENT_KIND_LED_EVENT => LED_EVENT_LED_TYPE,
+        INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT => RELATIVE_AXIS_EVENT_RELATIVE_AXIS_TYPE,
+        INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT => ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE,
+        INPUT_EVENT_KIND_SWITCH_EVENT => SWITCH_EVENT_SWITCH_TYPE,
+        _ => return None,
+    };
+    Some((input_event_kind, event.get(code)?.as_i64()?))
+}
+
+/// Converts the payload of the property `send_event` into input events. The payload is either a
+/// single event or an array of events. Each event consists of an `event_type`, a `code` and an
+/// integer `value`.
+pub fn to_input_events(send_event: &Value) -> Result<Vec<InputEvent>, InputDeviceError> {
+    match send_event {
+        Value::Array(send_events) => {
+            if send_events.is_empty() {
+                return Err(InputDeviceError::InvalidPayload("The array of events is empty".into()));
+            }
+            send_events.iter().map(to_input_event).collect()
+        }
+        Value::Object(_) => Ok(vec![to_input_event(send_event)?]),
+        _ => Err(InputDeviceError::InvalidPayload(format!("Expected an event or an array of events but got {}", send_event))),
+    }
+}
+
+/// Appends a SYN_REPORT which completes the frame unless the last event is already a SYN_REPORT.
+pub fn with_syn_report(mut events: Vec<InputEvent>) -> Vec<InputEvent> {
+    if !events.last().map(is_syn_report).unwrap_or(false) {
+        events.push(InputEvent::new(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0));
+    }
+    events
+}
+
+/// Removes the SYN_REPORT at the end of the events, for devices which complete the frame themselves.
+pub fn without_syn_report(mut events: Vec<InputEvent>) -> Vec<InputEvent> {
+    if events.last().map(is_syn_report).unwrap_or(false) {
+        events.pop();
+    }
+    events
+}
+
+/// Converts a single event of the payload of the property `send_event` into an input event.
+pub fn to_input_event(send_event: &Value) -> Result<InputEvent, InputDeviceError> {
+    let event_type = send_event
+        .get(SEND_EVENT_EVENT_TYPE)
+        .and_then(|event_type| event_type.as_u64())
+        .and_then(|event_type| u16::try_from(event_type).ok())
+        .ok_or_else(|| InputDeviceError::InvalidPayload(format!("Invalid or missing {} in {}", SEND_EVENT_EVENT_TYPE, send_event)))?;
+    let code = send_event
+        .get(SEND_EVENT_CODE)
+        .and_then(|code| code.as_u64())
+        .and_then(|code| u16::try_from(code).ok())
+        .ok_or_else(|| InputDeviceError::InvalidPayload(format!("Invalid or missing {} in {}", SEND_EVENT_CODE, send_event)))?;
+    let value = send_event
+        .get(SEND_EVENT_VALUE)
+        .and_then(to_value)
+        .ok_or_else(|| InputDeviceError::InvalidPayload(format!("Invalid or missing {} in {}", SEND_EVENT_VALUE, send_event)))?;
+    Ok(InputEvent::new(EventType(event_type), code, value))
+}
+
+/// The value of an event is an integer, for example 0 (released), 1 (pressed) or 2 (repeated)
+/// for keys. For convenience booleans are accepted as 0 and 1.
+fn to_value(value: &Value) -> Option<i32> {
+    match value {
+        Value::Bool(value) => Some(if *value { 1 } else { 0 }),
+        Value::Number(value) => value.as_i64().and_then(|value| i32::try_from(value).ok()),
+        _ => None,
+    }
+}
+
+pub fn is_syn_report(event: &InputEvent) -> bool {
+    event.event_type() == EventType::SYNCHRONIZATION && event.code() == Synchronization::SYN_REPORT.0
+}
@@ -1,3 +1,4 @@
 pub mod entity;
+pub mod error;
 pub mod event_payload;
 pub mod relation;
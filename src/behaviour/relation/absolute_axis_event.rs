@@ -1,17 +1,16 @@
-use std::convert::AsRef;
 use std::sync::Arc;
 
 use log::debug;
-use serde_json::json;
+use serde_json::{json, Value};
 
+use crate::api::InputDeviceEventDispatcher;
 use crate::behaviour::entity::input_device_absolute_axis_properties::InputDeviceAbsoluteAxisProperties;
-use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
-use crate::behaviour::event_payload::{ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE, INPUT_EVENT_KIND, INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_VALUE};
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{timestamp_of, ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE, INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_VALUE};
 use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveRelationInstance;
 use crate::reactive::entity::Disconnectable;
 use inexor_rgf_core_model::PropertyInstanceSetter;
-use inexor_rgf_core_reactive::BehaviourCreationError;
 
 pub const ABSOLUTE_AXIS_EVENT: &'static str = "absolute_axis_event";
 
@@ -19,56 +18,52 @@ pub struct AbsoluteAxisEvent {
     pub relation: Arc<ReactiveRelationInstance>,
 
     pub handle_id: u128,
+
+    input_device_absolute_axis_absolute_axis_type: i64,
+
+    input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
 }
 
 impl AbsoluteAxisEvent {
-    pub fn new<'a>(r: Arc<ReactiveRelationInstance>) -> Result<AbsoluteAxisEvent, BehaviourCreationError> {
+    pub fn new<'a>(
+        r: Arc<ReactiveRelationInstance>,
+        input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
+    ) -> Result<AbsoluteAxisEvent, InputDeviceError> {
         let input_device = r.outbound.clone();
         let input_device_absolute_axis = r.inbound.clone();
-        let input_device_absolute_axis_absolute_axis_type = input_device_absolute_axis.as_i64(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE);
-        if input_device_absolute_axis_absolute_axis_type.is_none() {
-            return Err(BehaviourCreationError.into());
-        }
-        let input_device_absolute_axis_absolute_axis_type = input_device_absolute_axis_absolute_axis_type.unwrap();
+        let input_device_absolute_axis_absolute_axis_type = input_device_absolute_axis
+            .as_i64(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE)
+            .ok_or_else(|| {
+                report_error(
+                    &input_device_absolute_axis,
+                    InputDeviceError::MissingProperty(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE.to_string()),
+                )
+            })?;
 
-        let handle_id = input_device.properties.get(InputDeviceProperties::EVENT.as_ref()).unwrap().id.as_u128();
+        // There is only one relation between the input device and the absolute axis
+        let handle_id = input_device_absolute_axis.id.as_u128();
 
-        input_device
-            .properties
-            .get(InputDeviceProperties::EVENT.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |v| {
-                    let event = v.clone();
-                    if !event.is_object() {
-                        return;
-                    }
-                    let input_event_kind = event.get(INPUT_EVENT_KIND);
-                    if input_event_kind.is_none() {
-                        return;
-                    }
-
-                    match input_event_kind.unwrap().as_str().unwrap() {
-                        INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT => {
-                            let event_absolute_axis_type = event.get(ABSOLUTE_AXIS_EVENT_ABSOLUTE_AXIS_TYPE).unwrap().as_i64().unwrap_or(-1);
-                            if input_device_absolute_axis_absolute_axis_type == event_absolute_axis_type {
-                                let default = json!(0);
-                                let value = event.get(INPUT_EVENT_VALUE).unwrap_or(&default);
-                                input_device_absolute_axis.set(InputDeviceAbsoluteAxisProperties::STATE.to_string(), value.clone());
-                            }
-                        }
-                        _ => {}
-                    }
-                },
-                handle_id,
-            );
+        input_device_event_dispatcher.register(
+            input_device.id,
+            INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+            input_device_absolute_axis_absolute_axis_type,
+            handle_id,
+            Arc::new(move |event: &Value| {
+                let default = json!(0);
+                let value = event.get(INPUT_EVENT_VALUE).unwrap_or(&default);
+                input_device_absolute_axis.set(InputDeviceAbsoluteAxisProperties::LAST_CHANGED.to_string(), timestamp_of(event));
+                input_device_absolute_axis.set(InputDeviceAbsoluteAxisProperties::STATE.to_string(), value.clone());
+                let default = json!(0.0);
+                let normalized = event.get(ABSOLUTE_AXIS_EVENT_NORMALIZED_VALUE).unwrap_or(&default);
+                input_device_absolute_axis.set(InputDeviceAbsoluteAxisProperties::NORMALIZED.to_string(), normalized.clone());
+            }),
+        );
 
         Ok(AbsoluteAxisEvent {
             relation: r.clone(),
             handle_id,
+            input_device_absolute_axis_absolute_axis_type,
+            input_device_event_dispatcher,
         })
     }
 
@@ -79,11 +74,13 @@ impl AbsoluteAxisEvent {
 
 impl Disconnectable for AbsoluteAxisEvent {
     fn disconnect(&self) {
-        debug!("Disconnecting behaviour {} from property instance {}", ABSOLUTE_AXIS_EVENT, self.handle_id);
-        let property = self.relation.inbound.properties.get(InputDeviceProperties::EVENT.as_ref());
-        if property.is_some() {
-            property.unwrap().stream.read().unwrap().remove(self.handle_id);
-        }
+        debug!("Disconnecting behaviour {} from input device {}", ABSOLUTE_AXIS_EVENT, self.relation.outbound.id);
+        self.input_device_event_dispatcher.unregister(
+            self.relation.outbound.id,
+            INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+            self.input_device_absolute_axis_absolute_axis_type,
+            self.handle_id,
+        );
     }
 }
 
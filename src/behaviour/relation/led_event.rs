@@ -1,17 +1,16 @@
-use std::convert::AsRef;
 use std::sync::Arc;
 
 use log::debug;
-use serde_json::json;
+use serde_json::{json, Value};
 
+use crate::api::InputDeviceEventDispatcher;
 use crate::behaviour::entity::input_device_led_properties::InputDeviceLedProperties;
-use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
-use crate::behaviour::event_payload::{INPUT_EVENT_KIND, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_VALUE, LED_EVENT_LED_TYPE};
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{timestamp_of, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_VALUE};
 use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveRelationInstance;
 use crate::reactive::entity::Disconnectable;
 use inexor_rgf_core_model::PropertyInstanceSetter;
-use inexor_rgf_core_reactive::BehaviourCreationError;
 
 pub const LED_EVENT: &'static str = "led_event";
 
@@ -19,71 +18,65 @@ pub struct LedEvent {
     pub relation: Arc<ReactiveRelationInstance>,
 
     pub handle_id: u128,
+
+    input_device_led_led_type: i64,
+
+    input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
 }
 
 impl LedEvent {
-    pub fn new<'a>(r: Arc<ReactiveRelationInstance>) -> Result<LedEvent, BehaviourCreationError> {
+    pub fn new<'a>(r: Arc<ReactiveRelationInstance>, input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>) -> Result<LedEvent, InputDeviceError> {
         let input_device = r.outbound.clone();
         let input_device_led = r.inbound.clone();
-        let input_device_led_led_type = input_device_led.as_i64(InputDeviceLedProperties::LED_TYPE);
-        if input_device_led_led_type.is_none() {
-            return Err(BehaviourCreationError.into());
-        }
-        let input_device_led_led_type = input_device_led_led_type.unwrap();
+        let input_device_led_led_type = input_device_led
+            .as_i64(InputDeviceLedProperties::LED_TYPE)
+            .ok_or_else(|| report_error(&input_device_led, InputDeviceError::MissingProperty(InputDeviceLedProperties::LED_TYPE.to_string())))?;
 
-        let handle_id = input_device.properties.get(InputDeviceProperties::EVENT.as_ref()).unwrap().id.as_u128();
+        // There is only one relation between the input device and the LED
+        let handle_id = input_device_led.id.as_u128();
 
-        input_device
-            .properties
-            .get(InputDeviceProperties::EVENT.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |v| {
-                    let event = v.clone();
-                    if !event.is_object() {
+        input_device_event_dispatcher.register(
+            input_device.id,
+            INPUT_EVENT_KIND_LED_EVENT,
+            input_device_led_led_type,
+            handle_id,
+            Arc::new(move |event: &Value| {
+                let value = match event.get(INPUT_EVENT_VALUE).and_then(Value::as_i64) {
+                    Some(value) => value,
+                    None => {
+                        report_error(
+                            &input_device_led,
+                            InputDeviceError::InvalidPayload(format!("Invalid or missing {} in {}", INPUT_EVENT_VALUE, event)),
+                        );
                         return;
                     }
-                    let input_event_kind = event.get(INPUT_EVENT_KIND);
-                    if input_event_kind.is_none() {
-                        return;
+                };
+                let old_value = input_device_led.as_bool(InputDeviceLedProperties::STATE).unwrap_or(false);
+                match value {
+                    // LED off
+                    0 => {
+                        if old_value {
+                            input_device_led.set(InputDeviceLedProperties::LAST_CHANGED.to_string(), timestamp_of(event));
+                            input_device_led.set(InputDeviceLedProperties::STATE.to_string(), json!(false))
+                        }
                     }
-
-                    match input_event_kind.unwrap().as_str().unwrap() {
-                        INPUT_EVENT_KIND_LED_EVENT => {
-                            let event_led_type = event.get(LED_EVENT_LED_TYPE).unwrap().as_i64().unwrap_or(-1);
-                            if input_device_led_led_type == event_led_type {
-                                let old_value = input_device_led.get(InputDeviceLedProperties::STATE).unwrap().as_bool().unwrap();
-                                let default = json!(-1);
-                                let value = event.get(INPUT_EVENT_VALUE).unwrap_or(&default).as_i64().unwrap();
-                                match value {
-                                    // LED off
-                                    0 => {
-                                        if old_value {
-                                            input_device_led.set(InputDeviceLedProperties::STATE.to_string(), json!(false))
-                                        }
-                                    }
-                                    // LED on
-                                    1 => {
-                                        if !old_value {
-                                            input_device_led.set(InputDeviceLedProperties::STATE.to_string(), json!(true));
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
+                    // LED on
+                    1 => {
+                        if !old_value {
+                            input_device_led.set(InputDeviceLedProperties::LAST_CHANGED.to_string(), timestamp_of(event));
+                            input_device_led.set(InputDeviceLedProperties::STATE.to_string(), json!(true));
                         }
-                        _ => {}
                     }
-                },
-                handle_id,
-            );
+                    _ => {}
+                }
+            }),
+        );
 
         Ok(LedEvent {
             relation: r.clone(),
             handle_id,
+            input_device_led_led_type,
+            input_device_event_dispatcher,
         })
     }
 
@@ -94,11 +87,9 @@ impl LedEvent {
 
 impl Disconnectable for LedEvent {
     fn disconnect(&self) {
-        debug!("Disconnecting behaviour {} from property instance {}", LED_EVENT, self.handle_id);
-        let property = self.relation.inbound.properties.get(InputDeviceProperties::EVENT.as_ref());
-        if property.is_some() {
-            property.unwrap().stream.read().unwrap().remove(self.handle_id);
-        }
+        debug!("Disconnecting behaviour {} from input device {}", LED_EVENT, self.relation.outbound.id);
+        self.input_device_event_dispatcher
+            .unregister(self.relation.outbound.id, INPUT_EVENT_KIND_LED_EVENT, self.input_device_led_led_type, self.handle_id);
     }
 }
 
@@ -5,4 +5,5 @@ pub mod relation_behaviour_provider;
 pub mod relative_axis_event;
 pub mod send_key_event;
 pub mod send_led_event;
+pub mod send_state_event;
 pub mod switch_event;
@@ -5,8 +5,10 @@ use async_trait::async_trait;
 use indradb::EdgeKey;
 use log::trace;
 
+use crate::api::{InputDeviceEventDispatcher, InputDeviceStatusManager};
 use crate::behaviour::relation::send_key_event::SendKeyEvent;
 use crate::behaviour::relation::send_led_event::SendLedEvent;
+use crate::behaviour::relation::send_state_event::{SendAbsoluteAxisEvent, SendRelativeAxisEvent, SendSwitchEvent};
 use crate::behaviour::relation::switch_event::SwitchEvent;
 use crate::behaviour::relation::{absolute_axis_event::AbsoluteAxisEvent, key_event::KeyEvent, led_event::LedEvent, relative_axis_event::RelativeAxisEvent};
 use crate::model::ReactiveRelationInstance;
@@ -22,10 +24,16 @@ const SEND_LED_EVENT: &'static str = "send_led_event";
 
 const RELATIVE_AXIS_EVENT: &'static str = "relative_axis_event";
 
+const SEND_RELATIVE_AXIS_EVENT: &'static str = "send_relative_axis_event";
+
 const ABSOLUTE_AXIS_EVENT: &'static str = "absolute_axis_event";
 
+const SEND_ABSOLUTE_AXIS_EVENT: &'static str = "send_absolute_axis_event";
+
 const SWITCH_EVENT: &'static str = "switch_event";
 
+const SEND_SWITCH_EVENT: &'static str = "send_switch_event";
+
 #[wrapper]
 pub struct KeyEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<KeyEvent>>>);
 
@@ -41,12 +49,21 @@ pub struct SendLedEventRelationBehaviourStorage(std::sync::RwLock<std::collectio
 #[wrapper]
 pub struct RelativeAxisEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<RelativeAxisEvent>>>);
 
+#[wrapper]
+pub struct SendRelativeAxisEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<SendRelativeAxisEvent>>>);
+
 #[wrapper]
 pub struct AbsoluteAxisEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<AbsoluteAxisEvent>>>);
 
+#[wrapper]
+pub struct SendAbsoluteAxisEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<SendAbsoluteAxisEvent>>>);
+
 #[wrapper]
 pub struct SwitchEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<SwitchEvent>>>);
 
+#[wrapper]
+pub struct SendSwitchEventRelationBehaviourStorage(std::sync::RwLock<std::collections::HashMap<EdgeKey, std::sync::Arc<SendSwitchEvent>>>);
+
 #[provides]
 fn create_key_event_relation_behaviour_storage() -> KeyEventRelationBehaviourStorage {
     KeyEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
@@ -72,16 +89,31 @@ fn create_relative_axis_event_relation_behaviour_storage() -> RelativeAxisEventR
     RelativeAxisEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
 }
 
+#[provides]
+fn create_send_relative_axis_event_relation_behaviour_storage() -> SendRelativeAxisEventRelationBehaviourStorage {
+    SendRelativeAxisEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
 #[provides]
 fn create_absolute_axis_event_relation_behaviour_storage() -> AbsoluteAxisEventRelationBehaviourStorage {
     AbsoluteAxisEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
 }
 
+#[provides]
+fn create_send_absolute_axis_event_relation_behaviour_storage() -> SendAbsoluteAxisEventRelationBehaviourStorage {
+    SendAbsoluteAxisEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
 #[provides]
 fn create_switch_event_relation_behaviour_storage() -> SwitchEventRelationBehaviourStorage {
     SwitchEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
 }
 
+#[provides]
+fn create_send_switch_event_relation_behaviour_storage() -> SendSwitchEventRelationBehaviourStorage {
+    SendSwitchEventRelationBehaviourStorage(std::sync::RwLock::new(std::collections::HashMap::new()))
+}
+
 #[async_trait]
 pub trait InputDeviceRelationBehaviourProvider: RelationBehaviourProvider + Send + Sync {
     fn create_key_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
@@ -104,14 +136,26 @@ pub trait InputDeviceRelationBehaviourProvider: RelationBehaviourProvider + Send
 
     fn remove_relative_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
 
+    fn create_send_relative_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
+    fn remove_send_relative_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
     fn create_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
 
     fn remove_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
 
+    fn create_send_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
+    fn remove_send_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
     fn create_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
 
     fn remove_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
 
+    fn create_send_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
+    fn remove_send_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>);
+
     fn remove_by_key(&self, edge_key: EdgeKey);
 }
 
@@ -122,8 +166,14 @@ pub struct InputDeviceRelationBehaviourProviderImpl {
     led_event_relation_behaviours: LedEventRelationBehaviourStorage,
     send_led_event_relation_behaviours: SendLedEventRelationBehaviourStorage,
     relative_axis_event_relation_behaviours: RelativeAxisEventRelationBehaviourStorage,
+    send_relative_axis_event_relation_behaviours: SendRelativeAxisEventRelationBehaviourStorage,
     absolute_axis_event_relation_behaviours: AbsoluteAxisEventRelationBehaviourStorage,
+    send_absolute_axis_event_relation_behaviours: SendAbsoluteAxisEventRelationBehaviourStorage,
     switch_event_relation_behaviours: SwitchEventRelationBehaviourStorage,
+    send_switch_event_relation_behaviours: SendSwitchEventRelationBehaviourStorage,
+
+    input_device_event_dispatcher: Wrc<dyn InputDeviceEventDispatcher>,
+    input_device_status_manager: Wrc<dyn InputDeviceStatusManager>,
 }
 
 interfaces!(InputDeviceRelationBehaviourProviderImpl: dyn RelationBehaviourProvider);
@@ -131,15 +181,20 @@ interfaces!(InputDeviceRelationBehaviourProviderImpl: dyn RelationBehaviourProvi
 #[component]
 impl InputDeviceRelationBehaviourProviderImpl {
     #[provides]
-    fn new() -> Self {
+    fn new(input_device_event_dispatcher: Wrc<dyn InputDeviceEventDispatcher>, input_device_status_manager: Wrc<dyn InputDeviceStatusManager>) -> Self {
         Self {
             key_event_relation_behaviours: create_key_event_relation_behaviour_storage(),
             send_key_event_relation_behaviours: create_send_key_event_relation_behaviour_storage(),
             led_event_relation_behaviours: create_led_event_relation_behaviour_storage(),
             send_led_event_relation_behaviours: create_send_led_event_relation_behaviour_storage(),
             relative_axis_event_relation_behaviours: create_relative_axis_event_relation_behaviour_storage(),
+            send_relative_axis_event_relation_behaviours: create_send_relative_axis_event_relation_behaviour_storage(),
             absolute_axis_event_relation_behaviours: create_absolute_axis_event_relation_behaviour_storage(),
+            send_absolute_axis_event_relation_behaviours: create_send_absolute_axis_event_relation_behaviour_storage(),
             switch_event_relation_behaviours: create_switch_event_relation_behaviour_storage(),
+            send_switch_event_relation_behaviours: create_send_switch_event_relation_behaviour_storage(),
+            input_device_event_dispatcher,
+            input_device_status_manager,
         }
     }
 }
@@ -153,7 +208,7 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             return;
         }
         let edge_key = edge_key.unwrap();
-        let key_event = KeyEvent::new(relation_instance.clone());
+        let key_event = KeyEvent::new(relation_instance.clone(), self.input_device_event_dispatcher.clone());
         if key_event.is_ok() {
             self.key_event_relation_behaviours
                 .0
@@ -182,7 +237,7 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             return;
         }
         let edge_key = edge_key.unwrap();
-        let send_key_event = SendKeyEvent::new(relation_instance.clone());
+        let send_key_event = SendKeyEvent::new(relation_instance.clone(), self.input_device_status_manager.clone());
         if send_key_event.is_ok() {
             self.send_key_event_relation_behaviours
                 .0
@@ -211,7 +266,7 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             return;
         }
         let edge_key = edge_key.unwrap();
-        let led_event = LedEvent::new(relation_instance.clone());
+        let led_event = LedEvent::new(relation_instance.clone(), self.input_device_event_dispatcher.clone());
         if led_event.is_ok() {
             self.led_event_relation_behaviours
                 .0
@@ -240,7 +295,7 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             return;
         }
         let edge_key = edge_key.unwrap();
-        let send_led_event = SendLedEvent::new(relation_instance.clone());
+        let send_led_event = SendLedEvent::new(relation_instance.clone(), self.input_device_status_manager.clone());
         if send_led_event.is_ok() {
             self.send_led_event_relation_behaviours
                 .0
@@ -269,7 +324,7 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             return;
         }
         let edge_key = edge_key.unwrap();
-        let relative_axis_event = RelativeAxisEvent::new(relation_instance.clone());
+        let relative_axis_event = RelativeAxisEvent::new(relation_instance.clone(), self.input_device_event_dispatcher.clone());
         if relative_axis_event.is_ok() {
             self.relative_axis_event_relation_behaviours
                 .0
@@ -292,13 +347,42 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
         trace!("Removed behaviour {} from relation instance {:?}", RELATIVE_AXIS_EVENT, edge_key);
     }
 
+    fn create_send_relative_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        let send_relative_axis_event = SendRelativeAxisEvent::new(relation_instance.clone(), self.input_device_status_manager.clone());
+        if send_relative_axis_event.is_ok() {
+            self.send_relative_axis_event_relation_behaviours
+                .0
+                .write()
+                .unwrap()
+                .insert(edge_key.clone(), Arc::new(send_relative_axis_event.unwrap()));
+            relation_instance.add_behaviour(SEND_RELATIVE_AXIS_EVENT);
+            trace!("Added relation behaviour {} to relation instance {:?}", SEND_RELATIVE_AXIS_EVENT, edge_key);
+        }
+    }
+
+    fn remove_send_relative_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        self.send_relative_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+        relation_instance.remove_behaviour(SEND_RELATIVE_AXIS_EVENT);
+        trace!("Removed behaviour {} from relation instance {:?}", SEND_RELATIVE_AXIS_EVENT, edge_key);
+    }
+
     fn create_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
         let edge_key = relation_instance.get_key();
         if edge_key.is_none() {
             return;
         }
         let edge_key = edge_key.unwrap();
-        let absolute_axis_event = AbsoluteAxisEvent::new(relation_instance.clone());
+        let absolute_axis_event = AbsoluteAxisEvent::new(relation_instance.clone(), self.input_device_event_dispatcher.clone());
         if absolute_axis_event.is_ok() {
             self.absolute_axis_event_relation_behaviours
                 .0
@@ -321,13 +405,42 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
         trace!("Removed behaviour {} from relation instance {:?}", ABSOLUTE_AXIS_EVENT, edge_key);
     }
 
+    fn create_send_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        let send_absolute_axis_event = SendAbsoluteAxisEvent::new(relation_instance.clone(), self.input_device_status_manager.clone());
+        if send_absolute_axis_event.is_ok() {
+            self.send_absolute_axis_event_relation_behaviours
+                .0
+                .write()
+                .unwrap()
+                .insert(edge_key.clone(), Arc::new(send_absolute_axis_event.unwrap()));
+            relation_instance.add_behaviour(SEND_ABSOLUTE_AXIS_EVENT);
+            trace!("Added relation behaviour {} to relation instance {:?}", SEND_ABSOLUTE_AXIS_EVENT, edge_key);
+        }
+    }
+
+    fn remove_send_absolute_axis_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        self.send_absolute_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+        relation_instance.remove_behaviour(SEND_ABSOLUTE_AXIS_EVENT);
+        trace!("Removed behaviour {} from relation instance {:?}", SEND_ABSOLUTE_AXIS_EVENT, edge_key);
+    }
+
     fn create_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
         let edge_key = relation_instance.get_key();
         if edge_key.is_none() {
             return;
         }
         let edge_key = edge_key.unwrap();
-        let switch_event = SwitchEvent::new(relation_instance.clone());
+        let switch_event = SwitchEvent::new(relation_instance.clone(), self.input_device_event_dispatcher.clone());
         if switch_event.is_ok() {
             self.switch_event_relation_behaviours
                 .0
@@ -350,6 +463,35 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
         trace!("Removed behaviour {} from relation instance {:?}", SWITCH_EVENT, edge_key);
     }
 
+    fn create_send_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        let send_switch_event = SendSwitchEvent::new(relation_instance.clone(), self.input_device_status_manager.clone());
+        if send_switch_event.is_ok() {
+            self.send_switch_event_relation_behaviours
+                .0
+                .write()
+                .unwrap()
+                .insert(edge_key.clone(), Arc::new(send_switch_event.unwrap()));
+            relation_instance.add_behaviour(SEND_SWITCH_EVENT);
+            trace!("Added relation behaviour {} to relation instance {:?}", SEND_SWITCH_EVENT, edge_key);
+        }
+    }
+
+    fn remove_send_switch_event_behaviour(&self, relation_instance: Arc<ReactiveRelationInstance>) {
+        let edge_key = relation_instance.get_key();
+        if edge_key.is_none() {
+            return;
+        }
+        let edge_key = edge_key.unwrap();
+        self.send_switch_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+        relation_instance.remove_behaviour(SEND_SWITCH_EVENT);
+        trace!("Removed behaviour {} from relation instance {:?}", SEND_SWITCH_EVENT, edge_key);
+    }
+
     fn remove_by_key(&self, edge_key: EdgeKey) {
         if self.key_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
             self.key_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
@@ -371,14 +513,26 @@ impl InputDeviceRelationBehaviourProvider for InputDeviceRelationBehaviourProvid
             self.relative_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
             trace!("Removed behaviour {} from relation instance {:?}", RELATIVE_AXIS_EVENT, edge_key);
         }
+        if self.send_relative_axis_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
+            self.send_relative_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+            trace!("Removed behaviour {} from relation instance {:?}", SEND_RELATIVE_AXIS_EVENT, edge_key);
+        }
         if self.absolute_axis_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
             self.absolute_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
             trace!("Removed behaviour {} from relation instance {:?}", ABSOLUTE_AXIS_EVENT, edge_key);
         }
+        if self.send_absolute_axis_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
+            self.send_absolute_axis_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+            trace!("Removed behaviour {} from relation instance {:?}", SEND_ABSOLUTE_AXIS_EVENT, edge_key);
+        }
         if self.switch_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
             self.switch_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
             trace!("Removed behaviour {} from relation instance {:?}", SWITCH_EVENT, edge_key);
         }
+        if self.send_switch_event_relation_behaviours.0.write().unwrap().contains_key(&edge_key) {
+            self.send_switch_event_relation_behaviours.0.write().unwrap().remove(&edge_key);
+            trace!("Removed behaviour {} from relation instance {:?}", SEND_SWITCH_EVENT, edge_key);
+        }
     }
 }
 
@@ -390,8 +544,11 @@ impl RelationBehaviourProvider for InputDeviceRelationBehaviourProviderImpl {
             LED_EVENT => self.create_led_event_behaviour(relation_instance),
             SEND_LED_EVENT => self.create_send_led_event_behaviour(relation_instance),
             RELATIVE_AXIS_EVENT => self.create_relative_axis_event_behaviour(relation_instance),
+            SEND_RELATIVE_AXIS_EVENT => self.create_send_relative_axis_event_behaviour(relation_instance),
             ABSOLUTE_AXIS_EVENT => self.create_absolute_axis_event_behaviour(relation_instance),
+            SEND_ABSOLUTE_AXIS_EVENT => self.create_send_absolute_axis_event_behaviour(relation_instance),
             SWITCH_EVENT => self.create_switch_event_behaviour(relation_instance),
+            SEND_SWITCH_EVENT => self.create_send_switch_event_behaviour(relation_instance),
             _ => {}
         }
     }
@@ -403,8 +560,11 @@ impl RelationBehaviourProvider for InputDeviceRelationBehaviourProviderImpl {
             LED_EVENT => self.remove_led_event_behaviour(relation_instance),
             SEND_LED_EVENT => self.remove_send_led_event_behaviour(relation_instance),
             RELATIVE_AXIS_EVENT => self.remove_relative_axis_event_behaviour(relation_instance),
+            SEND_RELATIVE_AXIS_EVENT => self.remove_send_relative_axis_event_behaviour(relation_instance),
             ABSOLUTE_AXIS_EVENT => self.remove_absolute_axis_event_behaviour(relation_instance),
+            SEND_ABSOLUTE_AXIS_EVENT => self.remove_send_absolute_axis_event_behaviour(relation_instance),
             SWITCH_EVENT => self.remove_switch_event_behaviour(relation_instance),
+            SEND_SWITCH_EVENT => self.remove_send_switch_event_behaviour(relation_instance),
             _ => {}
         }
     }
@@ -1,16 +1,20 @@
 use evdev::EventType;
 use std::convert::AsRef;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use log::debug;
 use serde_json::json;
 
+use crate::api::InputDeviceStatusManager;
 use crate::behaviour::entity::input_device_key_properties::InputDeviceKeyProperties;
 use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{SEND_EVENT_CODE, SEND_EVENT_EVENT_TYPE, SEND_EVENT_VALUE};
 use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
 use crate::model::ReactiveRelationInstance;
 use crate::reactive::entity::Disconnectable;
-use inexor_rgf_core_reactive::BehaviourCreationError;
 
 pub const SEND_KEY_EVENT: &'static str = "send_key_event";
 
@@ -18,67 +22,74 @@ pub struct SendKeyEvent {
     pub relation: Arc<ReactiveRelationInstance>,
 
     pub handle_id: u128,
+
+    /// True, if the key has been pressed by this relation and hasn't been released yet.
+    pressed: Arc<AtomicBool>,
 }
 
 impl SendKeyEvent {
-    pub fn new<'a>(r: Arc<ReactiveRelationInstance>) -> Result<SendKeyEvent, BehaviourCreationError> {
+    pub fn new<'a>(r: Arc<ReactiveRelationInstance>, input_device_status_manager: Arc<dyn InputDeviceStatusManager>) -> Result<SendKeyEvent, InputDeviceError> {
         let input_device_key = r.outbound.clone();
         let input_device = r.inbound.clone();
-        let input_device_key_key_code = input_device_key.as_i64(InputDeviceKeyProperties::KEY_CODE);
-        if input_device_key_key_code.is_none() {
-            return Err(BehaviourCreationError.into());
-        }
-        let event_type = u64::try_from(EventType::KEY.0).unwrap();
-        let code = input_device_key_key_code.unwrap();
-        let handle_id = input_device_key
+        let code = input_device_key
+            .as_i64(InputDeviceKeyProperties::KEY_CODE)
+            .ok_or_else(|| report_error(&input_device_key, InputDeviceError::MissingProperty(InputDeviceKeyProperties::KEY_CODE.to_string())))?;
+        let property_set_key_down = input_device_key
             .properties
             .get(InputDeviceKeyProperties::SET_KEY_DOWN.as_ref())
-            .unwrap()
-            .id
-            .as_u128();
+            .ok_or_else(|| report_error(&input_device_key, InputDeviceError::MissingProperty(InputDeviceKeyProperties::SET_KEY_DOWN.to_string())))?;
+        let handle_id = property_set_key_down.id.as_u128();
 
-        input_device_key
-            .properties
-            .get(InputDeviceKeyProperties::SET_KEY_DOWN.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |v| {
-                    if !v.is_boolean() {
+        let pressed = Arc::new(AtomicBool::new(false));
+        let observer_pressed = pressed.clone();
+        property_set_key_down.stream.read().unwrap().observe_with_handle(
+            move |v| {
+                let key_down = match v.as_bool() {
+                    Some(key_down) => key_down,
+                    None => {
                         // Invalid input
+                        input_device_status_manager.send_request_dropped();
                         return;
                     }
-                    let send_event = input_device.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()).unwrap();
-                    let event = json!({
-                        "event_type": event_type, // u64
-                        "code": code, // i64
-                        "value": v // bool
-                    });
-                    send_event.set(event);
-                },
-                handle_id,
-            );
+                };
+                observer_pressed.store(key_down, Ordering::SeqCst);
+                send_key_event(&input_device, code, key_down);
+            },
+            handle_id,
+        );
 
         Ok(SendKeyEvent {
             relation: r.clone(),
             handle_id,
+            pressed,
         })
     }
 
     pub fn type_name(&self) -> String {
         self.relation.type_name.clone()
     }
+
+    /// Releases the key if it has been pressed by this relation. The key up event is sent like
+    /// the key down event, so that it reaches the same (virtual) device.
+    pub fn release(&self) {
+        if self.pressed.swap(false, Ordering::SeqCst) {
+            debug!("Releasing key {} of input device {}", self.relation.outbound.id, self.relation.inbound.id);
+            if let Some(code) = self.relation.outbound.as_i64(InputDeviceKeyProperties::KEY_CODE) {
+                send_key_event(&self.relation.inbound, code, false);
+            }
+        }
+    }
 }
 
 impl Disconnectable for SendKeyEvent {
     fn disconnect(&self) {
         debug!("Disconnecting behaviour {} from property instance {}", SEND_KEY_EVENT, self.handle_id);
-        let property = self.relation.inbound.properties.get(InputDeviceProperties::EVENT.as_ref());
+        let property = self.relation.outbound.properties.get(InputDeviceKeyProperties::SET_KEY_DOWN.as_ref());
         if property.is_some() {
             property.unwrap().stream.read().unwrap().remove(self.handle_id);
         }
+        // Keys which are still held down would be stuck
+        self.release();
     }
 }
 
@@ -88,3 +99,15 @@ impl Drop for SendKeyEvent {
         self.disconnect();
     }
 }
+
+/// Sends a key down or key up event through the property `send_event` of the input device, whose
+/// behaviour routes it to the virtual device or to the device node.
+fn send_key_event(input_device: &ReactiveEntityInstance, code: i64, key_down: bool) {
+    if let Some(send_event) = input_device.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()) {
+        send_event.set(json!({
+            SEND_EVENT_EVENT_TYPE: EventType::KEY.0,
+            SEND_EVENT_CODE: code,
+            SEND_EVENT_VALUE: if key_down { 1 } else { 0 }
+        }));
+    }
+}
@@ -5,12 +5,14 @@ use std::sync::Arc;
 use log::debug;
 use serde_json::json;
 
+use crate::api::InputDeviceStatusManager;
 use crate::behaviour::entity::input_device_led_properties::InputDeviceLedProperties;
 use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{SEND_EVENT_CODE, SEND_EVENT_EVENT_TYPE, SEND_EVENT_VALUE};
 use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveRelationInstance;
 use crate::reactive::entity::Disconnectable;
-use inexor_rgf_core_reactive::BehaviourCreationError;
 
 pub const SEND_LED_EVENT: &'static str = "send_led_event";
 
@@ -21,45 +23,40 @@ pub struct SendLedEvent {
 }
 
 impl SendLedEvent {
-    pub fn new<'a>(r: Arc<ReactiveRelationInstance>) -> Result<SendLedEvent, BehaviourCreationError> {
+    pub fn new<'a>(r: Arc<ReactiveRelationInstance>, input_device_status_manager: Arc<dyn InputDeviceStatusManager>) -> Result<SendLedEvent, InputDeviceError> {
         let input_device_led = r.outbound.clone();
         let input_device = r.inbound.clone();
-        let input_device_led_led_type = input_device_led.as_i64(InputDeviceLedProperties::LED_TYPE);
-        if input_device_led_led_type.is_none() {
-            return Err(BehaviourCreationError.into());
-        }
-        let event_type = u64::try_from(EventType::LED.0).unwrap();
-        let code = input_device_led_led_type.unwrap();
-        let handle_id = input_device_led
+        let code = input_device_led
+            .as_i64(InputDeviceLedProperties::LED_TYPE)
+            .ok_or_else(|| report_error(&input_device_led, InputDeviceError::MissingProperty(InputDeviceLedProperties::LED_TYPE.to_string())))?;
+        let event_type = EventType::LED.0;
+        let property_set_state = input_device_led
             .properties
             .get(InputDeviceLedProperties::SET_STATE.as_ref())
-            .unwrap()
-            .id
-            .as_u128();
+            .ok_or_else(|| report_error(&input_device_led, InputDeviceError::MissingProperty(InputDeviceLedProperties::SET_STATE.to_string())))?;
+        let handle_id = property_set_state.id.as_u128();
 
-        input_device_led
-            .properties
-            .get(InputDeviceLedProperties::SET_STATE.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |v| {
-                    if !v.is_boolean() {
+        property_set_state.stream.read().unwrap().observe_with_handle(
+            move |v| {
+                let state = match v.as_bool() {
+                    Some(state) => state,
+                    None => {
                         // Invalid input
+                        input_device_status_manager.send_request_dropped();
                         return;
                     }
-                    let send_event = input_device.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()).unwrap();
+                };
+                if let Some(send_event) = input_device.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()) {
                     let event = json!({
-                        "event_type": event_type, // u64
-                        "code": code, // i64
-                        "value": v // bool
+                        SEND_EVENT_EVENT_TYPE: event_type,
+                        SEND_EVENT_CODE: code,
+                        SEND_EVENT_VALUE: if state { 1 } else { 0 }
                     });
                     send_event.set(event);
-                },
-                handle_id,
-            );
+                }
+            },
+            handle_id,
+        );
 
         Ok(SendLedEvent {
             relation: r.clone(),
@@ -75,7 +72,7 @@ impl SendLedEvent {
 impl Disconnectable for SendLedEvent {
     fn disconnect(&self) {
         debug!("Disconnecting behaviour {} from property instance {}", SEND_LED_EVENT, self.handle_id);
-        let property = self.relation.inbound.properties.get(InputDeviceProperties::EVENT.as_ref());
+        let property = self.relation.outbound.properties.get(InputDeviceLedProperties::SET_STATE.as_ref());
         if property.is_some() {
             property.unwrap().stream.read().unwrap().remove(self.handle_id);
         }
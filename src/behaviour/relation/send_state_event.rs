@@ -0,0 +1,179 @@
+use evdev::EventType;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use log::debug;
+use serde_json::{json, Value};
+
+use crate::api::InputDeviceStatusManager;
+use crate::behaviour::entity::input_device_absolute_axis_properties::InputDeviceAbsoluteAxisProperties;
+use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
+use crate::behaviour::entity::input_device_relative_axis_properties::InputDeviceRelativeAxisProperties;
+use crate::behaviour::entity::input_device_switch_properties::InputDeviceSwitchProperties;
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{SEND_EVENT_CODE, SEND_EVENT_EVENT_TYPE, SEND_EVENT_VALUE};
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveRelationInstance;
+use crate::reactive::entity::Disconnectable;
+
+pub const SEND_RELATIVE_AXIS_EVENT: &'static str = "send_relative_axis_event";
+
+pub const SEND_ABSOLUTE_AXIS_EVENT: &'static str = "send_absolute_axis_event";
+
+pub const SEND_SWITCH_EVENT: &'static str = "send_switch_event";
+
+/// An axis or a switch whose state is sent to the input device by setting the property
+/// set_state. Unlike keys and LEDs, the state is sent as it is.
+pub trait SendStateEventType: Send + Sync + 'static {
+    /// The name of the relation type.
+    const NAME: &'static str;
+
+    /// The event type of the sent events.
+    const EVENT_TYPE: EventType;
+
+    /// The name of the property which contains the code of the axis or switch.
+    fn code_property() -> &'static str;
+
+    /// The name of the property which sends the state.
+    fn set_state_property() -> &'static str;
+
+    /// Returns true, if the given state can be sent.
+    fn accepts(state: &Value) -> bool;
+}
+
+pub struct RelativeAxis;
+
+impl SendStateEventType for RelativeAxis {
+    const NAME: &'static str = SEND_RELATIVE_AXIS_EVENT;
+    const EVENT_TYPE: EventType = EventType::RELATIVE;
+
+    fn code_property() -> &'static str {
+        InputDeviceRelativeAxisProperties::RELATIVE_AXIS_TYPE.into()
+    }
+
+    fn set_state_property() -> &'static str {
+        InputDeviceRelativeAxisProperties::SET_STATE.into()
+    }
+
+    fn accepts(state: &Value) -> bool {
+        state.is_i64()
+    }
+}
+
+pub struct AbsoluteAxis;
+
+impl SendStateEventType for AbsoluteAxis {
+    const NAME: &'static str = SEND_ABSOLUTE_AXIS_EVENT;
+    const EVENT_TYPE: EventType = EventType::ABSOLUTE;
+
+    fn code_property() -> &'static str {
+        InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE.into()
+    }
+
+    fn set_state_property() -> &'static str {
+        InputDeviceAbsoluteAxisProperties::SET_STATE.into()
+    }
+
+    fn accepts(state: &Value) -> bool {
+        state.is_i64()
+    }
+}
+
+pub struct Switch;
+
+impl SendStateEventType for Switch {
+    const NAME: &'static str = SEND_SWITCH_EVENT;
+    const EVENT_TYPE: EventType = EventType::SWITCH;
+
+    fn code_property() -> &'static str {
+        InputDeviceSwitchProperties::SWITCH_TYPE.into()
+    }
+
+    fn set_state_property() -> &'static str {
+        InputDeviceSwitchProperties::SET_STATE.into()
+    }
+
+    fn accepts(state: &Value) -> bool {
+        state.is_boolean() || state.is_i64()
+    }
+}
+
+pub type SendRelativeAxisEvent = SendStateEvent<RelativeAxis>;
+
+pub type SendAbsoluteAxisEvent = SendStateEvent<AbsoluteAxis>;
+
+pub type SendSwitchEvent = SendStateEvent<Switch>;
+
+/// Sends the state of an axis or a switch (outbound) to the input device (inbound).
+pub struct SendStateEvent<T: SendStateEventType> {
+    pub relation: Arc<ReactiveRelationInstance>,
+
+    pub handle_id: u128,
+
+    send_state_event_type: PhantomData<T>,
+}
+
+impl<T: SendStateEventType> SendStateEvent<T> {
+    pub fn new<'a>(
+        r: Arc<ReactiveRelationInstance>,
+        input_device_status_manager: Arc<dyn InputDeviceStatusManager>,
+    ) -> Result<SendStateEvent<T>, InputDeviceError> {
+        let input_device_state = r.outbound.clone();
+        let input_device = r.inbound.clone();
+        let code = input_device_state
+            .as_i64(T::code_property())
+            .ok_or_else(|| report_error(&input_device_state, InputDeviceError::MissingProperty(T::code_property().to_string())))?;
+        let event_type = T::EVENT_TYPE.0;
+        let property_set_state = input_device_state
+            .properties
+            .get(T::set_state_property())
+            .ok_or_else(|| report_error(&input_device_state, InputDeviceError::MissingProperty(T::set_state_property().to_string())))?;
+        let handle_id = property_set_state.id.as_u128();
+
+        property_set_state.stream.read().unwrap().observe_with_handle(
+            move |v: &Value| {
+                if !T::accepts(v) {
+                    // Invalid input
+                    input_device_status_manager.send_request_dropped();
+                    return;
+                }
+                if let Some(send_event) = input_device.properties.get(InputDeviceProperties::SEND_EVENT.as_ref()) {
+                    let event = json!({
+                        SEND_EVENT_EVENT_TYPE: event_type,
+                        SEND_EVENT_CODE: code,
+                        SEND_EVENT_VALUE: v
+                    });
+                    send_event.set(event);
+                }
+            },
+            handle_id,
+        );
+
+        Ok(SendStateEvent {
+            relation: r.clone(),
+            handle_id,
+            send_state_event_type: PhantomData,
+        })
+    }
+
+    pub fn type_name(&self) -> String {
+        self.relation.type_name.clone()
+    }
+}
+
+impl<T: SendStateEventType> Disconnectable for SendStateEvent<T> {
+    fn disconnect(&self) {
+        debug!("Disconnecting behaviour {} from property instance {}", T::NAME, self.handle_id);
+        let property = self.relation.outbound.properties.get(T::set_state_property());
+        if property.is_some() {
+            property.unwrap().stream.read().unwrap().remove(self.handle_id);
+        }
+    }
+}
+
+/// Automatically disconnect streams on destruction
+impl<T: SendStateEventType> Drop for SendStateEvent<T> {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
@@ -1,17 +1,16 @@
-use std::convert::AsRef;
 use std::sync::Arc;
 
 use log::debug;
-use serde_json::json;
+use serde_json::{json, Value};
 
-use crate::behaviour::entity::input_device_properties::InputDeviceProperties;
+use crate::api::InputDeviceEventDispatcher;
 use crate::behaviour::entity::input_device_switch_properties::InputDeviceSwitchProperties;
-use crate::behaviour::event_payload::{INPUT_EVENT_KIND, INPUT_EVENT_KIND_SWITCH_EVENT, INPUT_EVENT_VALUE, SWITCH_EVENT_SWITCH_TYPE};
+use crate::behaviour::error::{report_error, InputDeviceError};
+use crate::behaviour::event_payload::{timestamp_of, INPUT_EVENT_KIND_SWITCH_EVENT, INPUT_EVENT_VALUE};
 use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveRelationInstance;
 use crate::reactive::entity::Disconnectable;
 use inexor_rgf_core_model::PropertyInstanceSetter;
-use inexor_rgf_core_reactive::BehaviourCreationError;
 
 pub const SWITCH_EVENT: &'static str = "switch_event";
 
@@ -19,56 +18,44 @@ pub struct SwitchEvent {
     pub relation: Arc<ReactiveRelationInstance>,
 
     pub handle_id: u128,
+
+    input_device_switch_switch_type: i64,
+
+    input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
 }
 
 impl SwitchEvent {
-    pub fn new<'a>(r: Arc<ReactiveRelationInstance>) -> Result<SwitchEvent, BehaviourCreationError> {
+    pub fn new<'a>(
+        r: Arc<ReactiveRelationInstance>,
+        input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
+    ) -> Result<SwitchEvent, InputDeviceError> {
         let input_device = r.outbound.clone();
         let input_device_switch = r.inbound.clone();
-        let input_device_switch_switch_type = input_device_switch.as_i64(InputDeviceSwitchProperties::SWITCH_TYPE);
-        if input_device_switch_switch_type.is_none() {
-            return Err(BehaviourCreationError.into());
-        }
-        let input_device_switch_switch_type = input_device_switch_switch_type.unwrap();
+        let input_device_switch_switch_type = input_device_switch
+            .as_i64(InputDeviceSwitchProperties::SWITCH_TYPE)
+            .ok_or_else(|| report_error(&input_device_switch, InputDeviceError::MissingProperty(InputDeviceSwitchProperties::SWITCH_TYPE.to_string())))?;
 
-        let handle_id = input_device.properties.get(InputDeviceProperties::EVENT.as_ref()).unwrap().id.as_u128();
+        // There is only one relation between the input device and the switch
+        let handle_id = input_device_switch.id.as_u128();
 
-        input_device
-            .properties
-            .get(InputDeviceProperties::EVENT.as_ref())
-            .unwrap()
-            .stream
-            .read()
-            .unwrap()
-            .observe_with_handle(
-                move |v| {
-                    let event = v.clone();
-                    if !event.is_object() {
-                        return;
-                    }
-                    let input_event_kind = event.get(INPUT_EVENT_KIND);
-                    if input_event_kind.is_none() {
-                        return;
-                    }
-
-                    match input_event_kind.unwrap().as_str().unwrap() {
-                        INPUT_EVENT_KIND_SWITCH_EVENT => {
-                            let event_switch_type = event.get(SWITCH_EVENT_SWITCH_TYPE).unwrap().as_i64().unwrap_or(-1);
-                            if input_device_switch_switch_type == event_switch_type {
-                                let default = json!(0);
-                                let value = event.get(INPUT_EVENT_VALUE).unwrap_or(&default);
-                                input_device_switch.set(InputDeviceSwitchProperties::STATE.to_string(), value.clone());
-                            }
-                        }
-                        _ => {}
-                    }
-                },
-                handle_id,
-            );
+        input_device_event_dispatcher.register(
+            input_device.id,
+            INPUT_EVENT_KIND_SWITCH_EVENT,
+            input_device_switch_switch_type,
+            handle_id,
+            Arc::new(move |event: &Value| {
+                let default = json!(0);
+                let value = event.get(INPUT_EVENT_VALUE).unwrap_or(&default);
+                input_device_switch.set(InputDeviceSwitchProperties::LAST_CHANGED.to_string(), timestamp_of(event));
+                input_device_switch.set(InputDeviceSwitchProperties::STATE.to_string(), value.clone());
+            }),
+        );
 
         Ok(SwitchEvent {
             relation: r.clone(),
             handle_id,
+            input_device_switch_switch_type,
+            input_device_event_dispatcher,
         })
     }
 
@@ -79,11 +66,13 @@ impl SwitchEvent {
 
 impl Disconnectable for SwitchEvent {
     fn disconnect(&self) {
-        debug!("Disconnecting behaviour {} from property instance {}", SWITCH_EVENT, self.handle_id);
-        let property = self.relation.inbound.properties.get(InputDeviceProperties::EVENT.as_ref());
-        if property.is_some() {
-            property.unwrap().stream.read().unwrap().remove(self.handle_id);
-        }
+        debug!("Disconnecting behaviour {} from input device {}", SWITCH_EVENT, self.relation.outbound.id);
+        self.input_device_event_dispatcher.unregister(
+            self.relation.outbound.id,
+            INPUT_EVENT_KIND_SWITCH_EVENT,
+            self.input_device_switch_switch_type,
+            self.handle_id,
+        );
     }
 }
 
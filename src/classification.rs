@@ -0,0 +1,109 @@
+use evdev::{AbsoluteAxisType, AttributeSetRef, Key, PropType, RelativeAxisType};
+use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+use crate::backend::InputBackendDevice;
+
+/// The role of an input device, which is derived from its capabilities like the input_id
+/// builtin of udev does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, IntoStaticStr, Display, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceClass {
+    #[strum(serialize = "keyboard")]
+    Keyboard,
+
+    #[strum(serialize = "mouse")]
+    Mouse,
+
+    #[strum(serialize = "touchpad")]
+    Touchpad,
+
+    #[strum(serialize = "touchscreen")]
+    Touchscreen,
+
+    #[strum(serialize = "tablet")]
+    Tablet,
+
+    #[strum(serialize = "joystick")]
+    Joystick,
+
+    #[strum(serialize = "gamepad")]
+    Gamepad,
+
+    #[strum(serialize = "switch")]
+    Switch,
+}
+
+/// Classifies the given input device by its supported keys, axes, switches and properties. An
+/// input device may belong to several device classes, for example the receiver of a wireless
+/// keyboard and mouse, or to none at all, for example the power button.
+pub fn classify(device: &dyn InputBackendDevice) -> Vec<DeviceClass> {
+    let input_properties = device.input_properties();
+    let mut device_classes = Vec::new();
+    // Accelerometers report absolute axes, but they are not pointing devices
+    if input_properties.contains(PropType::ACCELEROMETER) {
+        return device_classes;
+    }
+    let has_key = |key: Key| device.supported_keys().map(|keys| keys.contains(key)).unwrap_or(false);
+    let has_absolute_axis = |absolute_axis: AbsoluteAxisType| {
+        device
+            .supported_absolute_axes()
+            .map(|absolute_axes| absolute_axes.contains(absolute_axis))
+            .unwrap_or(false)
+    };
+    let has_relative_axis = |relative_axis: RelativeAxisType| {
+        device
+            .supported_relative_axes()
+            .map(|relative_axes| relative_axes.contains(relative_axis))
+            .unwrap_or(false)
+    };
+    let is_direct = input_properties.contains(PropType::DIRECT);
+    let has_pen = has_key(Key::BTN_STYLUS) || has_key(Key::BTN_TOOL_PEN);
+    let has_finger_but_no_pen = has_key(Key::BTN_TOOL_FINGER) && !has_key(Key::BTN_TOOL_PEN);
+    let has_touch = has_key(Key::BTN_TOUCH);
+    let has_mouse_button = has_key(Key::BTN_LEFT);
+    let has_gamepad_button = has_key(Key::BTN_SOUTH);
+    let has_joystick_button = has_key(Key::BTN_TRIGGER) || has_key(Key::BTN_THUMB);
+    let has_absolute_coordinates = has_absolute_axis(AbsoluteAxisType::ABS_X) && has_absolute_axis(AbsoluteAxisType::ABS_Y);
+    let has_multitouch_coordinates = has_absolute_axis(AbsoluteAxisType::ABS_MT_POSITION_X) && has_absolute_axis(AbsoluteAxisType::ABS_MT_POSITION_Y);
+    let has_relative_coordinates = has_relative_axis(RelativeAxisType::REL_X) && has_relative_axis(RelativeAxisType::REL_Y);
+    if has_absolute_coordinates || has_multitouch_coordinates {
+        if has_pen {
+            device_classes.push(DeviceClass::Tablet);
+        } else if has_finger_but_no_pen && !is_direct {
+            device_classes.push(DeviceClass::Touchpad);
+        } else if has_mouse_button {
+            // For example the absolute pointer of a virtual machine
+            device_classes.push(DeviceClass::Mouse);
+        } else if has_touch || is_direct {
+            device_classes.push(DeviceClass::Touchscreen);
+        } else if has_joystick_button && !has_gamepad_button {
+            device_classes.push(DeviceClass::Joystick);
+        }
+    }
+    if has_relative_coordinates && has_mouse_button && !device_classes.contains(&DeviceClass::Mouse) {
+        device_classes.push(DeviceClass::Mouse);
+    }
+    if has_gamepad_button {
+        device_classes.push(DeviceClass::Gamepad);
+    }
+    if device.supported_keys().map(is_keyboard).unwrap_or(false) {
+        device_classes.push(DeviceClass::Keyboard);
+    }
+    if device.supported_switches().map(|switches| switches.iter().next().is_some()).unwrap_or(false) {
+        device_classes.push(DeviceClass::Switch);
+    }
+    device_classes
+}
+
+/// Returns true, if no device classes are required or if the input device belongs to at least one
+/// of the required device classes.
+pub fn matches_device_classes(required_device_classes: &[DeviceClass], device_classes: &[DeviceClass]) -> bool {
+    required_device_classes.is_empty() || required_device_classes.iter().any(|device_class| device_classes.contains(device_class))
+}
+
+/// A keyboard supports all keys from KEY_ESC to KEY_S, which are the first 31 keys. Keypads,
+/// multimedia keys and power buttons support only a few of them.
+fn is_keyboard(keys: &AttributeSetRef<Key>) -> bool {
+    (Key::KEY_ESC.code()..=Key::KEY_S.code()).all(|code| keys.contains(Key::new(code)))
+}
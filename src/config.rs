@@ -1,15 +1,58 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::classification::DeviceClass;
+use crate::hotplug::DEFAULT_INPUT_DEVICE_PATH;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InputDevicesConfig {
     /// If true, the devices gets detected automatically
     pub autodetect: bool,
 
+    /// If not empty, only the input devices which belong to one of the device classes are
+    /// detected automatically.
+    #[serde(default)]
+    pub autodetect_device_classes: Vec<DeviceClass>,
+
+    /// The options of the input devices which are detected automatically.
+    #[serde(default)]
+    pub autodetect_options: InputDeviceOptions,
+
+    /// If true, input devices which are plugged in or unplugged at runtime are added or removed.
+    #[serde(default = "default_true")]
+    pub hotplug: bool,
+
+    /// If true, input devices which have been unplugged are kept (connected = false) and are
+    /// reopened as soon as they are plugged in again. Otherwise they are removed (default).
+    /// Unplugged input devices are kept until they are plugged in again or are no longer
+    /// configured, so with autodetect every input device which has ever been plugged in is kept.
+    #[serde(default)]
+    pub reconnect: bool,
+
+    /// Eager creates the keys, LEDs, axes and switches of the input devices up front. Lazy creates
+    /// them as soon as the first event of the key, LED, axis or switch arrives.
+    #[serde(default)]
+    pub entity_creation: EntityCreation,
+
+    /// The directory which contains the device nodes, usually /dev/input
+    #[serde(default = "default_input_device_path")]
+    pub input_device_path: String,
+
     /// The input devices (only if autodetect is false)
     pub input_device: Vec<InputDeviceConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityCreation {
+    /// The entities are created when the input device is added.
+    #[default]
+    Eager,
+
+    /// The entities are created on demand.
+    Lazy,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InputDeviceConfig {
     /// The name of the input device.
     pub name: String,
@@ -21,6 +64,36 @@ pub struct InputDeviceConfig {
     /// The path to the input device, usually /dev/input/by-id/...
     pub path: String,
 
+    /// If not empty, the input device is only loaded if it belongs to one of the device classes.
+    /// For example, the keyboard interface of a gaming keyboard can be told apart from its mouse
+    /// interface.
+    #[serde(default)]
+    pub device_class: Vec<DeviceClass>,
+
+    /// How the input device is managed.
+    #[serde(flatten)]
+    pub options: InputDeviceOptions,
+}
+
+/// How an input device is managed. The options are configured per input device or once for all
+/// input devices which are detected automatically.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InputDeviceOptions {
+    /// If true, the input device is grabbed exclusively. The events of the input device are
+    /// only delivered to the flows and not to other applications like the desktop.
+    #[serde(default)]
+    pub grab: bool,
+
+    /// If true, the events between two SYN_REPORTs are delivered together as one frame. For
+    /// example, the X and Y axes of a diagonal mouse movement are updated at once.
+    #[serde(default)]
+    pub frame_mode: bool,
+
+    /// If true, a virtual input device (uinput) is created which emits the synthetic events
+    /// of the input device, for example the key presses sent by a flow.
+    #[serde(default)]
+    pub virtual_device: bool,
+
     /// If true, the keys which are supported by the input device will be automatically detected.
     #[serde(default = "default_true")]
     pub autodetect_keys: bool,
@@ -42,6 +115,25 @@ pub struct InputDeviceConfig {
     pub autodetect_switches: bool,
 }
 
+impl Default for InputDeviceOptions {
+    fn default() -> Self {
+        InputDeviceOptions {
+            grab: false,
+            frame_mode: false,
+            virtual_device: false,
+            autodetect_keys: true,
+            autodetect_leds: true,
+            autodetect_relative_axes: true,
+            autodetect_absolute_axes: true,
+            autodetect_switches: true,
+        }
+    }
+}
+
 fn default_true() -> bool {
     true
 }
+
+fn default_input_device_path() -> String {
+    DEFAULT_INPUT_DEVICE_PATH.into()
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+
+/// The default directory which contains the evdev device nodes.
+pub const DEFAULT_INPUT_DEVICE_PATH: &'static str = "/dev/input";
+
+/// The prefix of the evdev device nodes (event0, event1, ...).
+pub const DEVICE_NODE_PREFIX: &'static str = "event";
+
+/// The directory which contains the persistent symlinks to the device nodes by the id of the input devices.
+pub const BY_ID_DIRECTORY: &'static str = "by-id";
+
+/// The directory which contains the persistent symlinks to the device nodes by the port the input devices are connected to.
+pub const BY_PATH_DIRECTORY: &'static str = "by-path";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceNodeEvent {
+    /// A device node has been created or its permissions have changed.
+    Added(PathBuf),
+
+    /// A device node has been removed.
+    Removed(PathBuf),
+}
+
+/// Watches a directory (usually /dev/input) for device nodes which appear or disappear.
+///
+/// The watcher doesn't open any device. It only reports the paths of the device nodes, so
+/// it can be pointed to any directory which stands in for /dev/input.
+///
+/// udev creates the persistent symlinks in by-id and by-path after the device node. The watcher
+/// reports the device node again as soon as a symlink to it appears, so input devices which are
+/// configured by their symlink are recognized as well.
+pub struct DeviceNodeWatcher {
+    path: PathBuf,
+
+    inotify: Inotify,
+
+    /// The watch of the directory which contains the device nodes.
+    device_node_watch: WatchDescriptor,
+
+    /// The watches of the directories which contain the persistent symlinks.
+    symlink_watches: HashMap<WatchDescriptor, PathBuf>,
+
+    buffer: Vec<u8>,
+}
+
+impl DeviceNodeWatcher {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<DeviceNodeWatcher> {
+        let path = path.as_ref().to_path_buf();
+        let inotify = Inotify::init()?;
+        let device_node_watch = inotify
+            .watches()
+            .add(&path, WatchMask::CREATE | WatchMask::DELETE | WatchMask::ATTRIB | WatchMask::MOVED_TO | WatchMask::MOVED_FROM)?;
+        let mut watcher = DeviceNodeWatcher {
+            path,
+            inotify,
+            device_node_watch,
+            symlink_watches: HashMap::new(),
+            buffer: vec![0; 4096],
+        };
+        // The symlink directories don't exist until udev has created the first symlink
+        watcher.watch_symlink_directory(BY_ID_DIRECTORY);
+        watcher.watch_symlink_directory(BY_PATH_DIRECTORY);
+        Ok(watcher)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Returns the device node events which happened since the last call without blocking.
+    pub fn poll(&mut self) -> io::Result<Vec<DeviceNodeEvent>> {
+        let mut device_node_events = Vec::new();
+        let events = match self.inotify.read_events(&mut self.buffer) {
+            Ok(events) => events,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(device_node_events),
+            Err(e) => return Err(e),
+        };
+        let mut created_symlink_directories = Vec::new();
+        for event in events {
+            let name = match event.name {
+                Some(name) => name,
+                None => continue,
+            };
+            if let Some(directory) = self.symlink_watches.get(&event.wd) {
+                // A symlink which appears (again) means the device node can be matched by its symlink now
+                if event.mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) {
+                    if let Some(device_node) = device_node_of_symlink(&self.path, &directory.join(name)) {
+                        device_node_events.push(DeviceNodeEvent::Added(device_node));
+                    }
+                }
+                continue;
+            }
+            if event.wd != self.device_node_watch {
+                continue;
+            }
+            if event.mask.contains(EventMask::CREATE | EventMask::ISDIR) {
+                created_symlink_directories.push(name.to_os_string());
+                continue;
+            }
+            if !is_device_node_name(name) {
+                continue;
+            }
+            let path = self.path.join(name);
+            if event.mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+                device_node_events.push(DeviceNodeEvent::Removed(path));
+            } else if event.mask.intersects(EventMask::CREATE | EventMask::ATTRIB | EventMask::MOVED_TO) {
+                device_node_events.push(DeviceNodeEvent::Added(path));
+            }
+        }
+        for name in created_symlink_directories {
+            if let Some(name) = name.to_str() {
+                device_node_events.extend(self.watch_symlink_directory(name));
+            }
+        }
+        Ok(device_node_events)
+    }
+
+    /// Starts watching the given symlink directory (by-id or by-path), if it exists. Returns the
+    /// device nodes of the symlinks which have been created before the directory was watched.
+    fn watch_symlink_directory(&mut self, name: &str) -> Vec<DeviceNodeEvent> {
+        if name != BY_ID_DIRECTORY && name != BY_PATH_DIRECTORY {
+            return Vec::new();
+        }
+        let directory = self.path.join(name);
+        if self.symlink_watches.values().any(|symlink_directory| *symlink_directory == directory) {
+            return Vec::new();
+        }
+        match self.inotify.watches().add(&directory, WatchMask::CREATE | WatchMask::MOVED_TO) {
+            Ok(watch) => {
+                self.symlink_watches.insert(watch, directory.clone());
+            }
+            Err(_) => return Vec::new(),
+        }
+        std::fs::read_dir(&directory)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| device_node_of_symlink(&self.path, &entry.path()))
+                    .map(DeviceNodeEvent::Added)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the paths of all device nodes which are currently present in the given directory.
+pub fn list_device_nodes<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+    let mut device_nodes: Vec<PathBuf> = match std::fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| is_device_node_name(entry.file_name().as_os_str()))
+            .map(|entry| entry.path())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    device_nodes.sort();
+    device_nodes
+}
+
+/// Returns the persistent symlink (for example /dev/input/by-id/usb-...-event-kbd) which points
+/// to the given device node, if any.
+pub fn by_id_path_of(device_node: &Path) -> Option<PathBuf> {
+    symlink_of(device_node, BY_ID_DIRECTORY)
+}
+
+/// Returns the persistent symlink (for example /dev/input/by-path/pci-...-usb-0:2:1.0-event-kbd)
+/// which points to the given device node, if any.
+pub fn by_path_path_of(device_node: &Path) -> Option<PathBuf> {
+    symlink_of(device_node, BY_PATH_DIRECTORY)
+}
+
+fn symlink_of(device_node: &Path, directory: &str) -> Option<PathBuf> {
+    std::fs::read_dir(device_node.parent()?.join(directory))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| std::fs::canonicalize(path).map(|target| target == device_node).unwrap_or(false))
+}
+
+/// Returns the device node in the given directory which the given symlink points to, for example ../event3.
+fn device_node_of_symlink(path: &Path, symlink: &Path) -> Option<PathBuf> {
+    let target = std::fs::read_link(symlink).ok()?;
+    let name = target.file_name()?;
+    if !is_device_node_name(name) {
+        return None;
+    }
+    Some(path.join(name))
+}
+
+fn is_device_node_name(name: &OsStr) -> bool {
+    name.to_str().map(|name| name.starts_with(DEVICE_NODE_PREFIX)).unwrap_or(false)
+}
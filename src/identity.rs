@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use strum_macros::{AsRefStr, Display, IntoStaticStr};
+
+use crate::backend::InputBackendDevice;
+
+/// The directory which contains the input devices in the sysfs.
+pub const SYS_CLASS_INPUT: &'static str = "/sys/class/input";
+
+/// The source of the identity of an input device, from the most to the least stable one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, IntoStaticStr, Display)]
+pub enum IdentityStrategy {
+    /// The unique id (uniq) which is reported by the kernel, for example a serial number.
+    #[strum(serialize = "unique_id")]
+    UniqueId,
+
+    /// The symlink in /dev/input/by-path. Identical input devices are told apart by the port
+    /// they are connected to.
+    #[strum(serialize = "by_path")]
+    ByPath,
+
+    /// The symlink in /dev/input/by-id. It is preferred less than by-path, because udev creates
+    /// only one by-id symlink for identical input devices without a serial number.
+    #[strum(serialize = "by_id")]
+    ById,
+
+    /// The physical path which is reported by the kernel.
+    #[strum(serialize = "physical_path")]
+    PhysicalPath,
+
+    /// The device node, which may change after a reboot or after the input device has been
+    /// plugged in again.
+    #[strum(serialize = "device_node")]
+    DeviceNode,
+}
+
+/// The identity of an input device, which the ids of its entity instances are derived from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub identity: String,
+
+    pub strategy: IdentityStrategy,
+}
+
+/// Derives the identity of the given input device. The bus type, the vendor and the product are
+/// part of every identity, so that input devices of different models never collide.
+pub fn identity_of(device: &dyn InputBackendDevice) -> DeviceIdentity {
+    let input_id = device.input_id();
+    let model = format!("{:04x}:{:04x}:{:04x}", input_id.bus_type().0, input_id.vendor(), input_id.product());
+    let name = device.name().unwrap_or("Unnamed Device");
+    let physical_path = device.physical_path().unwrap_or("");
+    if let Some(unique_id) = device.unique_id().filter(|unique_id| !unique_id.is_empty()) {
+        // The interfaces of the same hardware share the unique id
+        let identity = match interface_of(physical_path) {
+            Some(interface) => format!("{}-{}-{}-{}", model, unique_id, name, interface),
+            None => format!("{}-{}-{}", model, unique_id, name),
+        };
+        return DeviceIdentity {
+            identity,
+            strategy: IdentityStrategy::UniqueId,
+        };
+    }
+    if let Some(by_path) = device.by_path().as_deref().and_then(file_name_of) {
+        return DeviceIdentity {
+            identity: format!("{}-{}", model, by_path),
+            strategy: IdentityStrategy::ByPath,
+        };
+    }
+    if let Some(by_id) = device.by_id().as_deref().and_then(file_name_of) {
+        return DeviceIdentity {
+            identity: format!("{}-{}", model, by_id),
+            strategy: IdentityStrategy::ById,
+        };
+    }
+    if !physical_path.is_empty() {
+        return DeviceIdentity {
+            identity: format!("{}-{}-{}", model, name, physical_path),
+            strategy: IdentityStrategy::PhysicalPath,
+        };
+    }
+    DeviceIdentity {
+        identity: format!("{}-{}-{}", model, name, device.device_node().display()),
+        strategy: IdentityStrategy::DeviceNode,
+    }
+}
+
+/// How the input devices are grouped into physical devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, IntoStaticStr, Display)]
+pub enum GroupingStrategy {
+    /// The interfaces share the unique id (uniq), for example the bluetooth address of a gamepad.
+    #[strum(serialize = "unique_id")]
+    UniqueId,
+
+    /// The interfaces belong to the same USB device.
+    #[strum(serialize = "usb_device")]
+    UsbDevice,
+
+    /// The physical paths of the interfaces only differ in the interface, for example
+    /// usb-0000:00:14.0-2/input0 and usb-0000:00:14.0-2/input1.
+    #[strum(serialize = "physical_path")]
+    PhysicalPath,
+
+    /// The input device isn't grouped with other input devices.
+    #[strum(serialize = "identity")]
+    Identity,
+}
+
+/// The identity of the physical device (the hardware) an input device belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalDeviceIdentity {
+    pub identity: String,
+
+    pub strategy: GroupingStrategy,
+}
+
+/// Derives the identity of the physical device of the given input device. Gaming keyboards and
+/// mice expose several input devices (interfaces) which share the same physical device identity.
+pub fn physical_device_identity_of(device: &dyn InputBackendDevice) -> PhysicalDeviceIdentity {
+    let input_id = device.input_id();
+    let model = format!("{:04x}:{:04x}:{:04x}", input_id.bus_type().0, input_id.vendor(), input_id.product());
+    if let Some(unique_id) = device.unique_id().filter(|unique_id| !unique_id.is_empty()) {
+        return PhysicalDeviceIdentity {
+            identity: format!("{}-{}", model, unique_id),
+            strategy: GroupingStrategy::UniqueId,
+        };
+    }
+    if let Some(usb_device) = device.sysfs_path().and_then(|sysfs_path| usb_device_of(&sysfs_path)) {
+        return PhysicalDeviceIdentity {
+            identity: format!("{}-{}", model, usb_device.display()),
+            strategy: GroupingStrategy::UsbDevice,
+        };
+    }
+    let physical_path = device.physical_path().unwrap_or("");
+    if let Some(interface) = interface_of(physical_path) {
+        let parent = physical_path.trim_end_matches(interface).trim_end_matches('/');
+        if !parent.is_empty() {
+            return PhysicalDeviceIdentity {
+                identity: format!("{}-{}", model, parent),
+                strategy: GroupingStrategy::PhysicalPath,
+            };
+        }
+    }
+    PhysicalDeviceIdentity {
+        identity: identity_of(device).identity,
+        strategy: GroupingStrategy::Identity,
+    }
+}
+
+/// Returns the path of the input device in the sysfs, for example
+/// /sys/devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/0003:1532:011A.0001/input/input5.
+pub fn sysfs_path_of(device_node: &Path) -> Option<PathBuf> {
+    let device_node_name = device_node.file_name()?;
+    std::fs::canonicalize(Path::new(SYS_CLASS_INPUT).join(device_node_name).join("device")).ok()
+}
+
+/// Returns the USB device the given sysfs path belongs to. The USB device is the closest parent
+/// which has a vendor id, the interfaces between them don't have one.
+fn usb_device_of(sysfs_path: &Path) -> Option<PathBuf> {
+    sysfs_path.ancestors().find(|path| path.join("idVendor").is_file()).map(Path::to_path_buf)
+}
+
+/// Returns the interface of the physical path, for example input1 of usb-0000:00:14.0-2/input1.
+fn interface_of(physical_path: &str) -> Option<&str> {
+    physical_path.rsplit('/').next().filter(|interface| interface.starts_with("input"))
+}
+
+fn file_name_of(path: &Path) -> Option<String> {
+    path.file_name().and_then(|file_name| file_name.to_str()).map(String::from)
+}
@@ -2,16 +2,21 @@ use crate::di::*;
 use async_trait::async_trait;
 use log::{error, trace};
 
-use crate::api::{InputDeviceAbsoluteAxisManager, INPUT_DEVICE_ABSOLUTE_AXIS, NAMESPACE_INPUT_DEVICE};
+use crate::api::{InputDeviceAbsoluteAxisManager, ANY_DEVICE_LABEL, INPUT_DEVICE_ABSOLUTE_AXIS, NAMESPACE_INPUT_DEVICE};
+use crate::backend::{AbsoluteAxisInfo, InputBackendDevice};
 use crate::behaviour::entity::InputDeviceAbsoluteAxisProperties;
+use crate::behaviour::entity::InputDeviceProperties;
 use crate::behaviour::relation::absolute_axis_event::ABSOLUTE_AXIS_EVENT;
+use crate::behaviour::relation::send_state_event::SEND_ABSOLUTE_AXIS_EVENT;
 use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::{AbsoluteAxisType, Device};
+use evdev::AbsoluteAxisType;
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -23,9 +28,20 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     return PluginContextContainer(RwLock::new(None));
 }
 
+/// The ids of the absolute axes of each input device, including the absolute axes of the pseudo device any-device.
+#[wrapper]
+pub struct InputDeviceAbsoluteAxisStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_input_device_absolute_axis_storage() -> InputDeviceAbsoluteAxisStorage {
+    InputDeviceAbsoluteAxisStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceAbsoluteAxisManagerImpl {
     context: PluginContextContainer,
+
+    input_device_absolute_axes: InputDeviceAbsoluteAxisStorage,
 }
 
 impl InputDeviceAbsoluteAxisManagerImpl {}
@@ -37,7 +53,7 @@ impl InputDeviceAbsoluteAxisManager for InputDeviceAbsoluteAxisManagerImpl {
         self.context.0.write().unwrap().replace(context.clone());
     }
 
-    fn create_input_device_absolute_axes(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>) {
+    fn create_input_device_absolute_axes(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>) {
         let supported_absolute_axes = device.supported_absolute_axes();
         match supported_absolute_axes {
             Some(supported_absolute_axes) => {
@@ -50,17 +66,20 @@ impl InputDeviceAbsoluteAxisManager for InputDeviceAbsoluteAxisManagerImpl {
         }
     }
 
-    fn create_input_device_absolute_axis(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType) {
+    fn create_input_device_absolute_axis(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType) {
         let reader = self.context.0.read().unwrap();
         let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
-        let device_name = device.name().unwrap_or("Unnamed Device");
-        let physical_path = device.physical_path().unwrap_or("");
+        // The ids and the labels are derived from the identity and the label of the input device
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
         let absolute_axis_name = format!("{:?}", absolute_axis);
-        let unique_name = format!("{}-{}-{}", device_name, physical_path, absolute_axis_name);
+        let unique_name = format!("{}-{}", identity, absolute_axis_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
-        let input_device_absolute_axis = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), absolute_axis_name.clone(), absolute_axis);
+        let absolute_axis_info = device.absolute_axis_info(absolute_axis).unwrap_or_default();
+        let input_device_absolute_axis =
+            self.create_entity_instance(uuid, device_label, unique_name.clone(), absolute_axis_name.clone(), absolute_axis, absolute_axis_info);
         let input_device_absolute_axis = entity_instance_manager.create(input_device_absolute_axis);
-        self.try_create_absolute_axis_event(input_device, input_device_absolute_axis, unique_name);
+        self.try_create_absolute_axis_event(input_device, input_device_absolute_axis, unique_name, true);
     }
 
     fn create_any_device_absolute_axis(&self, input_device: Arc<ReactiveEntityInstance>, absolute_axis: AbsoluteAxisType) {
@@ -71,30 +90,47 @@ impl InputDeviceAbsoluteAxisManager for InputDeviceAbsoluteAxisManagerImpl {
         let unique_name = format!("{}-{}", device_name, absolute_axis_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
         if !entity_instance_manager.has(uuid) {
-            let input_device_absolute_axis =
-                self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), absolute_axis_name.clone(), absolute_axis);
+            let input_device_absolute_axis = self.create_entity_instance(
+                uuid,
+                ANY_DEVICE_LABEL.into(),
+                unique_name.clone(),
+                absolute_axis_name.clone(),
+                absolute_axis,
+                AbsoluteAxisInfo::default(),
+            );
             let input_device_absolute_axis = entity_instance_manager.create(input_device_absolute_axis);
-            self.try_create_absolute_axis_event(input_device, input_device_absolute_axis, unique_name);
+            self.try_create_absolute_axis_event(input_device, input_device_absolute_axis, unique_name, false);
         } else {
-            self.create_absolute_axis_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone());
+            self.create_absolute_axis_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone(), false);
         }
     }
 
     fn create_entity_instance(
         &self,
         uuid: Uuid,
-        device_name: String,
+        device_label: String,
         unique_name: String,
         absolute_axis_name: String,
         absolute_axis: AbsoluteAxisType,
+        absolute_axis_info: AbsoluteAxisInfo,
     ) -> EntityInstance {
         EntityInstanceBuilder::new(INPUT_DEVICE_ABSOLUTE_AXIS)
             .id(uuid)
             .property(InputDeviceAbsoluteAxisProperties::NAME, json!(unique_name))
-            .property(InputDeviceAbsoluteAxisProperties::LABEL, unique_label(device_name, absolute_axis_name.clone()))
+            .property(InputDeviceAbsoluteAxisProperties::LABEL, unique_label(device_label, absolute_axis_name.clone()))
             .property(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS, json!(absolute_axis_name))
             .property(InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS_TYPE, json!(absolute_axis.0))
-            .property(InputDeviceAbsoluteAxisProperties::STATE, InputDeviceAbsoluteAxisProperties::STATE.default_value())
+            .property(InputDeviceAbsoluteAxisProperties::MINIMUM, json!(absolute_axis_info.minimum))
+            .property(InputDeviceAbsoluteAxisProperties::MAXIMUM, json!(absolute_axis_info.maximum))
+            .property(InputDeviceAbsoluteAxisProperties::FUZZ, json!(absolute_axis_info.fuzz))
+            .property(InputDeviceAbsoluteAxisProperties::FLAT, json!(absolute_axis_info.flat))
+            .property(InputDeviceAbsoluteAxisProperties::RESOLUTION, json!(absolute_axis_info.resolution))
+            .property(InputDeviceAbsoluteAxisProperties::STATE, json!(absolute_axis_info.value))
+            .property(InputDeviceAbsoluteAxisProperties::NORMALIZED, json!(absolute_axis_info.normalize(absolute_axis_info.value)))
+            .property(
+                InputDeviceAbsoluteAxisProperties::LAST_CHANGED,
+                InputDeviceAbsoluteAxisProperties::LAST_CHANGED.default_value(),
+            )
             .get()
     }
 
@@ -103,11 +139,12 @@ impl InputDeviceAbsoluteAxisManager for InputDeviceAbsoluteAxisManagerImpl {
         input_device: Arc<ReactiveEntityInstance>,
         input_device_absolute_axis: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_absolute_axis: bool,
     ) {
         match input_device_absolute_axis {
             Ok(input_device_absolute_axis) => {
                 trace!("Registered {} {} as {}", INPUT_DEVICE_ABSOLUTE_AXIS, unique_name, input_device_absolute_axis.id);
-                self.create_absolute_axis_event(input_device.clone(), input_device_absolute_axis.clone());
+                self.create_absolute_axis_event(input_device.clone(), input_device_absolute_axis.clone(), create_send_absolute_axis);
             }
             Err(_) => {
                 error!("Failed to create entity instance for {} {}!", INPUT_DEVICE_ABSOLUTE_AXIS, unique_name);
@@ -115,18 +152,71 @@ impl InputDeviceAbsoluteAxisManager for InputDeviceAbsoluteAxisManagerImpl {
         }
     }
 
-    fn create_absolute_axis_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_absolute_axis: Arc<ReactiveEntityInstance>) {
+    fn create_absolute_axis_event(
+        &self,
+        input_device: Arc<ReactiveEntityInstance>,
+        input_device_absolute_axis: Arc<ReactiveEntityInstance>,
+        create_send_absolute_axis: bool,
+    ) {
+        self.input_device_absolute_axes
+            .0
+            .write()
+            .unwrap()
+            .entry(input_device.id)
+            .or_default()
+            .insert(input_device_absolute_axis.id);
         let reader = self.context.0.read().unwrap();
         let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
         let absolute_axis_event = RelationInstanceBuilder::new(input_device.id, ABSOLUTE_AXIS_EVENT, input_device_absolute_axis.id).get();
         let _absolute_axis_event = relation_instance_manager.create(absolute_axis_event);
+        if create_send_absolute_axis {
+            let send_absolute_axis_event = RelationInstanceBuilder::new(input_device_absolute_axis.id, SEND_ABSOLUTE_AXIS_EVENT, input_device.id).get();
+            let _send_absolute_axis_event = relation_instance_manager.create(send_absolute_axis_event);
+        }
+    }
+
+    fn remove_input_device_absolute_axes(&self, input_device_id: Uuid) {
+        let input_device_absolute_axes = match self.input_device_absolute_axes.0.write().unwrap().remove(&input_device_id) {
+            Some(input_device_absolute_axes) => input_device_absolute_axes,
+            None => return,
+        };
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        for input_device_absolute_axis_id in input_device_absolute_axes {
+            // The absolute axes of any-device are kept as long as another input device provides them
+            let provided = self
+                .input_device_absolute_axes
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|input_device_absolute_axes| input_device_absolute_axes.contains(&input_device_absolute_axis_id));
+            let relation_instances = relation_instance_manager
+                .get_by_inbound_entity(input_device_absolute_axis_id)
+                .into_iter()
+                .chain(relation_instance_manager.get_by_outbound_entity(input_device_absolute_axis_id));
+            for relation_instance in relation_instances {
+                // Only the relations from and to the removed input device (absolute_axis_event, send_absolute_axis_event)
+                if provided && relation_instance.outbound.id != input_device_id && relation_instance.inbound.id != input_device_id {
+                    continue;
+                }
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            if !provided {
+                entity_instance_manager.delete(input_device_absolute_axis_id);
+                trace!("Removed {} {}", INPUT_DEVICE_ABSOLUTE_AXIS, input_device_absolute_axis_id);
+            }
+        }
     }
 }
 
-fn unique_label(device_name: String, absolute_axis_name: String) -> Value {
+fn unique_label(device_label: String, absolute_axis_name: String) -> Value {
     json!(format!(
-        "/org/inexor/input/{}/absolute_axis/{}",
-        device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_"),
-        absolute_axis_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")
+        "{}/absolute_axis/{}",
+        device_label,
+        absolute_axis_name.to_lowercase().replace("-", "_").replace(" ", "_")
     ))
 }
@@ -0,0 +1,43 @@
+use crate::di::*;
+use async_trait::async_trait;
+
+use crate::api::InputDeviceBackendManager;
+use crate::backend::{EvdevBackend, InputBackend};
+use crate::config::InputDevicesConfig;
+use crate::hotplug::DEFAULT_INPUT_DEVICE_PATH;
+use std::sync::{Arc, RwLock};
+
+#[wrapper]
+pub struct InputBackendContainer(RwLock<Option<Arc<dyn InputBackend>>>);
+
+#[provides]
+fn create_empty_input_backend_container() -> InputBackendContainer {
+    InputBackendContainer(RwLock::new(None))
+}
+
+#[component]
+pub struct InputDeviceBackendManagerImpl {
+    backend: InputBackendContainer,
+}
+
+impl InputDeviceBackendManagerImpl {}
+
+#[async_trait]
+#[provides]
+impl InputDeviceBackendManager for InputDeviceBackendManagerImpl {
+    fn configure(&self, config: &InputDevicesConfig) {
+        let mut writer = self.backend.0.write().unwrap();
+        if writer.is_none() {
+            writer.replace(Arc::new(EvdevBackend::new(&config.input_device_path)));
+        }
+    }
+
+    fn get_backend(&self) -> Arc<dyn InputBackend> {
+        let mut writer = self.backend.0.write().unwrap();
+        writer.get_or_insert_with(|| Arc::new(EvdevBackend::new(DEFAULT_INPUT_DEVICE_PATH))).clone()
+    }
+
+    fn set_backend(&self, backend: Arc<dyn InputBackend>) {
+        self.backend.0.write().unwrap().replace(backend);
+    }
+}
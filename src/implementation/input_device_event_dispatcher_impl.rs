@@ -0,0 +1,162 @@
+use crate::di::*;
+use async_trait::async_trait;
+
+use crate::api::{InputDeviceEventDispatcher, InputEntityFactory, InputEventHandler};
+use crate::backend::DeviceHandle;
+use crate::behaviour::event_payload::kind_and_code_of;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use uuid::Uuid;
+
+/// The handlers of an input device by the kind and the code of the events.
+type InputEventHandlers = HashMap<String, HashMap<i64, Vec<(u128, InputEventHandler)>>>;
+
+#[wrapper]
+pub struct InputEventHandlerStorage(RwLock<HashMap<Uuid, InputEventHandlers>>);
+
+#[provides]
+fn create_input_event_handler_storage() -> InputEventHandlerStorage {
+    InputEventHandlerStorage(RwLock::new(HashMap::new()))
+}
+
+/// The fallbacks by the id of the input device.
+#[wrapper]
+pub struct InputEventFallbackStorage(RwLock<HashMap<Uuid, InputEntityFactory>>);
+
+#[provides]
+fn create_input_event_fallback_storage() -> InputEventFallbackStorage {
+    InputEventFallbackStorage(RwLock::new(HashMap::new()))
+}
+
+#[component]
+pub struct InputDeviceEventDispatcherImpl {
+    handlers: InputEventHandlerStorage,
+
+    fallbacks: InputEventFallbackStorage,
+}
+
+impl InputDeviceEventDispatcherImpl {
+    fn get_handlers(&self, input_device_id: Uuid, input_event_kind: &str, code: i64) -> Option<Vec<InputEventHandler>> {
+        self.handlers
+            .0
+            .read()
+            .unwrap()
+            .get(&input_device_id)
+            .and_then(|handlers_by_kind| handlers_by_kind.get(input_event_kind))
+            .and_then(|handlers_by_code| handlers_by_code.get(&code))
+            .map(|handlers| handlers.iter().map(|(_, handler)| handler.clone()).collect())
+    }
+
+    /// Calls the fallback of the input device with the handle of the input device. Returns false,
+    /// if there is no fallback or the input device is disconnected.
+    fn call_fallback(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, device: &DeviceHandle) -> bool {
+        let fallback = match self.fallbacks.0.read().unwrap().get(&input_device_id).cloned() {
+            Some(fallback) => fallback,
+            None => return false,
+        };
+        // The handle isn't used for sending while the entities are created
+        match device.lock().unwrap().as_deref() {
+            Some(device) => {
+                fallback(input_event_kind, code, device);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+#[provides]
+impl InputDeviceEventDispatcher for InputDeviceEventDispatcherImpl {
+    fn register(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, handle_id: u128, handler: InputEventHandler) {
+        let mut writer = self.handlers.0.write().unwrap();
+        let handlers = writer
+            .entry(input_device_id)
+            .or_insert_with(HashMap::new)
+            .entry(input_event_kind.to_string())
+            .or_insert_with(HashMap::new)
+            .entry(code)
+            .or_insert_with(Vec::new);
+        handlers.retain(|(id, _)| *id != handle_id);
+        handlers.push((handle_id, handler));
+    }
+
+    fn unregister(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, handle_id: u128) {
+        let mut writer = self.handlers.0.write().unwrap();
+        if let Some(handlers_by_kind) = writer.get_mut(&input_device_id) {
+            if let Some(handlers_by_code) = handlers_by_kind.get_mut(input_event_kind) {
+                if let Some(handlers) = handlers_by_code.get_mut(&code) {
+                    handlers.retain(|(id, _)| *id != handle_id);
+                    if handlers.is_empty() {
+                        handlers_by_code.remove(&code);
+                    }
+                }
+                if handlers_by_code.is_empty() {
+                    handlers_by_kind.remove(input_event_kind);
+                }
+            }
+            if handlers_by_kind.is_empty() {
+                writer.remove(&input_device_id);
+            }
+        }
+    }
+
+    fn dispatch(&self, input_device_id: Uuid, event: &Value, device: &DeviceHandle) {
+        let (input_event_kind, code) = match kind_and_code_of(event) {
+            Some(kind_and_code) => kind_and_code,
+            None => return,
+        };
+        // The handlers are called without holding the lock, because a handler may cause the
+        // creation or removal of other handlers
+        let handlers = match self.get_handlers(input_device_id, input_event_kind, code) {
+            Some(handlers) => handlers,
+            None => {
+                if !self.call_fallback(input_device_id, input_event_kind, code, device) {
+                    return;
+                }
+                self.get_handlers(input_device_id, input_event_kind, code).unwrap_or_default()
+            }
+        };
+        for handler in handlers {
+            handler(event);
+        }
+    }
+
+    fn materialize(&self, input_device_id: Uuid, input_event_kind: &str, code: i64, device: &DeviceHandle) -> bool {
+        if self.has_handlers(input_device_id, input_event_kind, code) {
+            return true;
+        }
+        self.call_fallback(input_device_id, input_event_kind, code, device) && self.has_handlers(input_device_id, input_event_kind, code)
+    }
+
+    fn has_handlers(&self, input_device_id: Uuid, input_event_kind: &str, code: i64) -> bool {
+        self.get_handlers(input_device_id, input_event_kind, code)
+            .map(|handlers| !handlers.is_empty())
+            .unwrap_or(false)
+    }
+
+    fn register_fallback(&self, input_device_id: Uuid, fallback: InputEntityFactory) {
+        self.fallbacks.0.write().unwrap().insert(input_device_id, fallback);
+    }
+
+    fn unregister_fallback(&self, input_device_id: Uuid) {
+        self.fallbacks.0.write().unwrap().remove(&input_device_id);
+    }
+
+    fn count_handlers(&self, input_device_id: Uuid) -> usize {
+        self.handlers
+            .0
+            .read()
+            .unwrap()
+            .get(&input_device_id)
+            .map(|handlers_by_kind| {
+                handlers_by_kind
+                    .values()
+                    .flat_map(|handlers_by_code| handlers_by_code.values())
+                    .map(Vec::len)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::di::*;
+use async_trait::async_trait;
+use log::{debug, error};
+
+use crate::api::{InputDeviceHotplugManager, InputDeviceManager};
+use crate::hotplug::{DeviceNodeEvent, DeviceNodeWatcher};
+use std::sync::RwLock;
+use std::thread;
+use std::time::Duration;
+
+/// The interval in which the device node watcher is polled.
+const HOTPLUG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[wrapper]
+pub struct HotplugStopperContainer(RwLock<Option<crossbeam::channel::Sender<()>>>);
+
+#[provides]
+fn create_empty_hotplug_stopper_container() -> HotplugStopperContainer {
+    HotplugStopperContainer(RwLock::new(None))
+}
+
+#[component]
+pub struct InputDeviceHotplugManagerImpl {
+    input_device_manager: Wrc<dyn InputDeviceManager>,
+
+    stopper: HotplugStopperContainer,
+}
+
+impl InputDeviceHotplugManagerImpl {}
+
+#[async_trait]
+#[provides]
+impl InputDeviceHotplugManager for InputDeviceHotplugManagerImpl {
+    fn start(&self) {
+        let config = self.input_device_manager.get_config();
+        if config.is_none() {
+            return;
+        }
+        let config = config.unwrap();
+        if !config.hotplug {
+            return;
+        }
+        let mut watcher = match DeviceNodeWatcher::new(&config.input_device_path) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                error!("Failed to watch {} for hotplugged input devices: {}", config.input_device_path, e);
+                return;
+            }
+        };
+        let (stopper_tx, stopper_rx) = crossbeam::channel::bounded(1);
+        self.stopper.0.write().unwrap().replace(stopper_tx);
+        let input_device_manager = self.input_device_manager.clone();
+        let _handle = thread::Builder::new().name("input-device-hotplug".into()).spawn(move || {
+            debug!("Watching {} for hotplugged input devices", watcher.path().display());
+            loop {
+                match stopper_rx.recv_timeout(HOTPLUG_POLL_INTERVAL) {
+                    Err(crossbeam::channel::RecvTimeoutError::Timeout) => {}
+                    // Stop thread
+                    _ => break,
+                }
+                match watcher.poll() {
+                    Ok(events) => {
+                        for event in events {
+                            match event {
+                                DeviceNodeEvent::Added(device_node) => input_device_manager.add_device_node(&device_node),
+                                DeviceNodeEvent::Removed(device_node) => input_device_manager.remove_device_node(&device_node),
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to read hotplug events: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    fn stop(&self) {
+        if let Some(stopper) = self.stopper.0.write().unwrap().take() {
+            let _ = stopper.send(());
+        }
+    }
+}
@@ -2,17 +2,21 @@ use crate::di::*;
 use async_trait::async_trait;
 use log::{error, trace};
 
-use crate::api::{InputDeviceKeyManager, INPUT_DEVICE_KEY, NAMESPACE_INPUT_DEVICE};
+use crate::api::{InputDeviceKeyManager, ANY_DEVICE_LABEL, INPUT_DEVICE_KEY, NAMESPACE_INPUT_DEVICE};
+use crate::backend::InputBackendDevice;
 use crate::behaviour::entity::InputDeviceKeyProperties;
+use crate::behaviour::entity::InputDeviceProperties;
 use crate::behaviour::relation::key_event::KEY_EVENT;
 use crate::behaviour::relation::send_key_event::SEND_KEY_EVENT;
 use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::{Device, Key};
+use evdev::{AttributeSet, Key};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -24,9 +28,20 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     return PluginContextContainer(RwLock::new(None));
 }
 
+/// The ids of the keys of each input device, including the keys of the pseudo device any-device.
+#[wrapper]
+pub struct InputDeviceKeyStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_input_device_key_storage() -> InputDeviceKeyStorage {
+    InputDeviceKeyStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceKeyManagerImpl {
     context: PluginContextContainer,
+
+    input_device_keys: InputDeviceKeyStorage,
 }
 
 impl InputDeviceKeyManagerImpl {}
@@ -38,12 +53,13 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
         self.context.0.write().unwrap().replace(context.clone());
     }
 
-    fn create_input_device_keys(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>) {
+    fn create_input_device_keys(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>) {
         let supported_keys = device.supported_keys();
         match supported_keys {
             Some(supported_keys) => {
+                let key_state = device.key_state().ok();
                 for key in supported_keys.iter() {
-                    self.create_input_device_key(device, entity_instance.clone(), key);
+                    self.create_input_device_key(entity_instance.clone(), key, key_state.as_ref());
                     self.create_any_device_key(entity_instance.clone(), key);
                 }
             }
@@ -51,15 +67,17 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
         }
     }
 
-    fn create_input_device_key(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, key: Key) {
+    fn create_input_device_key(&self, input_device: Arc<ReactiveEntityInstance>, key: Key, key_state: Option<&AttributeSet<Key>>) {
         let reader = self.context.0.read().unwrap();
         let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
-        let device_name = device.name().unwrap_or("Unnamed Device");
-        let physical_path = device.physical_path().unwrap_or("");
+        // The ids and the labels are derived from the identity and the label of the input device
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
         let key_name = format!("{:?}", key);
-        let unique_name = format!("{}-{}-{}", device_name, physical_path, key_name);
+        let unique_name = format!("{}-{}", identity, key_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
-        let input_device_key = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), key_name.clone(), key);
+        let key_down = key_state.map(|key_state| key_state.contains(key)).unwrap_or(false);
+        let input_device_key = self.create_entity_instance(uuid, device_label, unique_name.clone(), key_name.clone(), key, key_down);
         let input_device_key = entity_instance_manager.create(input_device_key);
         self.try_create_key_event(input_device, input_device_key, unique_name, true);
     }
@@ -72,7 +90,7 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
         let unique_name = format!("{}-{}", device_name, key_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
         if !entity_instance_manager.has(uuid) {
-            let input_device_key = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), key_name.clone(), key);
+            let input_device_key = self.create_entity_instance(uuid, ANY_DEVICE_LABEL.into(), unique_name.clone(), key_name.clone(), key, false);
             let input_device_key = entity_instance_manager.create(input_device_key);
             self.try_create_key_event(input_device, input_device_key, unique_name, false);
         } else {
@@ -80,14 +98,15 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
         }
     }
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, key_name: String, key: Key) -> EntityInstance {
+    fn create_entity_instance(&self, uuid: Uuid, device_label: String, unique_name: String, key_name: String, key: Key, key_down: bool) -> EntityInstance {
         EntityInstanceBuilder::new(INPUT_DEVICE_KEY)
             .id(uuid)
             .property(InputDeviceKeyProperties::NAME, json!(unique_name.clone()))
-            .property(InputDeviceKeyProperties::LABEL, unique_label(device_name, key_name.clone()))
+            .property(InputDeviceKeyProperties::LABEL, unique_label(device_label, key_name.clone()))
             .property(InputDeviceKeyProperties::KEY, json!(key_name))
             .property(InputDeviceKeyProperties::KEY_CODE, json!(key.code()))
-            .property(InputDeviceKeyProperties::KEY_DOWN, json!(false))
+            .property(InputDeviceKeyProperties::KEY_DOWN, json!(key_down))
+            .property(InputDeviceKeyProperties::LAST_CHANGED, InputDeviceKeyProperties::LAST_CHANGED.default_value())
             .get()
     }
 
@@ -110,6 +129,13 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
     }
 
     fn create_key_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_key: Arc<ReactiveEntityInstance>, create_send_key: bool) {
+        self.input_device_keys
+            .0
+            .write()
+            .unwrap()
+            .entry(input_device.id)
+            .or_default()
+            .insert(input_device_key.id);
         let reader = self.context.0.read().unwrap();
         let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
         let key_event = RelationInstanceBuilder::new(input_device.id, KEY_EVENT, input_device_key.id).get();
@@ -119,12 +145,45 @@ impl InputDeviceKeyManager for InputDeviceKeyManagerImpl {
             let _send_key_event = relation_instance_manager.create(send_key_event);
         }
     }
+
+    fn remove_input_device_keys(&self, input_device_id: Uuid) {
+        let input_device_keys = match self.input_device_keys.0.write().unwrap().remove(&input_device_id) {
+            Some(input_device_keys) => input_device_keys,
+            None => return,
+        };
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        for input_device_key_id in input_device_keys {
+            // The keys of any-device are kept as long as another input device provides them
+            let provided = self
+                .input_device_keys
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|input_device_keys| input_device_keys.contains(&input_device_key_id));
+            let relation_instances = relation_instance_manager
+                .get_by_inbound_entity(input_device_key_id)
+                .into_iter()
+                .chain(relation_instance_manager.get_by_outbound_entity(input_device_key_id));
+            for relation_instance in relation_instances {
+                // Only the relations from and to the removed input device (key_event, send_key_event)
+                if provided && relation_instance.outbound.id != input_device_id && relation_instance.inbound.id != input_device_id {
+                    continue;
+                }
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            if !provided {
+                entity_instance_manager.delete(input_device_key_id);
+                trace!("Removed {} {}", INPUT_DEVICE_KEY, input_device_key_id);
+            }
+        }
+    }
 }
 
-fn unique_label(device_name: String, key_name: String) -> Value {
-    json!(format!(
-        "/org/inexor/input/{}/key/{}",
-        device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_"),
-        key_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")
-    ))
+fn unique_label(device_label: String, key_name: String) -> Value {
+    json!(format!("{}/key/{}", device_label, key_name.to_lowercase().replace("-", "_").replace(" ", "_")))
 }
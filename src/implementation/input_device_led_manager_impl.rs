@@ -2,17 +2,21 @@ use crate::di::*;
 use async_trait::async_trait;
 use log::{error, trace};
 
-use crate::api::{InputDeviceLedManager, INPUT_DEVICE_LED, NAMESPACE_INPUT_DEVICE};
+use crate::api::{InputDeviceLedManager, ANY_DEVICE_LABEL, INPUT_DEVICE_LED, NAMESPACE_INPUT_DEVICE};
+use crate::backend::InputBackendDevice;
 use crate::behaviour::entity::InputDeviceLedProperties;
+use crate::behaviour::entity::InputDeviceProperties;
 use crate::behaviour::relation::led_event::LED_EVENT;
 use crate::behaviour::relation::send_led_event::SEND_LED_EVENT;
 use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::{Device, LedType};
+use evdev::{AttributeSet, LedType};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -24,9 +28,20 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     return PluginContextContainer(RwLock::new(None));
 }
 
+/// The ids of the LEDs of each input device, including the LEDs of the pseudo device any-device.
+#[wrapper]
+pub struct InputDeviceLedStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_input_device_led_storage() -> InputDeviceLedStorage {
+    InputDeviceLedStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceLedManagerImpl {
     context: PluginContextContainer,
+
+    input_device_leds: InputDeviceLedStorage,
 }
 
 impl InputDeviceLedManagerImpl {}
@@ -38,12 +53,13 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
         self.context.0.write().unwrap().replace(context.clone());
     }
 
-    fn create_input_device_leds(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>) {
+    fn create_input_device_leds(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>) {
         let supported_leds = device.supported_leds();
         match supported_leds {
             Some(supported_leds) => {
+                let led_state = device.led_state().ok();
                 for led in supported_leds.iter() {
-                    self.create_input_device_led(device, entity_instance.clone(), led);
+                    self.create_input_device_led(entity_instance.clone(), led, led_state.as_ref());
                     self.create_any_device_led(entity_instance.clone(), led);
                 }
             }
@@ -51,15 +67,17 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
         }
     }
 
-    fn create_input_device_led(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, led: LedType) {
+    fn create_input_device_led(&self, input_device: Arc<ReactiveEntityInstance>, led: LedType, led_state: Option<&AttributeSet<LedType>>) {
         let reader = self.context.0.read().unwrap();
         let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
-        let device_name = device.name().unwrap_or("Unnamed Device");
-        let physical_path = device.physical_path().unwrap_or("");
+        // The ids and the labels are derived from the identity and the label of the input device
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
         let led_name = format!("{:?}", led);
-        let unique_name = format!("{}-{}-{}", device_name, physical_path, led_name);
+        let unique_name = format!("{}-{}", identity, led_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
-        let input_device_led = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), led_name.clone(), led);
+        let state = led_state.map(|led_state| led_state.contains(led)).unwrap_or(false);
+        let input_device_led = self.create_entity_instance(uuid, device_label, unique_name.clone(), led_name.clone(), led, state);
         let input_device_led = entity_instance_manager.create(input_device_led);
         self.try_create_led_event(input_device, input_device_led, unique_name, true);
     }
@@ -72,7 +90,7 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
         let unique_name = format!("{}-{}", device_name, led_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
         if !entity_instance_manager.has(uuid) {
-            let input_device_led = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), led_name.clone(), led);
+            let input_device_led = self.create_entity_instance(uuid, ANY_DEVICE_LABEL.into(), unique_name.clone(), led_name.clone(), led, false);
             let input_device_led = entity_instance_manager.create(input_device_led);
             self.try_create_led_event(input_device, input_device_led, unique_name, false);
         } else {
@@ -80,14 +98,15 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
         }
     }
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, led_name: String, led: LedType) -> EntityInstance {
+    fn create_entity_instance(&self, uuid: Uuid, device_label: String, unique_name: String, led_name: String, led: LedType, state: bool) -> EntityInstance {
         EntityInstanceBuilder::new(INPUT_DEVICE_LED)
             .id(uuid)
             .property(InputDeviceLedProperties::NAME, json!(unique_name))
-            .property(InputDeviceLedProperties::LABEL, unique_label(device_name, led_name.clone()))
+            .property(InputDeviceLedProperties::LABEL, unique_label(device_label, led_name.clone()))
             .property(InputDeviceLedProperties::LED, json!(led_name))
             .property(InputDeviceLedProperties::LED_TYPE, json!(led.0))
-            .property(InputDeviceLedProperties::STATE, json!(false))
+            .property(InputDeviceLedProperties::STATE, json!(state))
+            .property(InputDeviceLedProperties::LAST_CHANGED, InputDeviceLedProperties::LAST_CHANGED.default_value())
             .get()
     }
 
@@ -110,6 +129,13 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
     }
 
     fn create_led_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_led: Arc<ReactiveEntityInstance>, create_send_led: bool) {
+        self.input_device_leds
+            .0
+            .write()
+            .unwrap()
+            .entry(input_device.id)
+            .or_default()
+            .insert(input_device_led.id);
         let reader = self.context.0.read().unwrap();
         let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
         let led_event = RelationInstanceBuilder::new(input_device.id, LED_EVENT, input_device_led.id).get();
@@ -119,12 +145,45 @@ impl InputDeviceLedManager for InputDeviceLedManagerImpl {
             let _send_led_event = relation_instance_manager.create(send_led_event);
         }
     }
+
+    fn remove_input_device_leds(&self, input_device_id: Uuid) {
+        let input_device_leds = match self.input_device_leds.0.write().unwrap().remove(&input_device_id) {
+            Some(input_device_leds) => input_device_leds,
+            None => return,
+        };
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        for input_device_led_id in input_device_leds {
+            // The LEDs of any-device are kept as long as another input device provides them
+            let provided = self
+                .input_device_leds
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|input_device_leds| input_device_leds.contains(&input_device_led_id));
+            let relation_instances = relation_instance_manager
+                .get_by_inbound_entity(input_device_led_id)
+                .into_iter()
+                .chain(relation_instance_manager.get_by_outbound_entity(input_device_led_id));
+            for relation_instance in relation_instances {
+                // Only the relations from and to the removed input device (led_event, send_led_event)
+                if provided && relation_instance.outbound.id != input_device_id && relation_instance.inbound.id != input_device_id {
+                    continue;
+                }
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            if !provided {
+                entity_instance_manager.delete(input_device_led_id);
+                trace!("Removed {} {}", INPUT_DEVICE_LED, input_device_led_id);
+            }
+        }
+    }
 }
 
-fn unique_label(device_name: String, led_name: String) -> Value {
-    json!(format!(
-        "/org/inexor/input/{}/led/{}",
-        device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_"),
-        led_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")
-    ))
+fn unique_label(device_label: String, led_name: String) -> Value {
+    json!(format!("{}/led/{}", device_label, led_name.to_lowercase().replace("-", "_").replace(" ", "_")))
 }
@@ -3,17 +3,32 @@ use async_trait::async_trait;
 use log::{debug, error, trace};
 
 use crate::api::{
-    InputDeviceAbsoluteAxisManager, InputDeviceKeyManager, InputDeviceLedManager, InputDeviceManager, InputDeviceRelativeAxisManager, InputDeviceSwitchManager,
-    NAMESPACE_INPUT_DEVICE,
+    InputDeviceAbsoluteAxisManager, InputDeviceBackendManager, InputDeviceEventDispatcher, InputDeviceKeyManager, InputDeviceLedManager, InputDeviceManager,
+    InputDevicePhysicalDeviceManager, InputDeviceProblemManager, InputDeviceRelativeAxisManager, InputDeviceStatusManager, InputDeviceSwitchManager,
+    InputDeviceVirtualDeviceManager, NAMESPACE_INPUT_DEVICE,
 };
+use crate::backend::InputBackendDevice;
 use crate::behaviour::entity::input_device::INPUT_DEVICE;
 use crate::behaviour::entity::InputDeviceProperties;
+use crate::behaviour::error::InputDeviceError;
+use crate::behaviour::event_payload::{
+    INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
+    INPUT_EVENT_KIND_SWITCH_EVENT,
+};
 use crate::builder::EntityInstanceBuilder;
-use crate::config::{InputDeviceConfig, InputDevicesConfig};
+use crate::classification::{classify, matches_device_classes, DeviceClass};
+use crate::config::{EntityCreation, InputDeviceConfig, InputDeviceOptions, InputDevicesConfig};
+use crate::identity::identity_of;
+use crate::metadata::{bus_type_of, capabilities_of, indexed_label, input_properties_of, location_of};
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::Device;
-use serde_json::{json, Value};
-use std::sync::{Arc, RwLock};
+use evdev::{AbsoluteAxisType, Key, LedType, RelativeAxisType, SwitchType};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use uuid::Uuid;
 
 const CONFIG_PATH: &str = "./config/input_devices.toml";
@@ -26,18 +41,194 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     PluginContextContainer(RwLock::new(None))
 }
 
+#[wrapper]
+pub struct InputDevicesConfigContainer(RwLock<Option<InputDevicesConfig>>);
+
+#[provides]
+fn create_empty_input_devices_config_container() -> InputDevicesConfigContainer {
+    InputDevicesConfigContainer(RwLock::new(None))
+}
+
+/// The ids of the input devices by their device node.
+#[wrapper]
+pub struct DeviceNodeStorage(RwLock<HashMap<PathBuf, Uuid>>);
+
+#[provides]
+fn create_device_node_storage() -> DeviceNodeStorage {
+    DeviceNodeStorage(RwLock::new(HashMap::new()))
+}
+
+/// The ids of the input devices, including the disconnected input devices which are kept for reconnecting.
+#[wrapper]
+pub struct ManagedDeviceStorage(RwLock<HashSet<Uuid>>);
+
+#[provides]
+fn create_managed_device_storage() -> ManagedDeviceStorage {
+    ManagedDeviceStorage(RwLock::new(HashSet::new()))
+}
+
+/// An input device whose keys, LEDs, axes and switches are created on demand.
+pub struct LazyInputDevice {
+    input_device: Arc<ReactiveEntityInstance>,
+
+    key_manager: Arc<dyn InputDeviceKeyManager>,
+    led_manager: Arc<dyn InputDeviceLedManager>,
+    relative_axis_manager: Arc<dyn InputDeviceRelativeAxisManager>,
+    absolute_axis_manager: Arc<dyn InputDeviceAbsoluteAxisManager>,
+    switch_manager: Arc<dyn InputDeviceSwitchManager>,
+
+    options: InputDeviceOptions,
+
+    /// The kinds and codes which have been handled already, including those which are not created at all.
+    materialized: Mutex<HashSet<(String, i64)>>,
+}
+
+impl LazyInputDevice {
+    /// Creates the key, LED, axis or switch of the given kind and code, including its counterpart of
+    /// any-device. The managers read the name, the state and the ranges from the given input device.
+    fn materialize(&self, input_event_kind: &str, code: i64, device: &dyn InputBackendDevice) {
+        if !self.materialized.lock().unwrap().insert((input_event_kind.to_string(), code)) {
+            return;
+        }
+        let enabled = match input_event_kind {
+            INPUT_EVENT_KIND_KEY_EVENT => self.options.autodetect_keys,
+            INPUT_EVENT_KIND_LED_EVENT => self.options.autodetect_leds,
+            INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT => self.options.autodetect_relative_axes,
+            INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT => self.options.autodetect_absolute_axes,
+            INPUT_EVENT_KIND_SWITCH_EVENT => self.options.autodetect_switches,
+            _ => false,
+        };
+        if !enabled {
+            return;
+        }
+        let input_device = self.input_device.clone();
+        let code = code as u16;
+        trace!("Creating {} {} of {} {} on demand", input_event_kind, code, INPUT_DEVICE, input_device.id);
+        match input_event_kind {
+            INPUT_EVENT_KIND_KEY_EVENT => {
+                self.key_manager
+                    .create_input_device_key(input_device.clone(), Key::new(code), device.key_state().ok().as_ref());
+                self.key_manager.create_any_device_key(input_device, Key::new(code));
+            }
+            INPUT_EVENT_KIND_LED_EVENT => {
+                self.led_manager
+                    .create_input_device_led(input_device.clone(), LedType(code), device.led_state().ok().as_ref());
+                self.led_manager.create_any_device_led(input_device, LedType(code));
+            }
+            INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT => {
+                self.relative_axis_manager
+                    .create_input_device_relative_axis(device, input_device.clone(), RelativeAxisType(code));
+                self.relative_axis_manager.create_any_device_relative_axis(input_device, RelativeAxisType(code));
+            }
+            INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT => {
+                self.absolute_axis_manager
+                    .create_input_device_absolute_axis(device, input_device.clone(), AbsoluteAxisType(code));
+                self.absolute_axis_manager.create_any_device_absolute_axis(input_device, AbsoluteAxisType(code));
+            }
+            INPUT_EVENT_KIND_SWITCH_EVENT => {
+                self.switch_manager
+                    .create_input_device_switch(input_device.clone(), SwitchType(code), device.switch_state().ok().as_ref());
+                self.switch_manager.create_any_device_switch(input_device, SwitchType(code));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The labels of the input devices by their id.
+#[wrapper]
+pub struct LabelStorage(RwLock<HashMap<Uuid, String>>);
+
+#[provides]
+fn create_label_storage() -> LabelStorage {
+    LabelStorage(RwLock::new(HashMap::new()))
+}
+
+/// The input devices whose entities are created on demand by their id.
+#[wrapper]
+pub struct LazyInputDeviceStorage(RwLock<HashMap<Uuid, Arc<LazyInputDevice>>>);
+
+#[provides]
+fn create_lazy_input_device_storage() -> LazyInputDeviceStorage {
+    LazyInputDeviceStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceManagerImpl {
+    input_device_backend_manager: Wrc<dyn InputDeviceBackendManager>,
     input_device_key_manager: Wrc<dyn InputDeviceKeyManager>,
     input_device_led_manager: Wrc<dyn InputDeviceLedManager>,
     input_device_relative_axis_manager: Wrc<dyn InputDeviceRelativeAxisManager>,
     input_device_absolute_axis_manager: Wrc<dyn InputDeviceAbsoluteAxisManager>,
     input_device_switch_manager: Wrc<dyn InputDeviceSwitchManager>,
+    input_device_virtual_device_manager: Wrc<dyn InputDeviceVirtualDeviceManager>,
+    input_device_physical_device_manager: Wrc<dyn InputDevicePhysicalDeviceManager>,
+    input_device_problem_manager: Wrc<dyn InputDeviceProblemManager>,
+    input_device_status_manager: Wrc<dyn InputDeviceStatusManager>,
+    input_device_event_dispatcher: Wrc<dyn InputDeviceEventDispatcher>,
 
     context: PluginContextContainer,
+
+    config: InputDevicesConfigContainer,
+
+    device_nodes: DeviceNodeStorage,
+
+    managed_devices: ManagedDeviceStorage,
+
+    lazy_input_devices: LazyInputDeviceStorage,
+
+    labels: LabelStorage,
 }
 
-impl InputDeviceManagerImpl {}
+impl InputDeviceManagerImpl {
+    /// Assigns a label to the input device. Input devices with the same name are told apart by an
+    /// index suffix, for example /org/inexor/input/gamepad_2. A label is kept as long as the input
+    /// device exists and a new input device gets the lowest free index, so that flows which are
+    /// bound to a label never switch to another input device.
+    fn assign_label(&self, id: Uuid, device_name: &str) -> String {
+        let mut labels = self.labels.0.write().unwrap();
+        if let Some(label) = labels.get(&id) {
+            return label.clone();
+        }
+        let label = indexed_label(&labels, &unique_label(device_name));
+        labels.insert(id, label.clone());
+        label
+    }
+
+    /// Removes the input devices which are not configured anymore. Unplugged input devices which
+    /// are still configured are kept, so that they are reconnected when they are plugged in again.
+    fn remove_unconfigured_input_devices(&self, config: &InputDevicesConfig) {
+        let entity_instance_manager = match self.context.0.read().unwrap().as_ref() {
+            Some(context) => context.get_entity_instance_manager().clone(),
+            None => return,
+        };
+        let unconfigured: Vec<Uuid> = self
+            .managed_devices
+            .0
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|id| {
+                entity_instance_manager
+                    .get(**id)
+                    .map(|input_device| !is_configured(config, &input_device))
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect();
+        for id in unconfigured {
+            debug!("Removing {} {} which is not configured anymore", INPUT_DEVICE, id);
+            self.remove_input_device(id);
+        }
+    }
+
+    /// Maps the device node to the input device. A reconnected input device may get a different device node.
+    fn map_device_node(&self, device_node: &Path, id: Uuid) {
+        let mut device_nodes = self.device_nodes.0.write().unwrap();
+        device_nodes.retain(|_, device_id| *device_id != id);
+        device_nodes.insert(device_node.to_path_buf(), id);
+    }
+}
 
 #[async_trait]
 #[provides]
@@ -53,6 +244,10 @@ impl InputDeviceManager for InputDeviceManagerImpl {
         self.input_device_relative_axis_manager.set_context(context.clone());
         self.input_device_absolute_axis_manager.set_context(context.clone());
         self.input_device_switch_manager.set_context(context.clone());
+        self.input_device_virtual_device_manager.set_context(context.clone());
+        self.input_device_physical_device_manager.set_context(context.clone());
+        self.input_device_problem_manager.set_context(context.clone());
+        self.input_device_status_manager.set_context(context.clone());
     }
 
     fn load_config(&self) {
@@ -62,11 +257,7 @@ impl InputDeviceManager for InputDeviceManagerImpl {
                 let input_devices_config: Result<InputDevicesConfig, _> = toml::from_str(&toml_string);
                 match input_devices_config {
                     Ok(input_devices_config) => {
-                        if input_devices_config.autodetect {
-                            self.autodetect_input_devices();
-                        } else {
-                            self.load_input_devices(input_devices_config.input_device);
-                        }
+                        self.apply_config(input_devices_config);
                     }
                     Err(_) => {
                         error!("Failed to load input devices configuration from {}: Invalid TOML:", CONFIG_PATH);
@@ -79,96 +270,355 @@ impl InputDeviceManager for InputDeviceManagerImpl {
         }
     }
 
+    fn apply_config(&self, config: InputDevicesConfig) {
+        self.config.0.write().unwrap().replace(config.clone());
+        self.input_device_backend_manager.configure(&config);
+        if config.autodetect {
+            self.autodetect_input_devices();
+        } else {
+            self.load_input_devices(config.input_device.clone());
+        }
+        self.remove_unconfigured_input_devices(&config);
+    }
+
+    fn get_config(&self) -> Option<InputDevicesConfig> {
+        self.config.0.read().unwrap().clone()
+    }
+
     fn autodetect_input_devices(&self) {
-        let devices = evdev::enumerate().collect::<Vec<_>>();
-        for device in devices.iter() {
+        let backend = self.input_device_backend_manager.get_backend();
+        let config = match self.get_config() {
+            Some(config) => config,
+            None => return,
+        };
+        let mut devices = Vec::new();
+        for device_node in backend.device_nodes() {
+            // Device nodes which can't be opened are reported instead of being skipped silently
+            let device = match backend.open(&device_node) {
+                Ok(device) => device,
+                Err(e) => {
+                    self.input_device_problem_manager
+                        .report_problem(&device_node, &InputDeviceError::from_io(&device_node, e));
+                    continue;
+                }
+            };
+            self.input_device_problem_manager.resolve_problem(&device_node);
+            if self.input_device_virtual_device_manager.is_virtual_device(device.as_ref()) {
+                continue;
+            }
+            if !matches_device_classes(&config.autodetect_device_classes, &classify(device.as_ref())) {
+                trace!(
+                    "Skipping input device {} which doesn't belong to the device classes",
+                    device.name().unwrap_or("Unnamed Device")
+                );
+                continue;
+            }
             debug!("Automatically detected input device: {}", device.name().unwrap_or("Unnamed Device"));
-            self.create_input_device(device, true, true, true, true, true);
+            devices.push(device);
+        }
+        // Input devices with the same name get their labels in the order of their identities
+        // instead of the order of their device nodes
+        devices.sort_by_cached_key(|device| identity_of(device.as_ref()).identity);
+        for device in devices {
+            self.create_input_device(device.as_ref(), &config.autodetect_options);
         }
     }
 
     fn load_input_devices(&self, input_devices: Vec<InputDeviceConfig>) {
+        let backend = self.input_device_backend_manager.get_backend();
         for input_device in input_devices.iter() {
             if input_device.active {
-                let device = Device::open(input_device.path.clone());
-                match device {
+                let device_node = device_node_of(Path::new(&input_device.path));
+                match backend.open(&device_node) {
                     Ok(device) => {
+                        self.input_device_problem_manager.resolve_problem(&device_node);
+                        if !matches_device_classes(&input_device.device_class, &classify(device.as_ref())) {
+                            debug!("Skipping input device {} which doesn't belong to the device classes", input_device.path);
+                            continue;
+                        }
                         debug!("Loading input device {} from {}", device.name().unwrap_or("Unnamed Device"), input_device.path.clone());
-                        self.create_input_device(
-                            &device,
-                            input_device.autodetect_keys,
-                            input_device.autodetect_leds,
-                            input_device.autodetect_relative_axes,
-                            input_device.autodetect_absolute_axes,
-                            input_device.autodetect_switches,
-                        );
+                        self.create_input_device(device.as_ref(), &input_device.options);
                     }
-                    Err(_) => {
+                    Err(e) => {
                         error!("Failed to load input device {}", input_device.path);
+                        self.input_device_problem_manager
+                            .report_problem(&device_node, &InputDeviceError::from_io(&device_node, e));
                     }
                 }
             }
         }
     }
-    fn create_input_device(
-        &self,
-        device: &Device,
-        autodetect_keys: bool,
-        autodetect_leds: bool,
-        autodetect_relative_axes: bool,
-        autodetect_absolute_axes: bool,
-        autodetect_switches: bool,
-    ) {
+
+    fn create_input_device(&self, device: &dyn InputBackendDevice, options: &InputDeviceOptions) {
         let device_name = device.name().unwrap_or("Unnamed Device");
         let physical_path = device.physical_path().unwrap_or("");
-        let unique_name = format!("{}-{}", device_name, physical_path);
+        let identity = identity_of(device);
+        let bus_type = bus_type_of(device);
+        let unique_id = device.unique_id().unwrap_or("");
+        let location = location_of(device);
+        let input_properties = input_properties_of(device);
+        let capabilities = capabilities_of(device);
+        let device_classes: Vec<&str> = classify(device).iter().map(DeviceClass::as_ref).collect();
         let driver_version = format!("{}.{}.{}", device.driver_version().0, device.driver_version().1, device.driver_version().2);
         let vendor = device.input_id().vendor();
         let product = device.input_id().product();
         let version = device.input_id().version();
-        let reader = self.context.0.read().unwrap();
-        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let id = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, identity.identity.as_bytes());
+        let entity_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_entity_instance_manager().clone();
+        if entity_instance_manager.has(id) {
+            // The input device has been kept after it was unplugged and its behaviour reconnects it
+            debug!("Reconnecting {} {} from {}", INPUT_DEVICE, device_name, device.device_node().display());
+            self.map_device_node(device.device_node(), id);
+            if let Some(input_device) = entity_instance_manager.get(id) {
+                // The device node and the symlinks may have changed
+                input_device.set(InputDeviceProperties::DEVICE_NODE.to_string(), json!(location.device_node));
+                input_device.set(InputDeviceProperties::BY_ID.to_string(), json!(location.by_id));
+                input_device.set(InputDeviceProperties::BY_PATH.to_string(), json!(location.by_path));
+                input_device.set(InputDeviceProperties::SYSFS_PATH.to_string(), json!(location.sysfs_path));
+            }
+            return;
+        }
         let entity_instance = EntityInstanceBuilder::new(INPUT_DEVICE)
-            .id(Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes()))
+            .id(id)
             .property(InputDeviceProperties::NAME, json!(device_name))
-            .property(InputDeviceProperties::LABEL, unique_label(device_name.into()))
+            .property(InputDeviceProperties::LABEL, json!(self.assign_label(id, device_name)))
             .property(InputDeviceProperties::PHYSICAL_PATH, json!(physical_path))
+            .property(InputDeviceProperties::IDENTITY, json!(identity.identity))
+            .property(InputDeviceProperties::IDENTITY_STRATEGY, json!(identity.strategy.as_ref()))
+            .property(InputDeviceProperties::BUS_TYPE, json!(bus_type))
+            .property(InputDeviceProperties::UNIQUE_ID, json!(unique_id))
+            .property(InputDeviceProperties::DEVICE_NODE, json!(location.device_node))
+            .property(InputDeviceProperties::BY_ID, json!(location.by_id))
+            .property(InputDeviceProperties::BY_PATH, json!(location.by_path))
+            .property(InputDeviceProperties::SYSFS_PATH, json!(location.sysfs_path))
+            .property(InputDeviceProperties::INPUT_PROPERTIES, json!(input_properties))
+            .property(InputDeviceProperties::DEVICE_CLASS, json!(device_classes))
+            .property(InputDeviceProperties::SUPPORTED_EVENTS, json!(capabilities.events))
+            .property(InputDeviceProperties::SUPPORTED_KEYS, json!(capabilities.keys))
+            .property(InputDeviceProperties::SUPPORTED_LEDS, json!(capabilities.leds))
+            .property(InputDeviceProperties::SUPPORTED_RELATIVE_AXES, json!(capabilities.relative_axes))
+            .property(InputDeviceProperties::SUPPORTED_ABSOLUTE_AXES, json!(capabilities.absolute_axes))
+            .property(InputDeviceProperties::SUPPORTED_SWITCHES, json!(capabilities.switches))
+            .property(InputDeviceProperties::SUPPORTED_FORCE_FEEDBACK_EFFECTS, json!(capabilities.force_feedback_effects))
+            .property(InputDeviceProperties::SUPPORTED_SOUNDS, json!(capabilities.sounds))
             .property(InputDeviceProperties::DRIVER_VERSION, json!(driver_version))
             .property(InputDeviceProperties::VENDOR, json!(vendor))
             .property(InputDeviceProperties::PRODUCT, json!(product))
             .property(InputDeviceProperties::VERSION, json!(version))
             .property(InputDeviceProperties::EVENT, json!({}))
+            .property(InputDeviceProperties::FRAME, json!([]))
+            .property(InputDeviceProperties::FRAME_MODE, json!(options.frame_mode))
+            .property(InputDeviceProperties::GRAB, json!(options.grab))
             .get();
         let reactive_entity_instance = entity_instance_manager.create(entity_instance);
         match reactive_entity_instance {
             Ok(reactive_entity_instance) => {
                 trace!("Registered {} {} as {}", INPUT_DEVICE, device_name, reactive_entity_instance.id);
-                if autodetect_keys {
+                self.map_device_node(device.device_node(), reactive_entity_instance.id);
+                let managed_devices = {
+                    let mut managed_devices = self.managed_devices.0.write().unwrap();
+                    managed_devices.insert(reactive_entity_instance.id);
+                    managed_devices.len()
+                };
+                self.input_device_status_manager.set_managed_devices(managed_devices);
+                self.input_device_physical_device_manager
+                    .add_interface(device, reactive_entity_instance.clone());
+                let entity_creation = self.get_config().map(|config| config.entity_creation).unwrap_or_default();
+                if entity_creation == EntityCreation::Lazy {
+                    // Only the input device is created up front
+                    let lazy_input_device = Arc::new(LazyInputDevice {
+                        input_device: reactive_entity_instance.clone(),
+                        key_manager: self.input_device_key_manager.clone(),
+                        led_manager: self.input_device_led_manager.clone(),
+                        relative_axis_manager: self.input_device_relative_axis_manager.clone(),
+                        absolute_axis_manager: self.input_device_absolute_axis_manager.clone(),
+                        switch_manager: self.input_device_switch_manager.clone(),
+                        options: options.clone(),
+                        materialized: Mutex::new(HashSet::new()),
+                    });
+                    self.lazy_input_devices
+                        .0
+                        .write()
+                        .unwrap()
+                        .insert(reactive_entity_instance.id, lazy_input_device.clone());
+                    // The first event or query of a key, LED, axis or switch creates its entity and relations
+                    self.input_device_event_dispatcher.register_fallback(
+                        reactive_entity_instance.id,
+                        Arc::new(move |input_event_kind: &str, code: i64, device: &dyn InputBackendDevice| {
+                            lazy_input_device.materialize(input_event_kind, code, device);
+                        }),
+                    );
+                }
+                if entity_creation == EntityCreation::Eager && options.autodetect_keys {
                     self.input_device_key_manager.create_input_device_keys(device, reactive_entity_instance.clone());
                 }
-                if autodetect_leds {
+                if entity_creation == EntityCreation::Eager && options.autodetect_leds {
                     self.input_device_led_manager.create_input_device_leds(device, reactive_entity_instance.clone());
                 }
-                if autodetect_relative_axes {
+                if entity_creation == EntityCreation::Eager && options.autodetect_relative_axes {
                     self.input_device_relative_axis_manager
                         .create_input_device_relative_axes(device, reactive_entity_instance.clone());
                 }
-                if autodetect_absolute_axes {
+                if entity_creation == EntityCreation::Eager && options.autodetect_absolute_axes {
                     self.input_device_absolute_axis_manager
                         .create_input_device_absolute_axes(device, reactive_entity_instance.clone());
                 }
-                if autodetect_switches {
+                if entity_creation == EntityCreation::Eager && options.autodetect_switches {
                     self.input_device_switch_manager
                         .create_input_device_switches(device, reactive_entity_instance.clone());
                 }
+                if options.virtual_device {
+                    self.input_device_virtual_device_manager
+                        .create_virtual_device(device, reactive_entity_instance.clone());
+                }
             }
             Err(_) => {
+                self.labels.0.write().unwrap().remove(&id);
                 error!("Failed to create entity instance for {} {}!", INPUT_DEVICE, device_name);
             }
         }
     }
+
+    fn add_device_node(&self, device_node: &Path) {
+        if self.device_nodes.0.read().unwrap().contains_key(device_node) {
+            return;
+        }
+        let config = self.get_config();
+        if config.is_none() {
+            return;
+        }
+        let config = config.unwrap();
+        let input_device = config
+            .input_device
+            .iter()
+            .find(|input_device| input_device.active && device_node_of(Path::new(&input_device.path)) == device_node);
+        // Device nodes which are not configured are ignored unless the input devices are detected automatically
+        if !config.autodetect && input_device.is_none() {
+            return;
+        }
+        // The device node may not be readable until udev has applied the permissions
+        let device = match self.input_device_backend_manager.get_backend().open(device_node) {
+            Ok(device) => device,
+            Err(e) => {
+                // The problem is resolved as soon as the device node has been added successfully
+                trace!("Device node {} is not readable (yet)", device_node.display());
+                self.input_device_problem_manager
+                    .report_problem(device_node, &InputDeviceError::from_io(device_node, e));
+                return;
+            }
+        };
+        self.input_device_problem_manager.resolve_problem(device_node);
+        // The virtual devices of the plugin itself are never managed as input devices
+        if self.input_device_virtual_device_manager.is_virtual_device(device.as_ref()) {
+            return;
+        }
+        let device_classes = classify(device.as_ref());
+        if config.autodetect {
+            if !matches_device_classes(&config.autodetect_device_classes, &device_classes) {
+                return;
+            }
+            debug!("Hotplugged input device: {}", device.name().unwrap_or("Unnamed Device"));
+            self.create_input_device(device.as_ref(), &config.autodetect_options);
+            return;
+        }
+        if let Some(input_device) = input_device {
+            if !matches_device_classes(&input_device.device_class, &device_classes) {
+                return;
+            }
+            debug!("Hotplugged input device {} from {}", device.name().unwrap_or("Unnamed Device"), input_device.path);
+            self.create_input_device(device.as_ref(), &input_device.options);
+        }
+    }
+
+    fn remove_device_node(&self, device_node: &Path) {
+        self.input_device_problem_manager.resolve_problem(device_node);
+        let id = self.device_nodes.0.write().unwrap().remove(device_node);
+        if let Some(id) = id {
+            debug!("Unplugged input device {} from {}", id, device_node.display());
+            let reconnect = self.get_config().map(|config| config.reconnect).unwrap_or(false);
+            if reconnect {
+                // The input device is kept (connected = false) until it is plugged in again
+                return;
+            }
+            self.remove_input_device(id);
+        }
+    }
+
+    fn remove_input_device(&self, id: Uuid) {
+        self.input_device_event_dispatcher.unregister_fallback(id);
+        self.lazy_input_devices.0.write().unwrap().remove(&id);
+        self.input_device_key_manager.remove_input_device_keys(id);
+        self.input_device_led_manager.remove_input_device_leds(id);
+        self.input_device_relative_axis_manager.remove_input_device_relative_axes(id);
+        self.input_device_absolute_axis_manager.remove_input_device_absolute_axes(id);
+        self.input_device_switch_manager.remove_input_device_switches(id);
+        self.input_device_physical_device_manager.remove_interface(id);
+        let entity_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_relation_instance_manager().clone();
+        // The remaining relations, for example from flows
+        let relation_instances = relation_instance_manager
+            .get_by_outbound_entity(id)
+            .into_iter()
+            .chain(relation_instance_manager.get_by_inbound_entity(id));
+        for relation_instance in relation_instances {
+            if let Some(edge_key) = relation_instance.get_key() {
+                relation_instance_manager.delete(edge_key);
+            }
+        }
+        entity_instance_manager.delete(id);
+        // The behaviour of the input device releases the keys held down by the virtual device
+        self.input_device_virtual_device_manager.remove_virtual_device(id);
+        self.labels.0.write().unwrap().remove(&id);
+        self.device_nodes.0.write().unwrap().retain(|_, device_id| *device_id != id);
+        let managed_devices = {
+            let mut managed_devices = self.managed_devices.0.write().unwrap();
+            managed_devices.remove(&id);
+            managed_devices.len()
+        };
+        self.input_device_status_manager.set_managed_devices(managed_devices);
+        trace!("Removed {} {}", INPUT_DEVICE, id);
+    }
+
+    fn remove_input_devices(&self) {
+        let ids: Vec<Uuid> = self.managed_devices.0.read().unwrap().iter().cloned().collect();
+        for id in ids {
+            self.remove_input_device(id);
+        }
+        self.input_device_problem_manager.resolve_problems();
+    }
+}
+
+/// Resolves symlinks like /dev/input/by-id/... to the device node.
+fn device_node_of(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Returns true, if the input device is still configured. If the input devices are detected
+/// automatically, the input device must belong to the device classes. Otherwise an active input
+/// device must be configured at its device node or at one of its symlinks.
+fn is_configured(config: &InputDevicesConfig, input_device: &ReactiveEntityInstance) -> bool {
+    let device_classes: Vec<DeviceClass> = input_device
+        .get(InputDeviceProperties::DEVICE_CLASS)
+        .and_then(|device_classes| serde_json::from_value(device_classes).ok())
+        .unwrap_or_default();
+    if config.autodetect {
+        return matches_device_classes(&config.autodetect_device_classes, &device_classes);
+    }
+    // The symlinks of an unplugged input device don't exist anymore and can't be resolved
+    let locations: Vec<PathBuf> = [InputDeviceProperties::DEVICE_NODE, InputDeviceProperties::BY_ID, InputDeviceProperties::BY_PATH]
+        .into_iter()
+        .filter_map(|property| input_device.as_string(property))
+        .filter(|location| !location.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    config.input_device.iter().any(|input_device_config| {
+        let path = Path::new(&input_device_config.path);
+        input_device_config.active
+            && matches_device_classes(&input_device_config.device_class, &device_classes)
+            && locations.iter().any(|location| location == path || *location == device_node_of(path))
+    })
 }
 
-fn unique_label(device_name: String) -> Value {
-    json!(format!("/org/inexor/input/{}", device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")))
+fn unique_label(device_name: &str) -> String {
+    format!("/org/inexor/input/{}", device_name.to_lowercase().replace("-", "_").replace(" ", "_"))
 }
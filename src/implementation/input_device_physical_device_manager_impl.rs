@@ -0,0 +1,270 @@
+use crate::di::*;
+use async_trait::async_trait;
+use log::{error, trace};
+
+use crate::api::{InputDevicePhysicalDeviceManager, HAS_INTERFACE, NAMESPACE_INPUT_DEVICE, PHYSICAL_DEVICE};
+use crate::backend::InputBackendDevice;
+use crate::behaviour::entity::{InputDeviceProperties, PhysicalDeviceProperties};
+use crate::behaviour::event_payload::{
+    INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
+    INPUT_EVENT_KIND_SWITCH_EVENT,
+};
+use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::identity::{physical_device_identity_of, PhysicalDeviceIdentity};
+use crate::metadata::indexed_label;
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+use crate::plugins::PluginContext;
+use evdev::{AbsoluteAxisType, Key, LedType, RelativeAxisType, SwitchType};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+#[wrapper]
+pub struct PluginContextContainer(RwLock<Option<std::sync::Arc<dyn PluginContext>>>);
+
+#[provides]
+fn create_empty_plugin_context_container() -> PluginContextContainer {
+    PluginContextContainer(RwLock::new(None))
+}
+
+/// The ids of the input devices (interfaces) by the id of their physical device.
+#[wrapper]
+pub struct InterfaceStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_interface_storage() -> InterfaceStorage {
+    InterfaceStorage(RwLock::new(HashMap::new()))
+}
+
+/// The labels of the physical devices by their id.
+#[wrapper]
+pub struct PhysicalDeviceLabelStorage(RwLock<HashMap<Uuid, String>>);
+
+#[provides]
+fn create_physical_device_label_storage() -> PhysicalDeviceLabelStorage {
+    PhysicalDeviceLabelStorage(RwLock::new(HashMap::new()))
+}
+
+#[component]
+pub struct InputDevicePhysicalDeviceManagerImpl {
+    context: PluginContextContainer,
+
+    interfaces: InterfaceStorage,
+
+    labels: PhysicalDeviceLabelStorage,
+}
+
+impl InputDevicePhysicalDeviceManagerImpl {
+    /// Assigns a label to the physical device. Physical devices with the same name are told apart
+    /// by an index suffix. A label is kept as long as the physical device exists and a new physical
+    /// device gets the lowest free index.
+    fn assign_label(&self, id: Uuid, device_name: &str) -> String {
+        let mut labels = self.labels.0.write().unwrap();
+        if let Some(label) = labels.get(&id) {
+            return label.clone();
+        }
+        let label = indexed_label(&labels, &unique_label(device_name));
+        labels.insert(id, label.clone());
+        label
+    }
+
+    fn create_physical_device(
+        &self,
+        id: Uuid,
+        device: &dyn InputBackendDevice,
+        physical_device_identity: PhysicalDeviceIdentity,
+    ) -> Option<Arc<ReactiveEntityInstance>> {
+        let entity_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_entity_instance_manager().clone();
+        if let Some(physical_device) = entity_instance_manager.get(id) {
+            return Some(physical_device);
+        }
+        let device_name = device.name().unwrap_or("Unnamed Device");
+        let entity_instance = EntityInstanceBuilder::new(PHYSICAL_DEVICE)
+            .id(id)
+            .property(PhysicalDeviceProperties::NAME, json!(device_name))
+            .property(PhysicalDeviceProperties::LABEL, json!(self.assign_label(id, device_name)))
+            .property(PhysicalDeviceProperties::IDENTITY, json!(physical_device_identity.identity))
+            .property(PhysicalDeviceProperties::GROUPED_BY, json!(physical_device_identity.strategy.as_ref()))
+            .property(PhysicalDeviceProperties::VENDOR, json!(device.input_id().vendor()))
+            .property(PhysicalDeviceProperties::PRODUCT, json!(device.input_id().product()))
+            .property(PhysicalDeviceProperties::INTERFACES, json!(0))
+            .property(PhysicalDeviceProperties::SUPPORTED_KEYS, json!([]))
+            .property(PhysicalDeviceProperties::SUPPORTED_LEDS, json!([]))
+            .property(PhysicalDeviceProperties::SUPPORTED_RELATIVE_AXES, json!([]))
+            .property(PhysicalDeviceProperties::SUPPORTED_ABSOLUTE_AXES, json!([]))
+            .property(PhysicalDeviceProperties::SUPPORTED_SWITCHES, json!([]))
+            .property(PhysicalDeviceProperties::QUERY, json!({}))
+            .get();
+        match entity_instance_manager.create(entity_instance) {
+            Ok(physical_device) => {
+                trace!("Registered {} {} as {}", PHYSICAL_DEVICE, device_name, physical_device.id);
+                Some(physical_device)
+            }
+            Err(_) => {
+                self.labels.0.write().unwrap().remove(&id);
+                error!("Failed to create entity instance for {} {}!", PHYSICAL_DEVICE, device_name);
+                None
+            }
+        }
+    }
+
+    /// Returns the entity instances of the interfaces of the physical device with the given id.
+    fn get_interfaces(&self, id: Uuid) -> Vec<Arc<ReactiveEntityInstance>> {
+        let interfaces: Vec<Uuid> = self
+            .interfaces
+            .0
+            .read()
+            .unwrap()
+            .get(&id)
+            .map(|interfaces| interfaces.iter().cloned().collect())
+            .unwrap_or_default();
+        let entity_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_entity_instance_manager().clone();
+        interfaces.into_iter().filter_map(|interface| entity_instance_manager.get(interface)).collect()
+    }
+
+    /// Aggregates the keys, LEDs, axes and switches supported by the interfaces of the physical
+    /// device.
+    fn update_capabilities(&self, physical_device: &ReactiveEntityInstance) {
+        let interfaces = self.get_interfaces(physical_device.id);
+        for (property, interface_property) in SUPPORTED_CAPABILITIES {
+            let capabilities: BTreeSet<String> = interfaces
+                .iter()
+                .filter_map(|interface| interface.get(interface_property.as_ref()))
+                .filter_map(|capabilities| serde_json::from_value::<Vec<String>>(capabilities).ok())
+                .flatten()
+                .collect();
+            physical_device.set(property.to_string(), json!(capabilities));
+        }
+    }
+}
+
+/// The aggregated properties of the physical device and the properties of its interfaces.
+const SUPPORTED_CAPABILITIES: [(PhysicalDeviceProperties, InputDeviceProperties); 5] = [
+    (PhysicalDeviceProperties::SUPPORTED_KEYS, InputDeviceProperties::SUPPORTED_KEYS),
+    (PhysicalDeviceProperties::SUPPORTED_LEDS, InputDeviceProperties::SUPPORTED_LEDS),
+    (PhysicalDeviceProperties::SUPPORTED_RELATIVE_AXES, InputDeviceProperties::SUPPORTED_RELATIVE_AXES),
+    (PhysicalDeviceProperties::SUPPORTED_ABSOLUTE_AXES, InputDeviceProperties::SUPPORTED_ABSOLUTE_AXES),
+    (PhysicalDeviceProperties::SUPPORTED_SWITCHES, InputDeviceProperties::SUPPORTED_SWITCHES),
+];
+
+#[async_trait]
+#[provides]
+impl InputDevicePhysicalDeviceManager for InputDevicePhysicalDeviceManagerImpl {
+    fn set_context(&self, context: Arc<dyn PluginContext>) {
+        self.context.0.write().unwrap().replace(context.clone());
+    }
+
+    fn add_interface(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>) {
+        let physical_device_identity = physical_device_identity_of(device);
+        let unique_name = format!("{}-{}", PHYSICAL_DEVICE, physical_device_identity.identity);
+        let id = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
+        let physical_device = match self.create_physical_device(id, device, physical_device_identity) {
+            Some(physical_device) => physical_device,
+            None => return,
+        };
+        let interfaces = {
+            let mut interfaces = self.interfaces.0.write().unwrap();
+            let interfaces = interfaces.entry(id).or_default();
+            interfaces.insert(input_device.id);
+            interfaces.len()
+        };
+        physical_device.set(PhysicalDeviceProperties::INTERFACES.to_string(), json!(interfaces));
+        self.update_capabilities(&physical_device);
+        let reader = self.context.0.read().unwrap();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        let has_interface = RelationInstanceBuilder::new(id, HAS_INTERFACE, input_device.id).get();
+        let _has_interface = relation_instance_manager.create(has_interface);
+    }
+
+    fn remove_interface(&self, input_device_id: Uuid) {
+        let id = match self.get_physical_device(input_device_id) {
+            Some(id) => id,
+            None => return,
+        };
+        let interfaces = {
+            let mut interfaces = self.interfaces.0.write().unwrap();
+            let remaining = interfaces
+                .get_mut(&id)
+                .map(|interfaces| {
+                    interfaces.remove(&input_device_id);
+                    interfaces.len()
+                })
+                .unwrap_or(0);
+            if remaining == 0 {
+                interfaces.remove(&id);
+            }
+            remaining
+        };
+        let entity_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = self.context.0.read().unwrap().as_ref().unwrap().get_relation_instance_manager().clone();
+        for relation_instance in relation_instance_manager.get_by_inbound_entity(input_device_id) {
+            if relation_instance.outbound.id != id {
+                continue;
+            }
+            if let Some(edge_key) = relation_instance.get_key() {
+                relation_instance_manager.delete(edge_key);
+            }
+        }
+        if interfaces > 0 {
+            if let Some(physical_device) = entity_instance_manager.get(id) {
+                physical_device.set(PhysicalDeviceProperties::INTERFACES.to_string(), json!(interfaces));
+                self.update_capabilities(&physical_device);
+            }
+            return;
+        }
+        entity_instance_manager.delete(id);
+        self.labels.0.write().unwrap().remove(&id);
+        trace!("Removed {} {}", PHYSICAL_DEVICE, id);
+    }
+
+    fn get_physical_device(&self, input_device_id: Uuid) -> Option<Uuid> {
+        self.interfaces
+            .0
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(_, interfaces)| interfaces.contains(&input_device_id))
+            .map(|(id, _)| *id)
+    }
+
+    fn query(&self, id: Uuid, input_event_kind: &str, code: i64, query: &Value) -> bool {
+        let (interface_property, name) = match capability_of(input_event_kind, code) {
+            Some(capability) => capability,
+            None => return false,
+        };
+        let mut supported = false;
+        for interface in self.get_interfaces(id) {
+            let capabilities = interface
+                .get(interface_property.as_ref())
+                .and_then(|capabilities| serde_json::from_value::<Vec<String>>(capabilities).ok())
+                .unwrap_or_default();
+            if capabilities.contains(&name) {
+                // The interface creates the key, LED, axis or switch on demand
+                interface.set(InputDeviceProperties::QUERY.to_string(), query.clone());
+                supported = true;
+            }
+        }
+        supported
+    }
+}
+
+/// Returns the property of an input device which lists the supported keys, LEDs, axes or
+/// switches of the given kind and the name of the given code in this list.
+fn capability_of(input_event_kind: &str, code: i64) -> Option<(InputDeviceProperties, String)> {
+    let code = u16::try_from(code).ok()?;
+    match input_event_kind {
+        INPUT_EVENT_KIND_KEY_EVENT => Some((InputDeviceProperties::SUPPORTED_KEYS, format!("{:?}", Key::new(code)))),
+        INPUT_EVENT_KIND_LED_EVENT => Some((InputDeviceProperties::SUPPORTED_LEDS, format!("{:?}", LedType(code)))),
+        INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT => Some((InputDeviceProperties::SUPPORTED_RELATIVE_AXES, format!("{:?}", RelativeAxisType(code)))),
+        INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT => Some((InputDeviceProperties::SUPPORTED_ABSOLUTE_AXES, format!("{:?}", AbsoluteAxisType(code)))),
+        INPUT_EVENT_KIND_SWITCH_EVENT => Some((InputDeviceProperties::SUPPORTED_SWITCHES, format!("{:?}", SwitchType(code)))),
+        _ => None,
+    }
+}
+
+fn unique_label(device_name: &str) -> String {
+    format!("/org/inexor/input/physical/{}", device_name.to_lowercase().replace("-", "_").replace(" ", "_"))
+}
@@ -0,0 +1,108 @@
+use crate::di::*;
+use async_trait::async_trait;
+use log::{error, trace};
+
+use crate::api::{InputDeviceProblemManager, INPUT_DEVICE_PROBLEM, NAMESPACE_INPUT_DEVICE};
+use crate::behaviour::entity::InputDeviceProblemProperties;
+use crate::behaviour::error::InputDeviceError;
+use crate::builder::EntityInstanceBuilder;
+use crate::model::PropertyInstanceSetter;
+use crate::plugins::PluginContext;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+#[wrapper]
+pub struct PluginContextContainer(RwLock<Option<std::sync::Arc<dyn PluginContext>>>);
+
+#[provides]
+fn create_empty_plugin_context_container() -> PluginContextContainer {
+    PluginContextContainer(RwLock::new(None))
+}
+
+/// The ids of the problem entity instances by their device node.
+#[wrapper]
+pub struct ProblemStorage(RwLock<HashMap<PathBuf, Uuid>>);
+
+#[provides]
+fn create_problem_storage() -> ProblemStorage {
+    ProblemStorage(RwLock::new(HashMap::new()))
+}
+
+#[component]
+pub struct InputDeviceProblemManagerImpl {
+    context: PluginContextContainer,
+
+    problems: ProblemStorage,
+}
+
+impl InputDeviceProblemManagerImpl {}
+
+#[async_trait]
+#[provides]
+impl InputDeviceProblemManager for InputDeviceProblemManagerImpl {
+    fn set_context(&self, context: Arc<dyn PluginContext>) {
+        self.context.0.write().unwrap().replace(context.clone());
+    }
+
+    fn report_problem(&self, device_node: &Path, error: &InputDeviceError) {
+        error!("{}", error);
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let existing = self.problems.0.read().unwrap().get(device_node).cloned();
+        if let Some(entity_instance) = existing.and_then(|id| entity_instance_manager.get(id)) {
+            // The problem of the device node may have changed, for example from permission denied to busy
+            entity_instance.set(InputDeviceProblemProperties::PROBLEM.to_string(), json!(error.kind()));
+            entity_instance.set(InputDeviceProblemProperties::MESSAGE.to_string(), json!(error.to_string()));
+            entity_instance.set(InputDeviceProblemProperties::HINT.to_string(), json!(error.hint()));
+            return;
+        }
+        let device_node_name = device_node.display().to_string();
+        let unique_name = format!("{}-{}", INPUT_DEVICE_PROBLEM, device_node_name);
+        let entity_instance = EntityInstanceBuilder::new(INPUT_DEVICE_PROBLEM)
+            .id(Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes()))
+            .property(InputDeviceProblemProperties::NAME, json!(device_node_name))
+            .property(InputDeviceProblemProperties::LABEL, unique_label(device_node))
+            .property(InputDeviceProblemProperties::DEVICE_NODE, json!(device_node_name))
+            .property(InputDeviceProblemProperties::PROBLEM, json!(error.kind()))
+            .property(InputDeviceProblemProperties::MESSAGE, json!(error.to_string()))
+            .property(InputDeviceProblemProperties::HINT, json!(error.hint()))
+            .get();
+        match entity_instance_manager.create(entity_instance) {
+            Ok(entity_instance) => {
+                trace!("Registered {} {} as {}", INPUT_DEVICE_PROBLEM, device_node_name, entity_instance.id);
+                self.problems.0.write().unwrap().insert(device_node.to_path_buf(), entity_instance.id);
+            }
+            Err(_) => {
+                error!("Failed to create entity instance for {} {}!", INPUT_DEVICE_PROBLEM, device_node_name);
+            }
+        }
+    }
+
+    fn resolve_problem(&self, device_node: &Path) {
+        let id = self.problems.0.write().unwrap().remove(device_node);
+        if let Some(id) = id {
+            let reader = self.context.0.read().unwrap();
+            let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+            entity_instance_manager.delete(id);
+            trace!("Resolved {} {}", INPUT_DEVICE_PROBLEM, device_node.display());
+        }
+    }
+
+    fn resolve_problems(&self) {
+        let device_nodes: Vec<PathBuf> = self.problems.0.read().unwrap().keys().cloned().collect();
+        for device_node in device_nodes {
+            self.resolve_problem(device_node.as_path());
+        }
+    }
+}
+
+fn unique_label(device_node: &Path) -> Value {
+    let device_node_name = device_node
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    json!(format!("/org/inexor/input/problem/{}", device_node_name))
+}
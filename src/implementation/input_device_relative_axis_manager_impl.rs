@@ -2,16 +2,21 @@ use crate::di::*;
 use async_trait::async_trait;
 use log::{error, trace};
 
-use crate::api::{InputDeviceRelativeAxisManager, INPUT_DEVICE_RELATIVE_AXIS, NAMESPACE_INPUT_DEVICE};
+use crate::api::{InputDeviceRelativeAxisManager, ANY_DEVICE_LABEL, INPUT_DEVICE_RELATIVE_AXIS, NAMESPACE_INPUT_DEVICE};
+use crate::backend::InputBackendDevice;
+use crate::behaviour::entity::InputDeviceProperties;
 use crate::behaviour::entity::InputDeviceRelativeAxisProperties;
 use crate::behaviour::relation::relative_axis_event::RELATIVE_AXIS_EVENT;
+use crate::behaviour::relation::send_state_event::SEND_RELATIVE_AXIS_EVENT;
 use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::{Device, RelativeAxisType};
+use evdev::RelativeAxisType;
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -23,9 +28,20 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     return PluginContextContainer(RwLock::new(None));
 }
 
+/// The ids of the relative axes of each input device, including the relative axes of the pseudo device any-device.
+#[wrapper]
+pub struct InputDeviceRelativeAxisStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_input_device_relative_axis_storage() -> InputDeviceRelativeAxisStorage {
+    InputDeviceRelativeAxisStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceRelativeAxisManagerImpl {
     context: PluginContextContainer,
+
+    input_device_relative_axes: InputDeviceRelativeAxisStorage,
 }
 
 impl InputDeviceRelativeAxisManagerImpl {}
@@ -37,7 +53,7 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         self.context.0.write().unwrap().replace(context.clone());
     }
 
-    fn create_input_device_relative_axes(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>) {
+    fn create_input_device_relative_axes(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>) {
         let supported_relative_axes = device.supported_relative_axes();
         match supported_relative_axes {
             Some(supported_relative_axes) => {
@@ -50,17 +66,18 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         }
     }
 
-    fn create_input_device_relative_axis(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType) {
+    fn create_input_device_relative_axis(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType) {
         let reader = self.context.0.read().unwrap();
         let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
-        let device_name = device.name().unwrap_or("Unnamed Device");
-        let physical_path = device.physical_path().unwrap_or("");
+        // The ids and the labels are derived from the identity and the label of the input device
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
         let relative_axis_name = format!("{:?}", relative_axis);
-        let unique_name = format!("{}-{}-{}", device_name, physical_path, relative_axis_name);
+        let unique_name = format!("{}-{}", identity, relative_axis_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
-        let input_device_relative_axis = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), relative_axis_name.clone(), relative_axis);
+        let input_device_relative_axis = self.create_entity_instance(uuid, device_label, unique_name.clone(), relative_axis_name.clone(), relative_axis);
         let input_device_relative_axis = entity_instance_manager.create(input_device_relative_axis);
-        self.try_create_relative_axis_event(input_device, input_device_relative_axis, unique_name);
+        self.try_create_relative_axis_event(input_device, input_device_relative_axis, unique_name, true);
     }
 
     fn create_any_device_relative_axis(&self, input_device: Arc<ReactiveEntityInstance>, relative_axis: RelativeAxisType) {
@@ -72,18 +89,18 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
         if !entity_instance_manager.has(uuid) {
             let input_device_relative_axis =
-                self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), relative_axis_name.clone(), relative_axis);
+                self.create_entity_instance(uuid, ANY_DEVICE_LABEL.into(), unique_name.clone(), relative_axis_name.clone(), relative_axis);
             let input_device_relative_axis = entity_instance_manager.create(input_device_relative_axis);
-            self.try_create_relative_axis_event(input_device, input_device_relative_axis, unique_name);
+            self.try_create_relative_axis_event(input_device, input_device_relative_axis, unique_name, false);
         } else {
-            self.create_relative_axis_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone());
+            self.create_relative_axis_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone(), false);
         }
     }
 
     fn create_entity_instance(
         &self,
         uuid: Uuid,
-        device_name: String,
+        device_label: String,
         unique_name: String,
         relative_axis_name: String,
         relative_axis: RelativeAxisType,
@@ -91,10 +108,14 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         EntityInstanceBuilder::new(INPUT_DEVICE_RELATIVE_AXIS)
             .id(uuid)
             .property(InputDeviceRelativeAxisProperties::NAME, json!(unique_name))
-            .property(InputDeviceRelativeAxisProperties::LABEL, unique_label(device_name, relative_axis_name.clone()))
+            .property(InputDeviceRelativeAxisProperties::LABEL, unique_label(device_label, relative_axis_name.clone()))
             .property(InputDeviceRelativeAxisProperties::RELATIVE_AXIS, json!(relative_axis_name))
             .property(InputDeviceRelativeAxisProperties::RELATIVE_AXIS_TYPE, json!(relative_axis.0))
             .property(InputDeviceRelativeAxisProperties::STATE, InputDeviceRelativeAxisProperties::STATE.default_value())
+            .property(
+                InputDeviceRelativeAxisProperties::LAST_CHANGED,
+                InputDeviceRelativeAxisProperties::LAST_CHANGED.default_value(),
+            )
             .get()
     }
 
@@ -103,11 +124,12 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         input_device: Arc<ReactiveEntityInstance>,
         input_device_relative_axis: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_relative_axis: bool,
     ) {
         match input_device_relative_axis {
             Ok(input_device_relative_axis) => {
                 trace!("Registered {} {} as {}", INPUT_DEVICE_RELATIVE_AXIS, unique_name, input_device_relative_axis.id);
-                self.create_relative_axis_event(input_device.clone(), input_device_relative_axis.clone());
+                self.create_relative_axis_event(input_device.clone(), input_device_relative_axis.clone(), create_send_relative_axis);
             }
             Err(_) => {
                 error!("Failed to create entity instance for {} {}!", INPUT_DEVICE_RELATIVE_AXIS, unique_name);
@@ -115,18 +137,71 @@ impl InputDeviceRelativeAxisManager for InputDeviceRelativeAxisManagerImpl {
         }
     }
 
-    fn create_relative_axis_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_relative_axis: Arc<ReactiveEntityInstance>) {
+    fn create_relative_axis_event(
+        &self,
+        input_device: Arc<ReactiveEntityInstance>,
+        input_device_relative_axis: Arc<ReactiveEntityInstance>,
+        create_send_relative_axis: bool,
+    ) {
+        self.input_device_relative_axes
+            .0
+            .write()
+            .unwrap()
+            .entry(input_device.id)
+            .or_default()
+            .insert(input_device_relative_axis.id);
         let reader = self.context.0.read().unwrap();
         let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
         let relative_axis_event = RelationInstanceBuilder::new(input_device.id, RELATIVE_AXIS_EVENT, input_device_relative_axis.id).get();
         let _relative_axis_event = relation_instance_manager.create(relative_axis_event);
+        if create_send_relative_axis {
+            let send_relative_axis_event = RelationInstanceBuilder::new(input_device_relative_axis.id, SEND_RELATIVE_AXIS_EVENT, input_device.id).get();
+            let _send_relative_axis_event = relation_instance_manager.create(send_relative_axis_event);
+        }
+    }
+
+    fn remove_input_device_relative_axes(&self, input_device_id: Uuid) {
+        let input_device_relative_axes = match self.input_device_relative_axes.0.write().unwrap().remove(&input_device_id) {
+            Some(input_device_relative_axes) => input_device_relative_axes,
+            None => return,
+        };
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        for input_device_relative_axis_id in input_device_relative_axes {
+            // The relative axes of any-device are kept as long as another input device provides them
+            let provided = self
+                .input_device_relative_axes
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|input_device_relative_axes| input_device_relative_axes.contains(&input_device_relative_axis_id));
+            let relation_instances = relation_instance_manager
+                .get_by_inbound_entity(input_device_relative_axis_id)
+                .into_iter()
+                .chain(relation_instance_manager.get_by_outbound_entity(input_device_relative_axis_id));
+            for relation_instance in relation_instances {
+                // Only the relations from and to the removed input device (relative_axis_event, send_relative_axis_event)
+                if provided && relation_instance.outbound.id != input_device_id && relation_instance.inbound.id != input_device_id {
+                    continue;
+                }
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            if !provided {
+                entity_instance_manager.delete(input_device_relative_axis_id);
+                trace!("Removed {} {}", INPUT_DEVICE_RELATIVE_AXIS, input_device_relative_axis_id);
+            }
+        }
     }
 }
 
-fn unique_label(device_name: String, relative_axis_name: String) -> Value {
+fn unique_label(device_label: String, relative_axis_name: String) -> Value {
     json!(format!(
-        "/org/inexor/input/{}/relative_axis/{}",
-        device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_"),
-        relative_axis_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")
+        "{}/relative_axis/{}",
+        device_label,
+        relative_axis_name.to_lowercase().replace("-", "_").replace(" ", "_")
     ))
 }
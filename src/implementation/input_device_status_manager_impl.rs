@@ -0,0 +1,231 @@
+use crate::di::*;
+use async_trait::async_trait;
+use log::{debug, error, trace};
+
+use crate::api::{InputDeviceStatusManager, INPUT_DEVICE_PLUGIN, NAMESPACE_INPUT_DEVICE};
+use crate::behaviour::entity::InputDevicePluginProperties;
+use crate::behaviour::event_payload::{
+    INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT, INPUT_EVENT_KIND_KEY_EVENT, INPUT_EVENT_KIND_LED_EVENT, INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
+    INPUT_EVENT_KIND_SWITCH_EVENT,
+};
+use crate::builder::EntityInstanceBuilder;
+use crate::model::PropertyInstanceGetter;
+use crate::model::PropertyInstanceSetter;
+use crate::model::ReactiveEntityInstance;
+use crate::plugins::PluginContext;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The interval in which the statistics are published on the status entity. The statistics are
+/// not published on every event, because the input devices may produce thousands of events per second.
+const STATUS_PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The kinds of the received events which are counted.
+const INPUT_EVENT_KINDS: [&str; 5] = [
+    INPUT_EVENT_KIND_KEY_EVENT,
+    INPUT_EVENT_KIND_LED_EVENT,
+    INPUT_EVENT_KIND_RELATIVE_AXIS_EVENT,
+    INPUT_EVENT_KIND_ABSOLUTE_AXIS_EVENT,
+    INPUT_EVENT_KIND_SWITCH_EVENT,
+];
+
+#[wrapper]
+pub struct PluginContextContainer(RwLock<Option<std::sync::Arc<dyn PluginContext>>>);
+
+#[provides]
+fn create_empty_plugin_context_container() -> PluginContextContainer {
+    PluginContextContainer(RwLock::new(None))
+}
+
+/// The statistics which are collected by the input devices and the relation behaviours.
+#[derive(Default)]
+pub struct InputDeviceStatistics {
+    managed_devices: AtomicUsize,
+
+    /// The number of received events by the kind of the event, in the order of INPUT_EVENT_KINDS.
+    /// The counters are updated by the readers without locking.
+    events_received: [AtomicU64; INPUT_EVENT_KINDS.len()],
+
+    events_sent: AtomicU64,
+
+    dropped_send_requests: AtomicU64,
+
+    reader_restarts: AtomicU64,
+
+    /// The timestamp of the last received event in microseconds since the unix epoch.
+    last_event: AtomicU64,
+
+    /// The ids of the input devices whose reader has been started at least once.
+    started_readers: Mutex<HashSet<Uuid>>,
+}
+
+#[wrapper]
+pub struct InputDeviceStatisticsContainer(Arc<InputDeviceStatistics>);
+
+#[provides]
+fn create_input_device_statistics_container() -> InputDeviceStatisticsContainer {
+    InputDeviceStatisticsContainer(Arc::new(InputDeviceStatistics::default()))
+}
+
+#[wrapper]
+pub struct StatusEntityContainer(RwLock<Option<Arc<ReactiveEntityInstance>>>);
+
+#[provides]
+fn create_empty_status_entity_container() -> StatusEntityContainer {
+    StatusEntityContainer(RwLock::new(None))
+}
+
+#[wrapper]
+pub struct StatusStopperContainer(RwLock<Option<crossbeam::channel::Sender<()>>>);
+
+#[provides]
+fn create_empty_status_stopper_container() -> StatusStopperContainer {
+    StatusStopperContainer(RwLock::new(None))
+}
+
+#[component]
+pub struct InputDeviceStatusManagerImpl {
+    context: PluginContextContainer,
+
+    statistics: InputDeviceStatisticsContainer,
+
+    status: StatusEntityContainer,
+
+    stopper: StatusStopperContainer,
+}
+
+impl InputDeviceStatusManagerImpl {
+    fn create_status_entity(&self) -> Option<Arc<ReactiveEntityInstance>> {
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref()?.get_entity_instance_manager().clone();
+        let entity_instance = EntityInstanceBuilder::new(INPUT_DEVICE_PLUGIN)
+            .id(Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, INPUT_DEVICE_PLUGIN.as_bytes()))
+            .property(InputDevicePluginProperties::NAME, json!(INPUT_DEVICE_PLUGIN))
+            .property(InputDevicePluginProperties::LABEL, json!("/org/inexor/input/plugin"))
+            .property(InputDevicePluginProperties::RELOAD_CONFIG, json!(false))
+            .get();
+        match entity_instance_manager.create(entity_instance) {
+            Ok(status) => {
+                trace!("Registered {} as {}", INPUT_DEVICE_PLUGIN, status.id);
+                Some(status)
+            }
+            Err(_) => {
+                error!("Failed to create entity instance for {}!", INPUT_DEVICE_PLUGIN);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+#[provides]
+impl InputDeviceStatusManager for InputDeviceStatusManagerImpl {
+    fn set_context(&self, context: Arc<dyn PluginContext>) {
+        self.context.0.write().unwrap().replace(context.clone());
+    }
+
+    fn start(&self) {
+        let status = match self.create_status_entity() {
+            Some(status) => status,
+            None => return,
+        };
+        self.status.0.write().unwrap().replace(status.clone());
+        publish(&status, &self.statistics.0);
+        let (stopper_tx, stopper_rx) = crossbeam::channel::bounded(1);
+        self.stopper.0.write().unwrap().replace(stopper_tx);
+        let statistics = self.statistics.0.clone();
+        let _handle = thread::Builder::new().name("input-device-status".into()).spawn(move || {
+            debug!("Publishing the status of the input device plugin on {}", status.id);
+            loop {
+                match stopper_rx.recv_timeout(STATUS_PUBLISH_INTERVAL) {
+                    Err(crossbeam::channel::RecvTimeoutError::Timeout) => {}
+                    // Stop thread
+                    _ => break,
+                }
+                publish(&status, &statistics);
+            }
+        });
+    }
+
+    fn stop(&self) {
+        if let Some(stopper) = self.stopper.0.write().unwrap().take() {
+            let _ = stopper.send(());
+        }
+        // The status entity is removed, so that no orphan is left behind if the plugin is unloaded
+        if let Some(status) = self.status.0.write().unwrap().take() {
+            let reader = self.context.0.read().unwrap();
+            if let Some(context) = reader.as_ref() {
+                context.get_entity_instance_manager().delete(status.id);
+                trace!("Removed {} {}", INPUT_DEVICE_PLUGIN, status.id);
+            }
+        }
+    }
+
+    fn set_managed_devices(&self, managed_devices: usize) {
+        self.statistics.0.managed_devices.store(managed_devices, Ordering::Relaxed);
+    }
+
+    fn event_received(&self, kind: &str, timestamp: u64) {
+        if let Some(index) = INPUT_EVENT_KINDS.iter().position(|input_event_kind| *input_event_kind == kind) {
+            self.statistics.0.events_received[index].fetch_add(1, Ordering::Relaxed);
+        }
+        self.statistics.0.last_event.fetch_max(timestamp, Ordering::Relaxed);
+    }
+
+    fn events_sent(&self, count: usize) {
+        self.statistics.0.events_sent.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    fn send_request_dropped(&self) {
+        self.statistics.0.dropped_send_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reader_started(&self, input_device_id: Uuid) {
+        if !self.statistics.0.started_readers.lock().unwrap().insert(input_device_id) {
+            self.statistics.0.reader_restarts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Publishes the statistics on the status entity. Only the properties which have been changed
+/// are set, so that observers of the status entity aren't notified needlessly.
+fn publish(status: &ReactiveEntityInstance, statistics: &InputDeviceStatistics) {
+    // Only the kinds of events which have been received at least once
+    let events_received: Map<String, Value> = INPUT_EVENT_KINDS
+        .iter()
+        .zip(statistics.events_received.iter())
+        .map(|(kind, count)| (kind.to_string(), count.load(Ordering::Relaxed)))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| (kind, json!(count)))
+        .collect();
+    set_if_changed(
+        status,
+        InputDevicePluginProperties::MANAGED_DEVICES,
+        json!(statistics.managed_devices.load(Ordering::Relaxed)),
+    );
+    set_if_changed(status, InputDevicePluginProperties::EVENTS_RECEIVED, json!(events_received));
+    set_if_changed(status, InputDevicePluginProperties::EVENTS_SENT, json!(statistics.events_sent.load(Ordering::Relaxed)));
+    set_if_changed(
+        status,
+        InputDevicePluginProperties::DROPPED_SEND_REQUESTS,
+        json!(statistics.dropped_send_requests.load(Ordering::Relaxed)),
+    );
+    set_if_changed(
+        status,
+        InputDevicePluginProperties::READER_RESTARTS,
+        json!(statistics.reader_restarts.load(Ordering::Relaxed)),
+    );
+    set_if_changed(status, InputDevicePluginProperties::LAST_EVENT, json!(statistics.last_event.load(Ordering::Relaxed)));
+}
+
+fn set_if_changed(status: &ReactiveEntityInstance, property: InputDevicePluginProperties, value: Value) {
+    let property_name = property.to_string();
+    if status.get(property_name.clone()).as_ref() != Some(&value) {
+        status.set(property_name, value);
+    }
+}
@@ -2,16 +2,21 @@ use crate::di::*;
 use async_trait::async_trait;
 use log::{error, trace};
 
-use crate::api::{InputDeviceSwitchManager, INPUT_DEVICE_SWITCH, NAMESPACE_INPUT_DEVICE};
+use crate::api::{InputDeviceSwitchManager, ANY_DEVICE_LABEL, INPUT_DEVICE_SWITCH, NAMESPACE_INPUT_DEVICE};
+use crate::backend::InputBackendDevice;
+use crate::behaviour::entity::InputDeviceProperties;
 use crate::behaviour::entity::InputDeviceSwitchProperties;
+use crate::behaviour::relation::send_state_event::SEND_SWITCH_EVENT;
 use crate::behaviour::relation::switch_event::SWITCH_EVENT;
 use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
 use crate::model::ReactiveEntityInstance;
 use crate::plugins::PluginContext;
-use evdev::{Device, SwitchType};
+use evdev::{AttributeSet, SwitchType};
 use inexor_rgf_core_model::EntityInstance;
 use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -23,9 +28,20 @@ fn create_empty_plugin_context_container() -> PluginContextContainer {
     return PluginContextContainer(RwLock::new(None));
 }
 
+/// The ids of the switches of each input device, including the switches of the pseudo device any-device.
+#[wrapper]
+pub struct InputDeviceSwitchStorage(RwLock<HashMap<Uuid, HashSet<Uuid>>>);
+
+#[provides]
+fn create_input_device_switch_storage() -> InputDeviceSwitchStorage {
+    InputDeviceSwitchStorage(RwLock::new(HashMap::new()))
+}
+
 #[component]
 pub struct InputDeviceSwitchManagerImpl {
     context: PluginContextContainer,
+
+    input_device_switches: InputDeviceSwitchStorage,
 }
 
 impl InputDeviceSwitchManagerImpl {}
@@ -37,29 +53,32 @@ impl InputDeviceSwitchManager for InputDeviceSwitchManagerImpl {
         self.context.0.write().unwrap().replace(context.clone());
     }
 
-    fn create_input_device_switches(&self, device: &Device, entity_instance: Arc<ReactiveEntityInstance>) {
+    fn create_input_device_switches(&self, device: &dyn InputBackendDevice, entity_instance: Arc<ReactiveEntityInstance>) {
         let supported_switches = device.supported_switches();
         match supported_switches {
             Some(supported_switches) => {
+                let switch_state = device.switch_state().ok();
                 for switch in supported_switches.iter() {
-                    self.create_input_device_switch(device, entity_instance.clone(), switch);
+                    self.create_input_device_switch(entity_instance.clone(), switch, switch_state.as_ref());
                 }
             }
             None => {}
         }
     }
 
-    fn create_input_device_switch(&self, device: &Device, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType) {
+    fn create_input_device_switch(&self, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType, switch_state: Option<&AttributeSet<SwitchType>>) {
         let reader = self.context.0.read().unwrap();
         let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
-        let device_name = device.name().unwrap_or("Unnamed Device");
-        let physical_path = device.physical_path().unwrap_or("");
+        // The ids and the labels are derived from the identity and the label of the input device
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
         let switch_name = format!("{:?}", switch);
-        let unique_name = format!("{}-{}-{}", device_name, physical_path, switch_name);
+        let unique_name = format!("{}-{}", identity, switch_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
-        let input_device_switch = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), switch_name.clone(), switch);
+        let state = switch_state.map(|switch_state| switch_state.contains(switch)).unwrap_or(false);
+        let input_device_switch = self.create_entity_instance(uuid, device_label, unique_name.clone(), switch_name.clone(), switch, state);
         let input_device_switch = entity_instance_manager.create(input_device_switch);
-        self.try_create_switch_event(input_device, input_device_switch, unique_name);
+        self.try_create_switch_event(input_device, input_device_switch, unique_name, true);
     }
 
     fn create_any_device_switch(&self, input_device: Arc<ReactiveEntityInstance>, switch: SwitchType) {
@@ -70,22 +89,31 @@ impl InputDeviceSwitchManager for InputDeviceSwitchManagerImpl {
         let unique_name = format!("{}-{}", device_name, switch_name);
         let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
         if !entity_instance_manager.has(uuid) {
-            let input_device_switch = self.create_entity_instance(uuid, device_name.into(), unique_name.clone(), switch_name.clone(), switch);
+            let input_device_switch = self.create_entity_instance(uuid, ANY_DEVICE_LABEL.into(), unique_name.clone(), switch_name.clone(), switch, false);
             let input_device_switch = entity_instance_manager.create(input_device_switch);
-            self.try_create_switch_event(input_device, input_device_switch, unique_name);
+            self.try_create_switch_event(input_device, input_device_switch, unique_name, false);
         } else {
-            self.create_switch_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone());
+            self.create_switch_event(input_device.clone(), entity_instance_manager.get(uuid).unwrap().clone(), false);
         }
     }
 
-    fn create_entity_instance(&self, uuid: Uuid, device_name: String, unique_name: String, switch_name: String, switch: SwitchType) -> EntityInstance {
+    fn create_entity_instance(
+        &self,
+        uuid: Uuid,
+        device_label: String,
+        unique_name: String,
+        switch_name: String,
+        switch: SwitchType,
+        state: bool,
+    ) -> EntityInstance {
         EntityInstanceBuilder::new(INPUT_DEVICE_SWITCH)
             .id(uuid)
             .property(InputDeviceSwitchProperties::NAME, json!(unique_name))
-            .property(InputDeviceSwitchProperties::LABEL, unique_label(device_name, switch_name.clone()))
+            .property(InputDeviceSwitchProperties::LABEL, unique_label(device_label, switch_name.clone()))
             .property(InputDeviceSwitchProperties::SWITCH, json!(switch_name))
             .property(InputDeviceSwitchProperties::SWITCH_TYPE, json!(switch.0))
-            .property(InputDeviceSwitchProperties::STATE, InputDeviceSwitchProperties::STATE.default_value())
+            .property(InputDeviceSwitchProperties::STATE, json!(if state { 1 } else { 0 }))
+            .property(InputDeviceSwitchProperties::LAST_CHANGED, InputDeviceSwitchProperties::LAST_CHANGED.default_value())
             .get()
     }
 
@@ -94,11 +122,12 @@ impl InputDeviceSwitchManager for InputDeviceSwitchManagerImpl {
         input_device: Arc<ReactiveEntityInstance>,
         input_device_switch: Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError>,
         unique_name: String,
+        create_send_switch: bool,
     ) {
         match input_device_switch {
             Ok(input_device_switch) => {
                 trace!("Registered {} {} as {}", INPUT_DEVICE_SWITCH, unique_name, input_device_switch.id);
-                self.create_switch_event(input_device.clone(), input_device_switch.clone());
+                self.create_switch_event(input_device.clone(), input_device_switch.clone(), create_send_switch);
             }
             Err(_) => {
                 error!("Failed to create entity instance for {} {}!", INPUT_DEVICE_SWITCH, unique_name);
@@ -106,18 +135,62 @@ impl InputDeviceSwitchManager for InputDeviceSwitchManagerImpl {
         }
     }
 
-    fn create_switch_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_switch: Arc<ReactiveEntityInstance>) {
+    fn create_switch_event(&self, input_device: Arc<ReactiveEntityInstance>, input_device_switch: Arc<ReactiveEntityInstance>, create_send_switch: bool) {
+        self.input_device_switches
+            .0
+            .write()
+            .unwrap()
+            .entry(input_device.id)
+            .or_default()
+            .insert(input_device_switch.id);
         let reader = self.context.0.read().unwrap();
         let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
         let switch_event = RelationInstanceBuilder::new(input_device.id, SWITCH_EVENT, input_device_switch.id).get();
         let _switch_event = relation_instance_manager.create(switch_event);
+        if create_send_switch {
+            let send_switch_event = RelationInstanceBuilder::new(input_device_switch.id, SEND_SWITCH_EVENT, input_device.id).get();
+            let _send_switch_event = relation_instance_manager.create(send_switch_event);
+        }
+    }
+
+    fn remove_input_device_switches(&self, input_device_id: Uuid) {
+        let input_device_switches = match self.input_device_switches.0.write().unwrap().remove(&input_device_id) {
+            Some(input_device_switches) => input_device_switches,
+            None => return,
+        };
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        for input_device_switch_id in input_device_switches {
+            // The switches of any-device are kept as long as another input device provides them
+            let provided = self
+                .input_device_switches
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|input_device_switches| input_device_switches.contains(&input_device_switch_id));
+            let relation_instances = relation_instance_manager
+                .get_by_inbound_entity(input_device_switch_id)
+                .into_iter()
+                .chain(relation_instance_manager.get_by_outbound_entity(input_device_switch_id));
+            for relation_instance in relation_instances {
+                // Only the relations from and to the removed input device (switch_event, send_switch_event)
+                if provided && relation_instance.outbound.id != input_device_id && relation_instance.inbound.id != input_device_id {
+                    continue;
+                }
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            if !provided {
+                entity_instance_manager.delete(input_device_switch_id);
+                trace!("Removed {} {}", INPUT_DEVICE_SWITCH, input_device_switch_id);
+            }
+        }
     }
 }
 
-fn unique_label(device_name: String, switch_name: String) -> Value {
-    json!(format!(
-        "/org/inexor/input/{}/switch/{}",
-        device_name.clone().to_lowercase().replace("-", "_").replace(" ", "_"),
-        switch_name.clone().to_lowercase().replace("-", "_").replace(" ", "_")
-    ))
+fn unique_label(device_label: String, switch_name: String) -> Value {
+    json!(format!("{}/switch/{}", device_label, switch_name.to_lowercase().replace("-", "_").replace(" ", "_")))
 }
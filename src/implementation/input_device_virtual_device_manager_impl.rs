@@ -0,0 +1,175 @@
+use crate::di::*;
+use async_trait::async_trait;
+use log::{debug, error, trace};
+
+use crate::api::{
+    InputDeviceBackendManager, InputDeviceVirtualDeviceManager, HAS_VIRTUAL_DEVICE, NAMESPACE_INPUT_DEVICE, VIRTUAL_DEVICE, VIRTUAL_DEVICE_NAME_SUFFIX,
+};
+use crate::backend::{InputBackendDevice, InputBackendVirtualDevice};
+use crate::behaviour::entity::InputDeviceProperties;
+use crate::behaviour::entity::VirtualDeviceProperties;
+use crate::builder::{EntityInstanceBuilder, RelationInstanceBuilder};
+use crate::model::PropertyInstanceGetter;
+use crate::model::ReactiveEntityInstance;
+use crate::plugins::PluginContext;
+use evdev::InputEvent;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex, RwLock};
+use uuid::Uuid;
+
+#[wrapper]
+pub struct PluginContextContainer(RwLock<Option<std::sync::Arc<dyn PluginContext>>>);
+
+#[provides]
+fn create_empty_plugin_context_container() -> PluginContextContainer {
+    PluginContextContainer(RwLock::new(None))
+}
+
+pub struct VirtualDeviceEntry {
+    /// The id of the virtual device entity instance.
+    pub id: Uuid,
+
+    pub virtual_device: Mutex<Box<dyn InputBackendVirtualDevice>>,
+}
+
+/// The virtual devices by the id of their input device.
+#[wrapper]
+pub struct VirtualDeviceStorage(RwLock<HashMap<Uuid, Arc<VirtualDeviceEntry>>>);
+
+#[provides]
+fn create_virtual_device_storage() -> VirtualDeviceStorage {
+    VirtualDeviceStorage(RwLock::new(HashMap::new()))
+}
+
+#[component]
+pub struct InputDeviceVirtualDeviceManagerImpl {
+    input_device_backend_manager: Wrc<dyn InputDeviceBackendManager>,
+
+    context: PluginContextContainer,
+
+    virtual_devices: VirtualDeviceStorage,
+}
+
+impl InputDeviceVirtualDeviceManagerImpl {
+    fn emit(entry: Option<Arc<VirtualDeviceEntry>>, events: &[InputEvent]) -> io::Result<()> {
+        match entry {
+            Some(entry) => entry.virtual_device.lock().unwrap().emit(events),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+}
+
+#[async_trait]
+#[provides]
+impl InputDeviceVirtualDeviceManager for InputDeviceVirtualDeviceManagerImpl {
+    fn set_context(&self, context: Arc<dyn PluginContext>) {
+        self.context.0.write().unwrap().replace(context.clone());
+    }
+
+    fn create_virtual_device(&self, device: &dyn InputBackendDevice, input_device: Arc<ReactiveEntityInstance>) {
+        let device_name = device.name().unwrap_or("Unnamed Device");
+        let identity = input_device.as_string(InputDeviceProperties::IDENTITY).unwrap_or_default();
+        let device_label = input_device.as_string(InputDeviceProperties::LABEL).unwrap_or_default();
+        let virtual_device_name = format!("{}{}", device_name, VIRTUAL_DEVICE_NAME_SUFFIX);
+        let virtual_device = match self
+            .input_device_backend_manager
+            .get_backend()
+            .create_virtual_device(&virtual_device_name, device)
+        {
+            Ok(virtual_device) => virtual_device,
+            Err(e) => {
+                error!("Failed to create virtual device for {}: {:?}", device_name, e);
+                return;
+            }
+        };
+        let unique_name = format!("{}-{}", identity, VIRTUAL_DEVICE);
+        let uuid = Uuid::new_v5(&NAMESPACE_INPUT_DEVICE, unique_name.as_bytes());
+        // The virtual device have to be registered before the behaviour of the entity instance is created
+        self.virtual_devices.0.write().unwrap().insert(
+            input_device.id,
+            Arc::new(VirtualDeviceEntry {
+                id: uuid,
+                virtual_device: Mutex::new(virtual_device),
+            }),
+        );
+        let reader = self.context.0.read().unwrap();
+        let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+        let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+        let entity_instance = EntityInstanceBuilder::new(VIRTUAL_DEVICE)
+            .id(uuid)
+            .property(VirtualDeviceProperties::NAME, json!(virtual_device_name))
+            .property(VirtualDeviceProperties::LABEL, unique_label(device_label))
+            .property(VirtualDeviceProperties::SEND_EVENT, json!({}))
+            .get();
+        match entity_instance_manager.create(entity_instance) {
+            Ok(virtual_device) => {
+                trace!("Registered {} {} as {}", VIRTUAL_DEVICE, virtual_device_name, virtual_device.id);
+                let has_virtual_device = RelationInstanceBuilder::new(input_device.id, HAS_VIRTUAL_DEVICE, virtual_device.id).get();
+                if relation_instance_manager.create(has_virtual_device).is_err() {
+                    error!("Failed to create relation instance {} for {} {}!", HAS_VIRTUAL_DEVICE, VIRTUAL_DEVICE, virtual_device_name);
+                }
+            }
+            Err(_) => {
+                // Otherwise the virtual device would be kept without an entity instance
+                self.virtual_devices.0.write().unwrap().remove(&input_device.id);
+                error!("Failed to create entity instance for {} {}!", VIRTUAL_DEVICE, virtual_device_name);
+            }
+        }
+    }
+
+    fn is_virtual_device(&self, device: &dyn InputBackendDevice) -> bool {
+        match device.name() {
+            Some(name) => self
+                .virtual_devices
+                .0
+                .read()
+                .unwrap()
+                .values()
+                .any(|entry| entry.virtual_device.lock().unwrap().name() == name),
+            None => false,
+        }
+    }
+
+    fn has_virtual_device(&self, input_device_id: Uuid) -> bool {
+        self.virtual_devices.0.read().unwrap().contains_key(&input_device_id)
+    }
+
+    fn send_events(&self, input_device_id: Uuid, events: &[InputEvent]) -> io::Result<()> {
+        let entry = self.virtual_devices.0.read().unwrap().get(&input_device_id).cloned();
+        InputDeviceVirtualDeviceManagerImpl::emit(entry, events)
+    }
+
+    fn send_events_by_virtual_device(&self, virtual_device_id: Uuid, events: &[InputEvent]) -> io::Result<()> {
+        let entry = self
+            .virtual_devices
+            .0
+            .read()
+            .unwrap()
+            .values()
+            .find(|entry| entry.id == virtual_device_id)
+            .cloned();
+        InputDeviceVirtualDeviceManagerImpl::emit(entry, events)
+    }
+
+    fn remove_virtual_device(&self, input_device_id: Uuid) {
+        let entry = self.virtual_devices.0.write().unwrap().remove(&input_device_id);
+        if let Some(entry) = entry {
+            let reader = self.context.0.read().unwrap();
+            let entity_instance_manager = reader.as_ref().unwrap().get_entity_instance_manager().clone();
+            let relation_instance_manager = reader.as_ref().unwrap().get_relation_instance_manager().clone();
+            for relation_instance in relation_instance_manager.get_by_inbound_entity(entry.id) {
+                if let Some(edge_key) = relation_instance.get_key() {
+                    relation_instance_manager.delete(edge_key);
+                }
+            }
+            entity_instance_manager.delete(entry.id);
+            debug!("Removed {} {}", VIRTUAL_DEVICE, entry.id);
+        }
+    }
+}
+
+fn unique_label(device_label: String) -> Value {
+    json!(format!("{}/virtual", device_label))
+}
@@ -1,13 +1,27 @@
 pub use input_device_absolute_axis_manager_impl::*;
+pub use input_device_backend_manager_impl::*;
+pub use input_device_event_dispatcher_impl::*;
+pub use input_device_hotplug_manager_impl::*;
 pub use input_device_key_manager_impl::*;
 pub use input_device_led_manager_impl::*;
 pub use input_device_manager_impl::*;
+pub use input_device_physical_device_manager_impl::*;
+pub use input_device_problem_manager_impl::*;
 pub use input_device_relative_axis_manager_impl::*;
+pub use input_device_status_manager_impl::*;
 pub use input_device_switch_manager_impl::*;
+pub use input_device_virtual_device_manager_impl::*;
 
 pub mod input_device_absolute_axis_manager_impl;
+pub mod input_device_backend_manager_impl;
+pub mod input_device_event_dispatcher_impl;
+pub mod input_device_hotplug_manager_impl;
 pub mod input_device_key_manager_impl;
 pub mod input_device_led_manager_impl;
 pub mod input_device_manager_impl;
+pub mod input_device_physical_device_manager_impl;
+pub mod input_device_problem_manager_impl;
 pub mod input_device_relative_axis_manager_impl;
+pub mod input_device_status_manager_impl;
 pub mod input_device_switch_manager_impl;
+pub mod input_device_virtual_device_manager_impl;
@@ -15,9 +15,14 @@ use crate::plugin::InputDevicePlugin;
 use crate::plugins::{Plugin, PluginError};
 
 pub mod api;
+pub mod backend;
 pub mod behaviour;
+pub mod classification;
 pub mod config;
+pub mod hotplug;
+pub mod identity;
 pub mod implementation;
+pub mod metadata;
 pub mod plugin;
 pub mod provider;
 
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+use crate::backend::InputBackendDevice;
+use uuid::Uuid;
+
+/// Where an input device can be found in the file system. The locations may change if the input
+/// device is plugged in again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceLocation {
+    /// The device node, for example /dev/input/event3.
+    pub device_node: String,
+
+    /// The symlink in /dev/input/by-id, if any.
+    pub by_id: String,
+
+    /// The symlink in /dev/input/by-path, if any.
+    pub by_path: String,
+
+    /// The path of the input device in the sysfs, if any.
+    pub sysfs_path: String,
+}
+
+/// The names of the event types, keys, LEDs, axes, switches, force feedback effects and sounds
+/// which are supported by an input device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceCapabilities {
+    pub events: Vec<String>,
+
+    pub keys: Vec<String>,
+
+    pub leds: Vec<String>,
+
+    pub relative_axes: Vec<String>,
+
+    pub absolute_axes: Vec<String>,
+
+    pub switches: Vec<String>,
+
+    pub force_feedback_effects: Vec<String>,
+
+    pub sounds: Vec<String>,
+}
+
+/// Returns the locations of the given input device.
+pub fn location_of(device: &dyn InputBackendDevice) -> DeviceLocation {
+    DeviceLocation {
+        device_node: device.device_node().display().to_string(),
+        by_id: display_of(device.by_id()),
+        by_path: display_of(device.by_path()),
+        sysfs_path: display_of(device.sysfs_path()),
+    }
+}
+
+/// Returns the bus type of the given input device, for example usb, bluetooth, i2c, i8042 or
+/// virtual.
+pub fn bus_type_of(device: &dyn InputBackendDevice) -> String {
+    format!("{:?}", device.input_id().bus_type()).trim_start_matches("BUS_").to_lowercase()
+}
+
+/// Returns the names of the properties (INPUT_PROP_*) of the given input device, for example
+/// pointer, direct, buttonpad or accelerometer.
+pub fn input_properties_of(device: &dyn InputBackendDevice) -> Vec<String> {
+    device
+        .input_properties()
+        .iter()
+        .map(|input_property| format!("{:?}", input_property).to_lowercase())
+        .collect()
+}
+
+/// Returns the capabilities of the given input device. The names are the same as the names of the
+/// keys, LEDs, axes and switches, for example KEY_A or LED_NUML.
+pub fn capabilities_of(device: &dyn InputBackendDevice) -> DeviceCapabilities {
+    DeviceCapabilities {
+        events: names_of(device.supported_events().iter()),
+        keys: device.supported_keys().map(|keys| names_of(keys.iter())).unwrap_or_default(),
+        leds: device.supported_leds().map(|leds| names_of(leds.iter())).unwrap_or_default(),
+        relative_axes: device
+            .supported_relative_axes()
+            .map(|relative_axes| names_of(relative_axes.iter()))
+            .unwrap_or_default(),
+        absolute_axes: device
+            .supported_absolute_axes()
+            .map(|absolute_axes| names_of(absolute_axes.iter()))
+            .unwrap_or_default(),
+        switches: device.supported_switches().map(|switches| names_of(switches.iter())).unwrap_or_default(),
+        force_feedback_effects: device
+            .supported_force_feedback_effects()
+            .map(|force_feedback_effects| names_of(force_feedback_effects.iter()))
+            .unwrap_or_default(),
+        sounds: device.supported_sounds().map(|sounds| names_of(sounds.iter())).unwrap_or_default(),
+    }
+}
+
+fn names_of<T: Debug>(items: impl Iterator<Item = T>) -> Vec<String> {
+    items.map(|item| format!("{:?}", item)).collect()
+}
+
+fn display_of(path: Option<PathBuf>) -> String {
+    path.map(|path| path.display().to_string()).unwrap_or_default()
+}
+
+/// Returns the given label or, if it is assigned already, the label with the lowest free index
+/// suffix, for example /org/inexor/input/gamepad_2.
+pub fn indexed_label(labels: &HashMap<Uuid, String>, label: &str) -> String {
+    let mut indexed_label = label.to_string();
+    let mut index = 1;
+    while labels.values().any(|assigned_label| *assigned_label == indexed_label) {
+        index += 1;
+        indexed_label = format!("{}_{}", label, index);
+    }
+    indexed_label
+}
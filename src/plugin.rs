@@ -3,8 +3,8 @@ use std::sync::{Arc, RwLock};
 use crate::di::*;
 use async_trait::async_trait;
 
-use crate::api::InputDeviceManager;
-use crate::behaviour::entity::entity_behaviour_provider::InputDeviceEntityBehaviourProviderImpl;
+use crate::api::{InputDeviceHotplugManager, InputDeviceManager, InputDeviceStatusManager};
+use crate::behaviour::entity::entity_behaviour_provider::{InputDeviceEntityBehaviourProvider, InputDeviceEntityBehaviourProviderImpl};
 use crate::behaviour::relation::relation_behaviour_provider::InputDeviceRelationBehaviourProviderImpl;
 use crate::plugins::plugin::PluginMetadata;
 use crate::plugins::plugin_context::PluginContext;
@@ -35,6 +35,8 @@ pub struct InputDevicePluginImpl {
     relation_behaviour_provider: Wrc<InputDeviceRelationBehaviourProviderImpl>,
 
     input_device_manager: Wrc<dyn InputDeviceManager>,
+    input_device_hotplug_manager: Wrc<dyn InputDeviceHotplugManager>,
+    input_device_status_manager: Wrc<dyn InputDeviceStatusManager>,
 
     context: PluginContextContainer,
 }
@@ -61,11 +63,20 @@ impl Plugin for InputDevicePluginImpl {
     }
 
     fn post_init(&self) -> Result<(), PluginError> {
+        // The status entity is created first, so that the input devices are counted from the beginning
+        self.input_device_status_manager.start();
         self.input_device_manager.init();
+        self.input_device_hotplug_manager.start();
         Ok(())
     }
 
     fn pre_shutdown(&self) -> Result<(), PluginError> {
+        self.input_device_hotplug_manager.stop();
+        // Releases the keys held down by the virtual devices and waits for the readers
+        self.entity_behaviour_provider.stop_input_devices();
+        // No entities and relations are left behind if the plugin is unloaded or reloaded
+        self.input_device_manager.remove_input_devices();
+        self.input_device_status_manager.stop();
         Ok(())
     }
 
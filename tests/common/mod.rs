@@ -0,0 +1,337 @@
+//! An in-memory stand-in for the reactive graph flow runtime. The entity and relation instances
+//! are kept in maps and the behaviours of the plugin are added and removed like the runtime does.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use indradb::EdgeKey;
+use inexor_rgf_core_di::{profiles, Provider};
+use inexor_rgf_core_model::{EntityInstance, PropertyInstanceGetter, ReactiveEntityInstance, ReactiveRelationInstance, RelationInstance};
+use inexor_rgf_core_plugins::entity_instance_manager::EntityInstanceCreationError;
+use inexor_rgf_core_plugins::relation_instance_manager::RelationInstanceCreationError;
+use inexor_rgf_core_plugins::{
+    ComponentManager, EntityBehaviourProvider, EntityInstanceManager, EntityTypeManager, FlowManager, PluginContext, RelationBehaviourProvider,
+    RelationInstanceManager, RelationTypeManager,
+};
+use uuid::Uuid;
+
+use inexor_rgf_plugin_input_device::api::{InputDeviceBackendManager, InputDeviceEventDispatcher, InputDeviceHotplugManager, InputDeviceManager};
+use inexor_rgf_plugin_input_device::backend::SimulatedBackend;
+use inexor_rgf_plugin_input_device::behaviour::entity::entity_behaviour_provider::InputDeviceEntityBehaviourProviderImpl;
+use inexor_rgf_plugin_input_device::behaviour::relation::relation_behaviour_provider::InputDeviceRelationBehaviourProviderImpl;
+use inexor_rgf_plugin_input_device::config::{EntityCreation, InputDeviceOptions, InputDevicesConfig};
+use inexor_rgf_plugin_input_device::hotplug::DEFAULT_INPUT_DEVICE_PATH;
+
+/// How long the tests wait for the reader tasks and the hotplug thread.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The plugin with a simulated backend and an in-memory runtime.
+pub struct TestPlugin {
+    pub backend: Arc<SimulatedBackend>,
+
+    pub context: Arc<TestPluginContext>,
+
+    pub input_device_manager: Arc<dyn InputDeviceManager>,
+
+    pub input_device_hotplug_manager: Arc<dyn InputDeviceHotplugManager>,
+
+    pub input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher>,
+}
+
+impl TestPlugin {
+    pub fn new() -> TestPlugin {
+        let mut container = inexor_rgf_plugin_input_device::get::<profiles::Default>();
+        let entity_behaviour_provider: Arc<dyn EntityBehaviourProvider> = Provider::<InputDeviceEntityBehaviourProviderImpl>::get(&mut container);
+        let relation_behaviour_provider: Arc<dyn RelationBehaviourProvider> = Provider::<InputDeviceRelationBehaviourProviderImpl>::get(&mut container);
+        let input_device_backend_manager: Arc<dyn InputDeviceBackendManager> = Provider::<dyn InputDeviceBackendManager>::get(&mut container);
+        let input_device_manager: Arc<dyn InputDeviceManager> = Provider::<dyn InputDeviceManager>::get(&mut container);
+        let input_device_hotplug_manager: Arc<dyn InputDeviceHotplugManager> = Provider::<dyn InputDeviceHotplugManager>::get(&mut container);
+        let input_device_event_dispatcher: Arc<dyn InputDeviceEventDispatcher> = Provider::<dyn InputDeviceEventDispatcher>::get(&mut container);
+        let backend = Arc::new(SimulatedBackend::new());
+        input_device_backend_manager.set_backend(backend.clone());
+        let context = Arc::new(TestPluginContext::new(entity_behaviour_provider, relation_behaviour_provider));
+        input_device_manager.set_context(context.clone());
+        TestPlugin {
+            backend,
+            context,
+            input_device_manager,
+            input_device_hotplug_manager,
+            input_device_event_dispatcher,
+        }
+    }
+
+    /// Returns the entity instances of the given type.
+    pub fn entities(&self, type_name: &str) -> Vec<Arc<ReactiveEntityInstance>> {
+        self.context.entity_instance_manager.get_by_type(type_name)
+    }
+
+    /// Returns the relation instances of the given type.
+    pub fn relations(&self, type_name: &str) -> Vec<Arc<ReactiveRelationInstance>> {
+        self.context.relation_instance_manager.get_by_type(type_name)
+    }
+
+    /// Returns the entity instance of the given type whose property has the given value.
+    pub fn entity<P: Into<String>>(&self, type_name: &str, property: P, value: &str) -> Option<Arc<ReactiveEntityInstance>> {
+        let property = property.into();
+        self.entities(type_name)
+            .into_iter()
+            .find(|entity_instance| entity_instance.as_string(property.clone()).as_deref() == Some(value))
+    }
+}
+
+impl TestPlugin {
+    /// Returns the entity instances the given entity instance points to by relations of the given type.
+    pub fn related(&self, outbound_id: Uuid, type_name: &str) -> Vec<Arc<ReactiveEntityInstance>> {
+        self.context
+            .relation_instance_manager
+            .get_by_outbound_entity(outbound_id)
+            .into_iter()
+            .filter(|relation_instance| relation_instance.type_name == type_name)
+            .map(|relation_instance| relation_instance.inbound.clone())
+            .collect()
+    }
+
+    /// Returns the entity instance the given entity instance points to by a relation of the given
+    /// type and whose property has the given value.
+    pub fn related_entity<P: Into<String>>(&self, outbound_id: Uuid, type_name: &str, property: P, value: &str) -> Option<Arc<ReactiveEntityInstance>> {
+        let property = property.into();
+        self.related(outbound_id, type_name)
+            .into_iter()
+            .find(|entity_instance| entity_instance.as_string(property.clone()).as_deref() == Some(value))
+    }
+}
+
+impl Drop for TestPlugin {
+    fn drop(&mut self) {
+        self.input_device_hotplug_manager.stop();
+        self.input_device_manager.remove_input_devices();
+    }
+}
+
+/// A configuration which detects all input devices of the given directory and creates their entities up front.
+pub fn autodetect_config(input_device_path: &Path) -> InputDevicesConfig {
+    InputDevicesConfig {
+        autodetect: true,
+        autodetect_device_classes: Vec::new(),
+        autodetect_options: InputDeviceOptions::default(),
+        hotplug: true,
+        reconnect: false,
+        entity_creation: EntityCreation::Eager,
+        input_device_path: input_device_path.to_str().unwrap_or(DEFAULT_INPUT_DEVICE_PATH).into(),
+        input_device: Vec::new(),
+    }
+}
+
+/// Waits until the given condition is met. Returns false if the condition isn't met in time.
+pub fn wait_until<F: Fn() -> bool>(condition: F) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < TIMEOUT {
+        if condition() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    condition()
+}
+
+pub struct TestPluginContext {
+    pub entity_instance_manager: Arc<TestEntityInstanceManager>,
+
+    pub relation_instance_manager: Arc<TestRelationInstanceManager>,
+}
+
+impl TestPluginContext {
+    pub fn new(
+        entity_behaviour_provider: Arc<dyn EntityBehaviourProvider>,
+        relation_behaviour_provider: Arc<dyn RelationBehaviourProvider>,
+    ) -> TestPluginContext {
+        let entity_instance_manager = Arc::new(TestEntityInstanceManager {
+            entity_instances: RwLock::new(HashMap::new()),
+            entity_behaviour_provider,
+        });
+        let relation_instance_manager = Arc::new(TestRelationInstanceManager {
+            entity_instance_manager: entity_instance_manager.clone(),
+            relation_instances: RwLock::new(HashMap::new()),
+            relation_behaviour_provider,
+        });
+        TestPluginContext {
+            entity_instance_manager,
+            relation_instance_manager,
+        }
+    }
+}
+
+impl PluginContext for TestPluginContext {
+    fn get_component_manager(&self) -> Arc<dyn ComponentManager> {
+        unimplemented!()
+    }
+
+    fn get_entity_type_manager(&self) -> Arc<dyn EntityTypeManager> {
+        unimplemented!()
+    }
+
+    fn get_relation_type_manager(&self) -> Arc<dyn RelationTypeManager> {
+        unimplemented!()
+    }
+
+    fn get_entity_instance_manager(&self) -> Arc<dyn EntityInstanceManager> {
+        self.entity_instance_manager.clone()
+    }
+
+    fn get_relation_instance_manager(&self) -> Arc<dyn RelationInstanceManager> {
+        self.relation_instance_manager.clone()
+    }
+
+    fn get_flow_manager(&self) -> Arc<dyn FlowManager> {
+        unimplemented!()
+    }
+}
+
+pub struct TestEntityInstanceManager {
+    entity_instances: RwLock<HashMap<Uuid, Arc<ReactiveEntityInstance>>>,
+
+    entity_behaviour_provider: Arc<dyn EntityBehaviourProvider>,
+}
+
+impl EntityInstanceManager for TestEntityInstanceManager {
+    fn has(&self, id: Uuid) -> bool {
+        self.entity_instances.read().unwrap().contains_key(&id)
+    }
+
+    fn get(&self, id: Uuid) -> Option<Arc<ReactiveEntityInstance>> {
+        self.entity_instances.read().unwrap().get(&id).cloned()
+    }
+
+    fn get_by_label(&self, _label: &str) -> Option<Arc<ReactiveEntityInstance>> {
+        None
+    }
+
+    fn get_all(&self) -> Vec<Arc<ReactiveEntityInstance>> {
+        self.entity_instances.read().unwrap().values().cloned().collect()
+    }
+
+    fn get_by_type(&self, type_name: &str) -> Vec<Arc<ReactiveEntityInstance>> {
+        self.get_all()
+            .into_iter()
+            .filter(|entity_instance| entity_instance.type_name == type_name)
+            .collect()
+    }
+
+    fn get_ids(&self) -> Vec<Uuid> {
+        self.entity_instances.read().unwrap().keys().cloned().collect()
+    }
+
+    fn count(&self) -> usize {
+        self.entity_instances.read().unwrap().len()
+    }
+
+    fn create(&self, entity_instance: EntityInstance) -> Result<Arc<ReactiveEntityInstance>, EntityInstanceCreationError> {
+        // The runtime rejects duplicates, but the plugin checks for existing entity instances itself
+        if let Some(reactive_entity_instance) = self.get(entity_instance.id) {
+            return Ok(reactive_entity_instance);
+        }
+        let reactive_entity_instance = Arc::new(ReactiveEntityInstance::from(entity_instance));
+        self.entity_instances
+            .write()
+            .unwrap()
+            .insert(reactive_entity_instance.id, reactive_entity_instance.clone());
+        self.entity_behaviour_provider.add_behaviours(reactive_entity_instance.clone());
+        Ok(reactive_entity_instance)
+    }
+
+    fn add_component(&self, _id: Uuid, _component: &str) {}
+
+    fn remove_component(&self, _id: Uuid, _component: &str) {}
+
+    fn delete(&self, id: Uuid) {
+        let entity_instance = self.entity_instances.write().unwrap().remove(&id);
+        if let Some(entity_instance) = entity_instance {
+            self.entity_behaviour_provider.remove_behaviours(entity_instance);
+        }
+    }
+}
+
+pub struct TestRelationInstanceManager {
+    entity_instance_manager: Arc<TestEntityInstanceManager>,
+
+    relation_instances: RwLock<HashMap<EdgeKey, Arc<ReactiveRelationInstance>>>,
+
+    relation_behaviour_provider: Arc<dyn RelationBehaviourProvider>,
+}
+
+impl RelationInstanceManager for TestRelationInstanceManager {
+    fn has(&self, edge_key: EdgeKey) -> bool {
+        self.relation_instances.read().unwrap().contains_key(&edge_key)
+    }
+
+    fn get(&self, edge_key: EdgeKey) -> Option<Arc<ReactiveRelationInstance>> {
+        self.relation_instances.read().unwrap().get(&edge_key).cloned()
+    }
+
+    fn get_by_outbound_entity(&self, outbound_entity_id: Uuid) -> Vec<Arc<ReactiveRelationInstance>> {
+        self.get_all()
+            .into_iter()
+            .filter(|relation_instance| relation_instance.outbound.id == outbound_entity_id)
+            .collect()
+    }
+
+    fn get_by_inbound_entity(&self, inbound_entity_id: Uuid) -> Vec<Arc<ReactiveRelationInstance>> {
+        self.get_all()
+            .into_iter()
+            .filter(|relation_instance| relation_instance.inbound.id == inbound_entity_id)
+            .collect()
+    }
+
+    fn get_all(&self) -> Vec<Arc<ReactiveRelationInstance>> {
+        self.relation_instances.read().unwrap().values().cloned().collect()
+    }
+
+    fn get_by_type(&self, type_name: &str) -> Vec<Arc<ReactiveRelationInstance>> {
+        self.get_all()
+            .into_iter()
+            .filter(|relation_instance| relation_instance.type_name == type_name)
+            .collect()
+    }
+
+    fn count(&self) -> usize {
+        self.relation_instances.read().unwrap().len()
+    }
+
+    fn create(&self, relation_instance: RelationInstance) -> Result<Arc<ReactiveRelationInstance>, RelationInstanceCreationError> {
+        let outbound = self
+            .entity_instance_manager
+            .get(relation_instance.outbound_id)
+            .expect("The outbound entity instance of the relation doesn't exist");
+        let inbound = self
+            .entity_instance_manager
+            .get(relation_instance.inbound_id)
+            .expect("The inbound entity instance of the relation doesn't exist");
+        let reactive_relation_instance = Arc::new(ReactiveRelationInstance::from_instance(outbound, inbound, relation_instance));
+        let edge_key = reactive_relation_instance.get_key().expect("The relation instance has no edge key");
+        if let Some(reactive_relation_instance) = self.get(edge_key.clone()) {
+            return Ok(reactive_relation_instance);
+        }
+        self.relation_instances.write().unwrap().insert(edge_key, reactive_relation_instance.clone());
+        self.relation_behaviour_provider.add_behaviours(reactive_relation_instance.clone());
+        Ok(reactive_relation_instance)
+    }
+
+    fn add_component(&self, _edge_key: EdgeKey, _component: &str) {}
+
+    fn remove_component(&self, _edge_key: EdgeKey, _component: &str) {}
+
+    fn delete(&self, edge_key: EdgeKey) -> bool {
+        let relation_instance = self.relation_instances.write().unwrap().remove(&edge_key);
+        match relation_instance {
+            Some(relation_instance) => {
+                self.relation_behaviour_provider.remove_behaviours(relation_instance);
+                true
+            }
+            None => false,
+        }
+    }
+}
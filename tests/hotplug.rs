@@ -0,0 +1,225 @@
+//! Plugs in and unplugs simulated input devices by creating and removing device nodes in a
+//! temporary directory which stands in for /dev/input.
+
+mod common;
+
+use std::path::Path;
+
+use evdev::{AbsoluteAxisType, Key, LedType, RelativeAxisType, SwitchType};
+use inexor_rgf_core_model::PropertyInstanceGetter;
+
+use common::{autodetect_config, wait_until, TestPlugin};
+use inexor_rgf_plugin_input_device::api::{
+    INPUT_DEVICE_ABSOLUTE_AXIS, INPUT_DEVICE_KEY, INPUT_DEVICE_LED, INPUT_DEVICE_RELATIVE_AXIS, INPUT_DEVICE_SWITCH, PHYSICAL_DEVICE,
+};
+use inexor_rgf_plugin_input_device::backend::{AbsoluteAxisInfo, SimulatedDevice};
+use inexor_rgf_plugin_input_device::behaviour::entity::input_device::INPUT_DEVICE;
+use inexor_rgf_plugin_input_device::behaviour::entity::{InputDeviceKeyProperties, InputDeviceProperties, PhysicalDeviceProperties};
+use inexor_rgf_plugin_input_device::behaviour::relation::absolute_axis_event::ABSOLUTE_AXIS_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::key_event::KEY_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::led_event::LED_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::relative_axis_event::RELATIVE_AXIS_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::switch_event::SWITCH_EVENT;
+use inexor_rgf_plugin_input_device::config::InputDeviceConfig;
+
+fn device(device_node: &Path, name: &str) -> SimulatedDevice {
+    SimulatedDevice::new(device_node, name, &format!("usb-0000:00:14.0-{}/input0", name))
+        .with_keys(&[Key::KEY_A, Key::KEY_B])
+        .with_leds(&[LedType::LED_NUML])
+        .with_relative_axes(&[RelativeAxisType::REL_X])
+        .with_absolute_axis_info(
+            AbsoluteAxisType::ABS_X,
+            AbsoluteAxisInfo {
+                minimum: 0,
+                maximum: 255,
+                ..AbsoluteAxisInfo::default()
+            },
+        )
+        .with_switches(&[SwitchType::SW_LID])
+}
+
+fn device_node_of(test: &TestPlugin, name: &str) -> Option<String> {
+    test.entity(INPUT_DEVICE, InputDeviceProperties::NAME, name)
+        .and_then(|input_device| input_device.as_string(InputDeviceProperties::DEVICE_NODE))
+}
+
+/// A device named Twin Device which is plugged into the given USB port.
+fn twin_device(device_node: &Path, port: u8) -> SimulatedDevice {
+    SimulatedDevice::new(device_node, "Twin Device", &format!("usb-0000:00:14.0-{}/input0", port))
+        .with_by_path(format!("/dev/input/by-path/pci-0000:00:14.0-usb-0:{}:1.0-event-kbd", port))
+        .with_keys(&[Key::KEY_A, Key::KEY_B])
+}
+
+fn physical_device_labels(test: &TestPlugin) -> Vec<String> {
+    let mut labels: Vec<String> = test
+        .entities(PHYSICAL_DEVICE)
+        .iter()
+        .filter_map(|physical_device| physical_device.as_string(PhysicalDeviceProperties::LABEL))
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn label_of(test: &TestPlugin, device_node: &Path) -> Option<String> {
+    test.entity(INPUT_DEVICE, InputDeviceProperties::DEVICE_NODE, &device_node.display().to_string())
+        .and_then(|input_device| input_device.as_string(InputDeviceProperties::LABEL))
+}
+
+#[test]
+fn hotplugged_input_devices_are_added_and_removed() {
+    let input_device_path = tempfile::tempdir().unwrap();
+    let test = TestPlugin::new();
+    test.input_device_manager.apply_config(autodetect_config(input_device_path.path()));
+    test.input_device_hotplug_manager.start();
+    assert!(test.entities(INPUT_DEVICE).is_empty());
+
+    // Plug in
+    let device_node = input_device_path.path().join("event0");
+    test.backend.add_device(device(&device_node, "Hotplug Device"));
+    std::fs::write(&device_node, "").unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 1));
+    assert_eq!(device_node_of(&test, "Hotplug Device"), Some(device_node.display().to_string()));
+    // The entities of the input device and of any-device
+    assert_eq!(test.entities(INPUT_DEVICE_KEY).len(), 4);
+    assert_eq!(test.entities(INPUT_DEVICE_LED).len(), 2);
+    assert_eq!(test.entities(INPUT_DEVICE_RELATIVE_AXIS).len(), 2);
+    assert_eq!(test.entities(INPUT_DEVICE_ABSOLUTE_AXIS).len(), 2);
+    assert_eq!(test.entities(INPUT_DEVICE_SWITCH).len(), 2);
+    assert_eq!(test.relations(KEY_EVENT).len(), 4);
+    assert_eq!(test.relations(LED_EVENT).len(), 2);
+    assert_eq!(test.relations(RELATIVE_AXIS_EVENT).len(), 2);
+    assert_eq!(test.relations(ABSOLUTE_AXIS_EVENT).len(), 2);
+    assert_eq!(test.relations(SWITCH_EVENT).len(), 2);
+
+    // Unplug
+    test.backend.remove_device(&device_node);
+    std::fs::remove_file(&device_node).unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).is_empty()));
+    for type_name in [
+        INPUT_DEVICE_KEY,
+        INPUT_DEVICE_LED,
+        INPUT_DEVICE_RELATIVE_AXIS,
+        INPUT_DEVICE_ABSOLUTE_AXIS,
+        INPUT_DEVICE_SWITCH,
+    ] {
+        assert!(test.entities(type_name).is_empty(), "{} has not been removed", type_name);
+    }
+    for type_name in [KEY_EVENT, LED_EVENT, RELATIVE_AXIS_EVENT, ABSOLUTE_AXIS_EVENT, SWITCH_EVENT] {
+        assert!(test.relations(type_name).is_empty(), "{} has not been removed", type_name);
+    }
+}
+
+#[test]
+fn input_devices_which_are_present_at_startup_are_not_added_twice() {
+    let input_device_path = tempfile::tempdir().unwrap();
+    let device_node = input_device_path.path().join("event0");
+    let test = TestPlugin::new();
+    test.backend.add_device(device(&device_node, "Present Device"));
+    std::fs::write(&device_node, "").unwrap();
+    test.input_device_manager.apply_config(autodetect_config(input_device_path.path()));
+    test.input_device_hotplug_manager.start();
+    assert_eq!(test.entities(INPUT_DEVICE).len(), 1);
+
+    // Another device appears while the first one stays
+    let other_device_node = input_device_path.path().join("event1");
+    test.backend.add_device(device(&other_device_node, "Other Device"));
+    std::fs::write(&other_device_node, "").unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 2));
+    assert_eq!(device_node_of(&test, "Present Device"), Some(device_node.display().to_string()));
+    assert_eq!(device_node_of(&test, "Other Device"), Some(other_device_node.display().to_string()));
+
+    test.backend.remove_device(&other_device_node);
+    std::fs::remove_file(&other_device_node).unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 1));
+    assert!(device_node_of(&test, "Present Device").is_some());
+    assert!(device_node_of(&test, "Other Device").is_none());
+}
+
+#[test]
+fn configured_input_devices_are_added_when_their_symlink_appears() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    // The configured symlink is resolved, so the directory must not be reached through a symlink itself
+    let input_device_path = temp_dir.path().canonicalize().unwrap();
+    let by_id = input_device_path.join("by-id");
+    std::fs::create_dir(&by_id).unwrap();
+    let symlink = by_id.join("usb-Configured_Device-event-kbd");
+    let mut config = autodetect_config(&input_device_path);
+    config.autodetect = false;
+    config
+        .input_device
+        .push(toml::from_str::<InputDeviceConfig>(&format!("name = \"configured\"\npath = \"{}\"\n", symlink.display())).unwrap());
+    let test = TestPlugin::new();
+    test.input_device_manager.apply_config(config);
+    test.input_device_hotplug_manager.start();
+
+    // udev creates the device node first and the symlink afterwards
+    let device_node = input_device_path.join("event0");
+    test.backend.add_device(device(&device_node, "Configured Device"));
+    std::fs::write(&device_node, "").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    assert!(test.entities(INPUT_DEVICE).is_empty());
+    std::os::unix::fs::symlink("../event0", &symlink).unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 1));
+    assert_eq!(device_node_of(&test, "Configured Device"), Some(device_node.display().to_string()));
+}
+
+#[test]
+fn input_devices_present_at_startup_are_labeled_in_the_order_of_their_identities() {
+    let input_device_path = tempfile::tempdir().unwrap();
+    let test = TestPlugin::new();
+    // The device in the second port has the lower device node
+    let second_port = input_device_path.path().join("event0");
+    let first_port = input_device_path.path().join("event1");
+    for (device_node, port) in [(&second_port, 2), (&first_port, 1)] {
+        test.backend.add_device(twin_device(device_node, port));
+        std::fs::write(device_node, "").unwrap();
+    }
+    test.input_device_manager.apply_config(autodetect_config(input_device_path.path()));
+    assert_eq!(test.entities(INPUT_DEVICE).len(), 2);
+    assert_eq!(label_of(&test, &first_port), Some("/org/inexor/input/twin_device".to_string()));
+    assert_eq!(label_of(&test, &second_port), Some("/org/inexor/input/twin_device_2".to_string()));
+}
+
+#[test]
+fn labels_are_kept_when_input_devices_with_the_same_name_are_plugged_in() {
+    let input_device_path = tempfile::tempdir().unwrap();
+    let test = TestPlugin::new();
+    test.input_device_manager.apply_config(autodetect_config(input_device_path.path()));
+    test.input_device_hotplug_manager.start();
+
+    let second_port = input_device_path.path().join("event0");
+    test.backend.add_device(twin_device(&second_port, 2));
+    std::fs::write(&second_port, "").unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 1));
+    assert_eq!(label_of(&test, &second_port), Some("/org/inexor/input/twin_device".to_string()));
+
+    // The device which is plugged in later gets the next index, even though its identity is lower
+    let first_port = input_device_path.path().join("event1");
+    test.backend.add_device(twin_device(&first_port, 1));
+    std::fs::write(&first_port, "").unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 2));
+    assert_eq!(label_of(&test, &second_port), Some("/org/inexor/input/twin_device".to_string()));
+    assert_eq!(label_of(&test, &first_port), Some("/org/inexor/input/twin_device_2".to_string()));
+    assert!(test
+        .entity(INPUT_DEVICE_KEY, InputDeviceKeyProperties::LABEL, "/org/inexor/input/twin_device_2/key/key_a")
+        .is_some());
+    assert_eq!(
+        physical_device_labels(&test),
+        vec![
+            "/org/inexor/input/physical/twin_device".to_string(),
+            "/org/inexor/input/physical/twin_device_2".to_string()
+        ]
+    );
+
+    // The remaining device keeps its label and the free label is assigned again
+    test.backend.remove_device(&second_port);
+    std::fs::remove_file(&second_port).unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 1));
+    assert_eq!(label_of(&test, &first_port), Some("/org/inexor/input/twin_device_2".to_string()));
+    assert_eq!(physical_device_labels(&test), vec!["/org/inexor/input/physical/twin_device_2".to_string()]);
+    test.backend.add_device(twin_device(&second_port, 2));
+    std::fs::write(&second_port, "").unwrap();
+    assert!(wait_until(|| test.entities(INPUT_DEVICE).len() == 2));
+    assert_eq!(label_of(&test, &second_port), Some("/org/inexor/input/twin_device".to_string()));
+    assert_eq!(label_of(&test, &first_port), Some("/org/inexor/input/twin_device_2".to_string()));
+}
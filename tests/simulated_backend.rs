@@ -0,0 +1,500 @@
+//! Scripts input devices and their events with the simulated backend and checks the state of the
+//! keys, LEDs, axes and switches.
+
+mod common;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use evdev::{AbsoluteAxisType, EventType, InputEvent, Key, LedType, RelativeAxisType, Synchronization};
+use inexor_rgf_core_model::{PropertyInstanceGetter, PropertyInstanceSetter, ReactiveEntityInstance};
+use inexor_rgf_core_plugins::RelationInstanceManager;
+use serde_json::json;
+
+use common::{autodetect_config, wait_until, TestPlugin};
+use inexor_rgf_plugin_input_device::api::{INPUT_DEVICE_KEY, INPUT_DEVICE_LED, PHYSICAL_DEVICE, VIRTUAL_DEVICE_NAME_SUFFIX};
+use inexor_rgf_plugin_input_device::backend::{AbsoluteAxisInfo, SimulatedDevice};
+use inexor_rgf_plugin_input_device::behaviour::entity::input_device::INPUT_DEVICE;
+use inexor_rgf_plugin_input_device::behaviour::entity::{
+    InputDeviceAbsoluteAxisProperties, InputDeviceKeyProperties, InputDeviceLedProperties, InputDeviceProperties, InputDeviceRelativeAxisProperties,
+    PhysicalDeviceProperties,
+};
+use inexor_rgf_plugin_input_device::behaviour::event_payload::{
+    INPUT_EVENT_KIND, INPUT_EVENT_KIND_LED_EVENT, LED_EVENT_LED_TYPE, SEND_EVENT_CODE, SEND_EVENT_EVENT_TYPE, SEND_EVENT_VALUE,
+};
+use inexor_rgf_plugin_input_device::behaviour::relation::absolute_axis_event::ABSOLUTE_AXIS_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::key_event::KEY_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::led_event::LED_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::relative_axis_event::RELATIVE_AXIS_EVENT;
+use inexor_rgf_plugin_input_device::behaviour::relation::send_key_event::SEND_KEY_EVENT;
+use inexor_rgf_plugin_input_device::config::{EntityCreation, InputDeviceConfig};
+
+const DEVICE_NODE: &str = "/dev/input/event7";
+
+const DEVICE_NAME: &str = "Simulated Keyboard";
+
+fn keyboard() -> SimulatedDevice {
+    SimulatedDevice::new(DEVICE_NODE, DEVICE_NAME, "usb-0000:00:14.0-3/input0")
+        .with_keys(&[Key::KEY_A, Key::KEY_B])
+        .with_leds(&[LedType::LED_NUML, LedType::LED_CAPSL])
+        .with_relative_axes(&[RelativeAxisType::REL_X])
+        .with_absolute_axis_info(
+            AbsoluteAxisType::ABS_X,
+            AbsoluteAxisInfo {
+                value: 0,
+                minimum: 0,
+                maximum: 200,
+                ..AbsoluteAxisInfo::default()
+            },
+        )
+}
+
+/// Starts the plugin with the given simulated device and returns the entity of the input device.
+fn start(device: &SimulatedDevice) -> (TestPlugin, Arc<ReactiveEntityInstance>) {
+    let test = TestPlugin::new();
+    test.backend.add_device(device.clone());
+    test.input_device_manager.apply_config(autodetect_config(Path::new("/dev/input")));
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    assert!(wait_until(|| input_device.as_bool(InputDeviceProperties::CONNECTED) == Some(true)));
+    (test, input_device)
+}
+
+/// Starts the plugin with the given simulated device whose keys, LEDs, axes and switches are created on demand.
+fn start_lazy(device: &SimulatedDevice) -> (TestPlugin, Arc<ReactiveEntityInstance>) {
+    let test = TestPlugin::new();
+    test.backend.add_device(device.clone());
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.entity_creation = EntityCreation::Lazy;
+    test.input_device_manager.apply_config(config);
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    assert!(wait_until(|| input_device.as_bool(InputDeviceProperties::CONNECTED) == Some(true)));
+    (test, input_device)
+}
+
+fn emit_frame(device: &SimulatedDevice, events: &[(EventType, u16, i32)]) {
+    for (event_type, code, value) in events {
+        device.emit(*event_type, *code, *value);
+    }
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0);
+}
+
+/// Returns the key of the input device. The key of any-device has the same name, but can't send key events.
+fn key(test: &TestPlugin, input_device: &ReactiveEntityInstance, key: Key) -> Arc<ReactiveEntityInstance> {
+    test.related(input_device.id, KEY_EVENT)
+        .into_iter()
+        .find(|input_device_key| {
+            input_device_key.as_string(InputDeviceKeyProperties::KEY) == Some(format!("{:?}", key))
+                && !test.related(input_device_key.id, SEND_KEY_EVENT).is_empty()
+        })
+        .expect("The key has not been created")
+}
+
+fn led(test: &TestPlugin, input_device: &ReactiveEntityInstance, led_type: LedType) -> Arc<ReactiveEntityInstance> {
+    test.related_entity(input_device.id, LED_EVENT, InputDeviceLedProperties::LED, &format!("{:?}", led_type))
+        .expect("The LED has not been created")
+}
+
+fn absolute_axis(test: &TestPlugin, input_device: &ReactiveEntityInstance, absolute_axis_type: AbsoluteAxisType) -> Arc<ReactiveEntityInstance> {
+    test.related_entity(
+        input_device.id,
+        ABSOLUTE_AXIS_EVENT,
+        InputDeviceAbsoluteAxisProperties::ABSOLUTE_AXIS,
+        &format!("{:?}", absolute_axis_type),
+    )
+    .expect("The absolute axis has not been created")
+}
+
+fn relative_axis(test: &TestPlugin, input_device: &ReactiveEntityInstance, relative_axis_type: RelativeAxisType) -> Arc<ReactiveEntityInstance> {
+    test.related_entity(
+        input_device.id,
+        RELATIVE_AXIS_EVENT,
+        InputDeviceRelativeAxisProperties::RELATIVE_AXIS,
+        &format!("{:?}", relative_axis_type),
+    )
+    .expect("The relative axis has not been created")
+}
+
+#[test]
+fn keys_follow_the_key_events() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    let key_a = key(&test, &input_device, Key::KEY_A);
+    assert_eq!(key_a.as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(false));
+
+    emit_frame(&device, &[(EventType::KEY, Key::KEY_A.code(), 1)]);
+    assert!(wait_until(|| key_a.as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(true)));
+
+    emit_frame(&device, &[(EventType::KEY, Key::KEY_A.code(), 0)]);
+    assert!(wait_until(|| key_a.as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(false)));
+    assert_eq!(key(&test, &input_device, Key::KEY_B).as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(false));
+}
+
+#[test]
+fn leds_follow_the_led_events() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    let numlock = led(&test, &input_device, LedType::LED_NUML);
+    assert_eq!(numlock.as_bool(InputDeviceLedProperties::STATE), Some(false));
+
+    emit_frame(&device, &[(EventType::LED, LedType::LED_NUML.0, 1)]);
+    assert!(wait_until(|| numlock.as_bool(InputDeviceLedProperties::STATE) == Some(true)));
+    assert_eq!(led(&test, &input_device, LedType::LED_CAPSL).as_bool(InputDeviceLedProperties::STATE), Some(false));
+}
+
+#[test]
+fn absolute_axes_follow_the_absolute_axis_events() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    let abs_x = absolute_axis(&test, &input_device, AbsoluteAxisType::ABS_X);
+
+    emit_frame(&device, &[(EventType::ABSOLUTE, AbsoluteAxisType::ABS_X.0, 150)]);
+    assert!(wait_until(|| abs_x.as_i64(InputDeviceAbsoluteAxisProperties::STATE) == Some(150)));
+    assert_eq!(abs_x.as_f64(InputDeviceAbsoluteAxisProperties::NORMALIZED), Some(0.75));
+}
+
+#[test]
+fn the_initial_state_is_read_from_the_device() {
+    let device = keyboard();
+    device.set_state(EventType::KEY, Key::KEY_B.code(), 1);
+    device.set_state(EventType::LED, LedType::LED_CAPSL.0, 1);
+    device.set_state(EventType::ABSOLUTE, AbsoluteAxisType::ABS_X.0, 50);
+    let (test, input_device) = start(&device);
+    assert_eq!(key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(false));
+    assert_eq!(key(&test, &input_device, Key::KEY_B).as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(true));
+    assert_eq!(led(&test, &input_device, LedType::LED_CAPSL).as_bool(InputDeviceLedProperties::STATE), Some(true));
+    assert_eq!(
+        absolute_axis(&test, &input_device, AbsoluteAxisType::ABS_X).as_i64(InputDeviceAbsoluteAxisProperties::STATE),
+        Some(50)
+    );
+}
+
+#[test]
+fn sent_events_are_written_to_the_device_followed_by_a_syn_report() {
+    let device = keyboard();
+    let (_test, input_device) = start(&device);
+    input_device.set(
+        InputDeviceProperties::SEND_EVENT.to_string(),
+        json!([
+            { SEND_EVENT_EVENT_TYPE: EventType::LED.0, SEND_EVENT_CODE: LedType::LED_NUML.0, SEND_EVENT_VALUE: true },
+            { SEND_EVENT_EVENT_TYPE: EventType::LED.0, SEND_EVENT_CODE: LedType::LED_CAPSL.0, SEND_EVENT_VALUE: 1 }
+        ]),
+    );
+    let sent_events: Vec<(EventType, u16, i32)> = device
+        .sent_events()
+        .iter()
+        .map(|event: &InputEvent| (event.event_type(), event.code(), event.value()))
+        .collect();
+    assert_eq!(
+        sent_events,
+        vec![
+            (EventType::LED, LedType::LED_NUML.0, 1),
+            (EventType::LED, LedType::LED_CAPSL.0, 1),
+            (EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0)
+        ]
+    );
+    assert_eq!(input_device.as_string(InputDeviceProperties::SEND_ERROR), Some(String::new()));
+}
+
+#[test]
+fn invalid_send_events_are_published_as_send_error() {
+    let device = keyboard();
+    let (_test, input_device) = start(&device);
+    input_device.set(InputDeviceProperties::SEND_EVENT.to_string(), json!({ SEND_EVENT_EVENT_TYPE: EventType::LED.0 }));
+    assert!(device.sent_events().is_empty());
+    assert!(!input_device.as_string(InputDeviceProperties::SEND_ERROR).unwrap_or_default().is_empty());
+}
+
+#[test]
+fn frame_mode_publishes_the_events_between_two_syn_reports_at_once() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    input_device.set(InputDeviceProperties::FRAME_MODE.to_string(), json!(true));
+    let rel_x = relative_axis(&test, &input_device, RelativeAxisType::REL_X);
+
+    device.emit(EventType::RELATIVE, RelativeAxisType::REL_X.0, 3);
+    device.emit(EventType::KEY, Key::KEY_A.code(), 1);
+    // Nothing is published until the frame is complete
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(rel_x.as_i64(InputDeviceRelativeAxisProperties::STATE), Some(0));
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0);
+    assert!(wait_until(
+        || input_device.get(InputDeviceProperties::FRAME).and_then(|frame| frame.as_array().map(Vec::len)) == Some(2)
+    ));
+    assert_eq!(rel_x.as_i64(InputDeviceRelativeAxisProperties::STATE), Some(3));
+    assert_eq!(key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(true));
+}
+
+#[test]
+fn unplugged_input_devices_are_reconnected() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.reconnect = true;
+    test.input_device_manager.apply_config(config);
+
+    test.backend.remove_device(Path::new(DEVICE_NODE));
+    assert!(wait_until(|| input_device.as_bool(InputDeviceProperties::CONNECTED) == Some(false)));
+
+    // The key has been pressed while the input device was unplugged
+    device.set_state(EventType::KEY, Key::KEY_A.code(), 1);
+    test.backend.add_device(device.clone());
+    assert!(wait_until(|| input_device.as_bool(InputDeviceProperties::CONNECTED) == Some(true)));
+    assert!(wait_until(|| key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(true)));
+
+    // The events are read from the reconnected input device
+    emit_frame(&device, &[(EventType::KEY, Key::KEY_A.code(), 0)]);
+    assert!(wait_until(|| key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(false)));
+}
+
+#[test]
+fn unplugged_input_devices_are_kept_until_they_are_not_configured_anymore() {
+    let device = keyboard();
+    let test = TestPlugin::new();
+    test.backend.add_device(device.clone());
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.autodetect = false;
+    config.reconnect = true;
+    config
+        .input_device
+        .push(toml::from_str::<InputDeviceConfig>(&format!("name = \"keyboard\"\npath = \"{}\"\n", DEVICE_NODE)).unwrap());
+    test.input_device_manager.apply_config(config.clone());
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    test.backend.remove_device(Path::new(DEVICE_NODE));
+    assert!(wait_until(|| input_device.as_bool(InputDeviceProperties::CONNECTED) == Some(false)));
+
+    // Reloading the configuration keeps the unplugged input device for reconnecting
+    test.input_device_manager.apply_config(config.clone());
+    assert!(test.entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME).is_some());
+
+    config.input_device.clear();
+    test.input_device_manager.apply_config(config);
+    assert!(test.entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME).is_none());
+}
+
+#[test]
+fn events_are_discarded_after_syn_dropped_until_the_next_syn_report() {
+    let device = keyboard();
+    let (test, input_device) = start(&device);
+    let key_a = key(&test, &input_device, Key::KEY_A);
+    let numlock = led(&test, &input_device, LedType::LED_NUML);
+    let rel_x = relative_axis(&test, &input_device, RelativeAxisType::REL_X);
+
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_DROPPED.0, 0);
+    device.emit(EventType::KEY, Key::KEY_A.code(), 1);
+    device.emit(EventType::RELATIVE, RelativeAxisType::REL_X.0, 5);
+    // The LED has been lit while the events were dropped
+    device.set_state(EventType::LED, LedType::LED_NUML.0, 1);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(key_a.as_bool(InputDeviceKeyProperties::KEY_DOWN), Some(false));
+    assert_eq!(rel_x.as_i64(InputDeviceRelativeAxisProperties::STATE), Some(0));
+
+    // The state is queried after the next SYN_REPORT
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0);
+    assert!(wait_until(|| key_a.as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(true)));
+    assert!(wait_until(|| numlock.as_bool(InputDeviceLedProperties::STATE) == Some(true)));
+    // Relative axes have no state
+    assert_eq!(rel_x.as_i64(InputDeviceRelativeAxisProperties::STATE), Some(0));
+
+    // The following frames are published again
+    emit_frame(&device, &[(EventType::RELATIVE, RelativeAxisType::REL_X.0, 7)]);
+    assert!(wait_until(|| rel_x.as_i64(InputDeviceRelativeAxisProperties::STATE) == Some(7)));
+}
+
+#[test]
+fn lazy_entities_are_created_by_their_first_event() {
+    let device = keyboard();
+    let (test, input_device) = start_lazy(&device);
+    assert!(test.entities(INPUT_DEVICE_KEY).is_empty());
+    assert!(test.entities(INPUT_DEVICE_LED).is_empty());
+
+    emit_frame(&device, &[(EventType::KEY, Key::KEY_A.code(), 1)]);
+    // The key of the input device and the key of any-device
+    assert!(wait_until(|| test.entities(INPUT_DEVICE_KEY).len() == 2));
+    // The first event is delivered to the new key
+    assert!(wait_until(|| key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(true)));
+
+    // Resynchronizing the state doesn't create the keys and LEDs which haven't been created yet
+    device.set_state(EventType::KEY, Key::KEY_B.code(), 1);
+    device.set_state(EventType::LED, LedType::LED_NUML.0, 1);
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_DROPPED.0, 0);
+    device.emit(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0);
+    emit_frame(&device, &[(EventType::KEY, Key::KEY_A.code(), 0)]);
+    assert!(wait_until(|| key(&test, &input_device, Key::KEY_A).as_bool(InputDeviceKeyProperties::KEY_DOWN) == Some(false)));
+    assert_eq!(test.entities(INPUT_DEVICE_KEY).len(), 2);
+    assert!(test.entities(INPUT_DEVICE_LED).is_empty());
+}
+
+#[test]
+fn lazy_entities_are_created_when_they_are_queried() {
+    let device = keyboard();
+    device.set_state(EventType::LED, LedType::LED_CAPSL.0, 1);
+    let (test, input_device) = start_lazy(&device);
+    assert!(test.entities(INPUT_DEVICE_LED).is_empty());
+
+    input_device.set(
+        InputDeviceProperties::QUERY.to_string(),
+        json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
+            LED_EVENT_LED_TYPE: LedType::LED_CAPSL.0
+        }),
+    );
+    assert_eq!(test.entities(INPUT_DEVICE_LED).len(), 2);
+    // The state is read from the input device
+    assert_eq!(led(&test, &input_device, LedType::LED_CAPSL).as_bool(InputDeviceLedProperties::STATE), Some(true));
+
+    // Querying an existing LED doesn't create it again
+    input_device.set(
+        InputDeviceProperties::QUERY.to_string(),
+        json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
+            LED_EVENT_LED_TYPE: LedType::LED_CAPSL.0
+        }),
+    );
+    assert_eq!(test.entities(INPUT_DEVICE_LED).len(), 2);
+    assert!(test.entities(INPUT_DEVICE_KEY).is_empty());
+}
+
+#[test]
+fn queries_of_a_physical_device_are_forwarded_to_the_interfaces_which_support_them() {
+    // A gaming keyboard provides the keys and the LEDs on different interfaces
+    let keys = SimulatedDevice::new("/dev/input/event8", "Gaming Keyboard", "usb-0000:00:14.0-4/input0").with_keys(&[Key::KEY_B, Key::KEY_A]);
+    let leds = SimulatedDevice::new("/dev/input/event9", "Gaming Keyboard LEDs", "usb-0000:00:14.0-4/input1").with_leds(&[LedType::LED_CAPSL]);
+    leds.set_state(EventType::LED, LedType::LED_CAPSL.0, 1);
+    let test = TestPlugin::new();
+    test.backend.add_device(keys);
+    test.backend.add_device(leds);
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.entity_creation = EntityCreation::Lazy;
+    test.input_device_manager.apply_config(config);
+    let led_interface = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, "Gaming Keyboard LEDs")
+        .expect("The input device has not been created");
+    assert!(wait_until(|| led_interface.as_bool(InputDeviceProperties::CONNECTED) == Some(true)));
+
+    let physical_devices = test.entities(PHYSICAL_DEVICE);
+    assert_eq!(physical_devices.len(), 1);
+    let physical_device = physical_devices[0].clone();
+    assert_eq!(physical_device.get(PhysicalDeviceProperties::SUPPORTED_KEYS), Some(json!(["KEY_A", "KEY_B"])));
+    assert_eq!(physical_device.get(PhysicalDeviceProperties::SUPPORTED_LEDS), Some(json!(["LED_CAPSL"])));
+
+    physical_device.set(
+        PhysicalDeviceProperties::QUERY.to_string(),
+        json!({
+            INPUT_EVENT_KIND: INPUT_EVENT_KIND_LED_EVENT,
+            LED_EVENT_LED_TYPE: LedType::LED_CAPSL.0
+        }),
+    );
+    // Only the interface which supports the LED creates it
+    assert_eq!(test.entities(INPUT_DEVICE_LED).len(), 2);
+    assert_eq!(led(&test, &led_interface, LedType::LED_CAPSL).as_bool(InputDeviceLedProperties::STATE), Some(true));
+    assert!(test.entities(INPUT_DEVICE_KEY).is_empty());
+}
+
+#[test]
+fn keys_held_down_by_the_virtual_device_are_released_when_the_input_device_is_removed() {
+    let device = keyboard();
+    let test = TestPlugin::new();
+    test.backend.add_device(device.clone());
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.autodetect_options.virtual_device = true;
+    test.input_device_manager.apply_config(config);
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    let virtual_device = test
+        .backend
+        .get_virtual_device(&format!("{}{}", DEVICE_NAME, VIRTUAL_DEVICE_NAME_SUFFIX))
+        .expect("The virtual device has not been created");
+    let key_events = || -> Vec<(u16, i32)> {
+        virtual_device
+            .emitted_events()
+            .iter()
+            .filter(|event| event.event_type() == EventType::KEY)
+            .map(|event| (event.code(), event.value()))
+            .collect()
+    };
+
+    key(&test, &input_device, Key::KEY_A).set(InputDeviceKeyProperties::SET_KEY_DOWN.to_string(), json!(true));
+    key(&test, &input_device, Key::KEY_B).set(InputDeviceKeyProperties::SET_KEY_DOWN.to_string(), json!(true));
+    key(&test, &input_device, Key::KEY_B).set(InputDeviceKeyProperties::SET_KEY_DOWN.to_string(), json!(false));
+    assert_eq!(key_events(), vec![(Key::KEY_A.code(), 1), (Key::KEY_B.code(), 1), (Key::KEY_B.code(), 0)]);
+
+    // Only the key which is still held down is released
+    test.input_device_manager.remove_input_devices();
+    assert_eq!(key_events().last(), Some(&(Key::KEY_A.code(), 0)));
+    assert_eq!(key_events().len(), 4);
+    // Keys are never sent to the device node while there is a virtual device
+    assert!(device.sent_events().is_empty());
+}
+
+#[test]
+fn keys_held_down_by_a_relation_are_released_when_the_relation_is_removed() {
+    let device = keyboard();
+    let test = TestPlugin::new();
+    test.backend.add_device(device.clone());
+    let mut config = autodetect_config(Path::new("/dev/input"));
+    config.autodetect_options.virtual_device = true;
+    test.input_device_manager.apply_config(config);
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    let virtual_device = test
+        .backend
+        .get_virtual_device(&format!("{}{}", DEVICE_NAME, VIRTUAL_DEVICE_NAME_SUFFIX))
+        .expect("The virtual device has not been created");
+    let key_events = || -> Vec<(u16, i32)> {
+        virtual_device
+            .emitted_events()
+            .iter()
+            .filter(|event| event.event_type() == EventType::KEY)
+            .map(|event| (event.code(), event.value()))
+            .collect()
+    };
+
+    let key_a = key(&test, &input_device, Key::KEY_A);
+    key_a.set(InputDeviceKeyProperties::SET_KEY_DOWN.to_string(), json!(true));
+    assert_eq!(key_events(), vec![(Key::KEY_A.code(), 1)]);
+
+    // A flow removes the relation while the key is held down
+    let send_key_event = test
+        .relations(SEND_KEY_EVENT)
+        .into_iter()
+        .find(|send_key_event| send_key_event.outbound.id == key_a.id)
+        .expect("The relation has not been created");
+    assert!(test.context.relation_instance_manager.delete(send_key_event.get_key().unwrap()));
+    assert_eq!(key_events(), vec![(Key::KEY_A.code(), 1), (Key::KEY_A.code(), 0)]);
+    // The input device doesn't release the key a second time
+    test.input_device_manager.remove_input_devices();
+    assert_eq!(key_events().len(), 2);
+    assert!(device.sent_events().is_empty());
+}
+
+#[test]
+fn handlers_are_unregistered_when_their_relations_are_removed() {
+    let test = TestPlugin::new();
+    test.backend.add_device(keyboard());
+    test.input_device_manager.apply_config(autodetect_config(Path::new("/dev/input")));
+    let input_device = test
+        .entity(INPUT_DEVICE, InputDeviceProperties::NAME, DEVICE_NAME)
+        .expect("The input device has not been created");
+    let number_of_handlers = test.input_device_event_dispatcher.count_handlers(input_device.id);
+    assert!(number_of_handlers > 0);
+
+    let key_a = key(&test, &input_device, Key::KEY_A);
+    let key_event = test
+        .relations(KEY_EVENT)
+        .into_iter()
+        .find(|key_event| key_event.outbound.id == input_device.id && key_event.inbound.id == key_a.id)
+        .expect("The relation has not been created");
+    assert!(test.context.relation_instance_manager.delete(key_event.get_key().unwrap()));
+    assert_eq!(test.input_device_event_dispatcher.count_handlers(input_device.id), number_of_handlers - 1);
+
+    test.input_device_manager.remove_input_devices();
+    assert_eq!(test.input_device_event_dispatcher.count_handlers(input_device.id), 0);
+}